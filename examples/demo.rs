@@ -0,0 +1,602 @@
+//! Demonstrações das ferramentas de cada módulo.
+//!
+//! ```text
+//! cargo run --example demo            # lista as demonstrações
+//! cargo run --example demo colisoes
+//! ```
+#![allow(
+  clippy::print_literal,
+  clippy::print_with_newline
+)]
+
+use jeotry::prelude::*;
+use jeotry::vector_data::Â90;
+use jeotry::{
+  hull_data,
+  kdtree_data,
+  measure_data,
+  pack_data,
+  raster_data,
+  svg_data,
+};
+use jeotry::pack_data::{
+  Heuristica as Heuristica,
+  Recipiente as Recipiente,
+};
+use std::f32::consts::FRAC_PI_4 as Â45;
+
+
+fn main() {
+  let demos: [(&str, fn()); 13] = [
+    ("modulo", modulo),
+    ("soma", soma),
+    ("objetos", objetos),
+    ("colisoes", colisoes),
+    ("contatos", contatos),
+    ("transformacoes", transformacoes),
+    ("empacotamento", empacotamento),
+    ("envoltoria", envoltoria),
+    ("proximidade", proximidade),
+    ("triangulacao", triangulacao),
+    ("voronoi", voronoi),
+    ("svg", svg),
+    ("imagem", imagem),
+  ];
+
+  let nome = match std::env::args().nth(1) {
+    Some(n) => n.to_lowercase(),
+    None    => {
+      for (nome, _) in demos.iter() {
+        println!("{}", nome);
+      }
+      return;
+    },
+  };
+
+  match demos.iter().find(|(n, _)| *n == nome) {
+    Some((_, demo)) => demo(),
+    None            => {
+      eprintln!("Não existe uma demonstração {}", nome);
+      std::process::exit(2);
+    },
+  }
+}
+
+/// Função teste para provar métodos e funções referentes á módulo
+/// e orientação de vetores
+#[allow(non_snake_case)]
+fn modulo() {
+  let A = Ponto::new(5, 5);
+  let B = Ponto::new(-5, 5);
+  let C = Ponto::new(-5, -5);
+  let D = Ponto::new(5, -5);
+  let O = Ponto::new(0, 0);
+
+  // isto forma um quadrado, mas no espaço vetorial
+  // são apenas vetores apontando para as 4 direções/sentidos 
+  // diferentes. (cima, baixo, <-, ->);
+  let AB = Vetor::from(A, B);
+  let BC = Vetor::from(B, C);
+  let CD = Vetor::from(C, D);
+  let DA = Vetor::from(D, A);
+
+  let mAB = Modulo::extract(AB);
+  let mBC = Modulo::extract(BC);
+  let mCD = Modulo::extract(CD);
+  let mDA = Modulo::extract(DA);
+
+  println!("\nVetores: ");
+  println!("Vetor {}\n >> {:?}\n", "AB", AB);
+  println!("Vetor {}\n >> {:?}\n", "BC", BC);
+  println!("Vetor {}\n >> {:?}\n", "CD", CD);
+  println!("Vetor {}\n >> {:?}\n", "DE", DA);
+
+  println!("\nVetores (module + angle em rads): ");
+  println!("Vetor {}\n >> {:.2}, {:.2}\n", "AB", AB.module(), AB.angle());
+  println!("Vetor {}\n >> {:.2}, {:.2}\n", "BC", BC.module(), BC.angle());
+  println!("Vetor {}\n >> {:.2}, {:.2}\n", "CD", CD.module(), CD.angle());
+  println!("Vetor {}\n >> {:.2}, {:.2}\n", "DE", DA.module(), DA.angle());
+
+  println!("\nMódulos: ");
+  println!("Vetor {}\n >> {:?}\n", "AB", mAB);
+  println!("Vetor {}\n >> {:?}\n", "BC", mBC);
+  println!("Vetor {}\n >> {:?}\n", "CD", mCD);
+  println!("Vetor {}\n >> {:?}\n", "DE", mDA);
+
+  let OA = Vetor::from(O, A);
+  let OB = Vetor::from(O, B);
+  let OC = Vetor::from(O, C);
+  let OD = Vetor::from(O, D);
+  
+  let mOA = Modulo::extract(OA);  
+  let mOB = Modulo::extract(OB); 
+  let mOC = Modulo::extract(OC);
+  let mOD = Modulo::extract(OD);
+
+  println!("\n\nVetores de O (ponto 0, 0): ");
+  println!("Vetor {}\n >> {:?}\n", "OA", OA);
+  println!("Vetor {}\n >> {:?}\n", "OB", OB);
+  println!("Vetor {}\n >> {:?}\n", "OC", OC);
+  println!("Vetor {}\n >> {:?}\n", "OD", OD);
+  
+  println!("\nMódulos: ");
+  println!("Vetor {}\n >> {:?}\n", "OA", mOA);
+  println!("Vetor {}\n >> {:?}\n", "OB", mOB);
+  println!("Vetor {}\n >> {:?}\n", "OC", mOC);
+  println!("Vetor {}\n >> {:?}\n", "OD", mOD);
+ 
+}
+
+/// Função teste para provar métodos e funções relacionadas
+/// à soma de vetores e extração
+fn soma() {
+
+  let v1 = Vetor::new(5, 5);
+  let v2 = Vetor::new(-5, -5);
+  let v3 = Vetor::new(0, 10);
+  let v4 = Vetor::new(-10, 0);
+
+  println!("\nVetores: ");
+  println!("Vetor {}\n >> {:?}\n", "1", v1);
+  println!("Vetor {}\n >> {:?}\n", "2", v2);
+  println!("Vetor {}\n >> {:?}\n", "3", v3);
+  println!("Vetor {}\n >> {:?}\n", "4", v4);
+
+  println!("\nVetores somados: ");
+  println!("Vetor {}\n >> {:?}\n", "1 + 2", v1.clone().sum(v2));
+  println!("Vetor {}\n >> {:?}\n", "2 + 3", v2.clone().sum(v3));
+  println!("Vetor {}\n >> {:?}\n", "3 + 4", v3.clone().sum(v4));
+  println!("Vetor {}\n >> {:?}\n", "4 + 1", v4.clone().sum(v1));
+
+  println!("\nMódulo do conjunto: ");
+  println!("Vetor {}\n >> {:?}\n", "1 + 2", Modulo::from(v1, v2));
+  println!("Vetor {}\n >> {:?}\n", "2 + 3", Modulo::from(v2, v3));
+  println!("Vetor {}\n >> {:?}\n", "3 + 4", Modulo::from(v3, v4));
+  println!("Vetor {}\n >> {:?}\n", "4 + 1", Modulo::from(v4, v1));
+}
+
+/// Função teste para provar métodos e funções relacionadas aos objetos
+#[allow(non_snake_case)]
+fn objetos() {
+  // vetores para teste
+  let v1 = Vetor::new(2, 4);
+
+  // pontos para teste
+  let p1 = Ponto::new(2, 4);
+  let p2 = Ponto::new(2, -4);
+
+
+  let mut B1 = Bloco::new((1, 1), (1, 1));
+  let mut B2 = Bloco::from(p1, p2);
+  
+  let mut B3 = Bloco::new((1, 3), (3, 1));
+  // deve resular em : 
+  // p1: 1, 1    p2: 3, 3 
+
+  let mut B4 = Bloco::new((2, 1), (5, 2));
+  // deve resultar em:
+  // p1: 2, 1    p2: 5, 2
+  
+
+  let mut Q1 = 
+  Quadrilatero::new( 
+    (1, 2), 
+    4, 
+    1 
+  );
+
+  let mut Q2 =
+  Quadrilatero::from(
+    p1,
+    1,
+    1
+  );
+
+  // correto
+  println!("\nBlocos: ");
+  println!("Bloco {}\n >> {:?}\n", "B1", B1);
+  println!("Bloco {}\n >> {:?}\n", "B2", B2);
+  println!("Bloco {}\n >> {:?}\n", "B3", B3);
+  println!("Bloco {}\n >> {:?}\n", "B4", B4);
+  
+  println!("\nQuadriláteros: ");
+  println!("Quad. {}\n >> {:?}\n", "Q1", Q1);
+  println!("Quad. {}\n >> {:?}\n", "Q2", Q2);
+
+  B1.mov(v1);
+  B2.mov(v1);
+  B3.mov(v1);
+  B4.mov(v1);
+
+  Q1.mov(v1);
+  Q2.mov(v1);
+
+
+  println!("\n\n Somados ao vetor {:?}", v1);
+  println!("\nBlocos: ");
+  println!("Bloco {}\n >> {:?}\n", "B1", B1);
+  println!("Bloco {}\n >> {:?}\n", "B2", B2);
+  println!("Bloco {}\n >> {:?}\n", "B3", B3);
+  println!("Bloco {}\n >> {:?}\n", "B4", B4);
+  
+  println!("\nQuadriláteros: ");
+  println!("Quad. {}\n >> {:?}\n", "Q1", Q1);
+  println!("Quad. {}\n >> {:?}\n", "Q2", Q2);
+  
+  B1.mov(v1.neg());
+  B2.mov(v1.neg());
+  B3.mov(v1.neg());
+  B4.mov(v1.neg());
+
+  Q1.mov(v1.neg());
+  Q2.mov(v1.neg());
+
+  println!("\nObjetos com merge (não somados ao vetor): ");
+  println!("Quad. {}\n >> {:?}\n", "Q1+Q2", Quadrilatero::merge(Q1, Q2));
+  println!("Bloco {}\n >> {:?}\n", "B1+B2", Bloco::merge(B1, B2));
+
+  println!("\nOperações entre objetos: ");
+  println!("Bloco {}\n >> {:?}\n", "B3 ∩ B4", B3.intersect(B4));
+  println!("Bloco {}\n >> {:?}\n", "B2 - B3", B2.difference(B3));
+  println!("Bloco {}\n >> {:?}\n", "B3 ∪ B4", B3.union(B4));
+  println!("Quad. {}\n >> {:?}\n", "Q1 ∩ Q2", Q1.intersect(Q2));
+  println!("Quad. {}\n >> {:?}\n", "Q1 - Q2", Q1.difference(Q2));
+
+  let blocos = [B1, B2, B3, B4];
+  println!("\nMedidas do conjunto de blocos: ");
+  println!("Área coberta\n >> {}\n", measure_data::area_coberta(&blocos));
+  println!("Perímetro da união\n >> {}\n", measure_data::perimetro_coberto(&blocos));
+
+  let formas = [Forma::Bloco(B3), Forma::Circulo(Circulo::new((6, 6), 1.5))];
+  println!("\nVolumes envolventes: ");
+  println!("Bloco {}\n >> {:?}\n", "dos pontos", Bloco::from_points([p1, p2, Ponto::new(-1, 0)]));
+  println!("Bloco {}\n >> {:?}\n", "das formas", Bloco::from_shapes(formas.iter()));
+  println!("Bloco {}\n >> {:?}\n", "do círculo", Circulo::new((6, 6), 1.5).into_block());
+  println!("Bloco {}\n >> {:?}\n", "B4 inflado", B4.inflate(1));
+  println!("Bloco {}\n >> {:?}\n", "B4 expandido", B4.expand(0, -1, 2, 0));
+  
+}
+
+fn colisoes() {
+  let b1 = Bloco::new((1, 1), (3, 3));
+  let b2 = Bloco::new((2, 2), (5, 5));
+  let b3 = Bloco::new((4, 4), (6, 6));
+  // uma corrente na diagonal de blocos
+  // bloco 1 colide com bloco 2
+  // bloco 2 colide com bloco 3
+  // bloco 3 não colide com bloco 1
+
+  println!("\n COLISÕES BLOCOS: ");
+  println!(">> {} : {}", "12", b1.collide_block(b2));
+  println!(">> {} : {}", "23", b2.collide_block(b3));
+  println!(">> {} : {}", "31", b3.collide_block(b1));
+  desenha(&[
+    ("b1", Forma::Bloco(b1)),
+    ("b2", Forma::Bloco(b2)),
+    ("b3", Forma::Bloco(b3)),
+  ]);
+
+
+  // mesma coisa com quadrláteros
+  let q1 = Quadrilatero::new((1, 1), 2, 2);
+  let q2 = Quadrilatero::new((2, 2), 2, 2);
+  let q3 = Quadrilatero::new((4, 4), 2, 2);
+  
+  println!("\n COLISÕES QUADRILÁTEROS: ");
+  println!(">> {} : {}", "12", q1.collide_quad(q2));
+  println!(">> {} : {}", "23", q2.collide_quad(q3));
+  println!(">> {} : {}", "31", q3.collide_quad(q1));
+  desenha(&[
+    ("q1", Forma::Quadrilatero(q1)),
+    ("q2", Forma::Quadrilatero(q2)),
+    ("q3", Forma::Quadrilatero(q3)),
+  ]);
+
+  println!("\n COLISÕES ENTRE QUADRILÁTEROS NÃO EQUILÁTEROS: ");
+  let q1 = Quadrilatero:: new((1, 1), 2, 1);
+  let q2 = Quadrilatero:: new((2, 2), 2, 1);
+  let q3 = Quadrilatero:: new((3, 1), 3, 1);
+  println!(">> {} : {}", "12", q1.collide_quad(q2));
+  println!(">> {} : {}", "23", q2.collide_quad(q3));
+  println!(">> {} : {}", "31", q3.collide_quad(q1));
+  desenha(&[
+    ("q1", Forma::Quadrilatero(q1)),
+    ("q2", Forma::Quadrilatero(q2)),
+    ("q3", Forma::Quadrilatero(q3)),
+  ]);
+  let q1 = Quadrilatero:: new((1, 1), 2, 1);
+  let q2 = Quadrilatero:: new((2, 3), 2, 1);
+  let q3 = Quadrilatero:: new((3, 1), 3, 1);
+  println!("\n>> {} : {}", "12", q1.collide_quad(q2));
+  println!(">> {} : {}", "23", q2.collide_quad(q3));
+  println!(">> {} : {}", "31", q3.collide_quad(q1));
+  desenha(&[
+    ("q1", Forma::Quadrilatero(q1)),
+    ("q2", Forma::Quadrilatero(q2)),
+    ("q3", Forma::Quadrilatero(q3)),
+  ]);
+
+  println!("\n COLISÕES ENTRE QUADRILÁTEROS E BLOCOS");
+  println!(">> {} : {}", "12", b1.collide_quad(q2));
+  println!(">> {} : {}", "23", b2.collide_quad(q3));
+  println!(">> {} : {}", "31", b3.collide_quad(q1));
+  desenha(&[
+    ("b1", Forma::Bloco(b1)),
+    ("b2", Forma::Bloco(b2)),
+    ("b3", Forma::Bloco(b3)),
+    ("q1", Forma::Quadrilatero(q1)),
+    ("q2", Forma::Quadrilatero(q2)),
+    ("q3", Forma::Quadrilatero(q3)),
+  ]);
+
+
+  let c1 = Circulo::new((1, 1), 2.0);
+  let c2 = Circulo::from(Ponto::new(3, 3), 1.0);
+  let c3 = Circulo::new((3, 5), 1.0);
+  println!("\n COLISÕES CÍRCULOS:");
+  println!(">> {} : {}", "12", c1.collide_circle(c2));
+  println!(">> {} : {}", "23", c2.collide_circle(c3));
+  println!(">> {} : {}", "31", c3.collide_circle(c1));
+  desenha(&[
+    ("c1", Forma::Circulo(c1)),
+    ("c2", Forma::Circulo(c2)),
+    ("c3", Forma::Circulo(c3)),
+  ]);
+
+  // quadrado de lado 2 girado em 45 graus no centro (3, 3)
+  // seus cantos alcançam até ~1.41 do centro
+  let o1 = QuadOrientado::new((3.0, 3.0), 1.0, 1.0, Â45);
+  let o2 = QuadOrientado::from_modulo((5.0, 3.0), 1.0, 0.5, Modulo::new(1, 1));
+  println!("\n COLISÕES QUADRILÁTEROS ORIENTADOS:");
+  println!(">> {} : {}", "o1 o2", o1.collide_orient(o2));
+  println!(">> {} : {}", "o1 b1", o1.collide_block(b1));
+  println!(">> {} : {}", "o1 b3", o1.collide_block(b3));
+  println!(">> {} : {}", "o1 q3", o1.collide_quad(q3));
+  println!(">> {} : {}", "o1 c2", o1.collide_circle(c2));
+  println!(">> {} : {}", "o2 c3", o2.collide_circle(c3));
+  println!(">> {} : {:?}", "cantos o1", o1.corners());
+  desenha(&[
+    ("o1", Forma::Orientado(o1)),
+    ("o2", Forma::Orientado(o2)),
+    ("c2", Forma::Circulo(c2)),
+  ]);
+}
+
+
+/// Desenha os objetos no terminal, com as sobreposições destacadas
+fn desenha( formas: &[(&str, Forma)]) {
+  let mut t = Terminal::new();
+  for (nome, f) in formas.iter() {
+    t.forma(nome, f.clone());
+  }
+
+  println!("{}", t);
+}
+
+/// Função teste para provar o rastreamento de contatos
+/// entre quadros
+fn contatos() {
+  let mut r = Rastreador::new();
+
+  // o bloco 3 pertence à segunda camada e só colide com ela
+  r.definir(3, Camada::new(0b10, 0b10));
+
+  let mut b1 = Bloco::new((0, 0), (2, 2));
+  let b2 = Bloco::new((3, 0), (5, 2));
+  let b3 = Bloco::new((1, 0), (4, 2));
+  let v = Vetor::new(1, 0);
+
+  println!("\n CONTATOS ENTRE QUADROS: ");
+  for quadro in 0..4 {
+    let objetos = [(1, b1), (2, b2), (3, b3)];
+    let eventos = r.detectar(&objetos, Bloco::collide_block);
+    println!(">> quadro {} : {:?}", quadro, eventos);
+
+    // o bloco 1 anda para a direita até atravessar o bloco 2
+    b1.mov(v);
+    b1.mov(v);
+  }
+}
+
+/// Função teste para provar as transformações afins
+/// sobre os objetos
+fn transformacoes() {
+  let p = Ponto::new(4, 2);
+  let b = Bloco::new((0, 0), (4, 2));
+  let c = Circulo::new((2, 2), 1.0);
+  let t = Poligono::from(&[(0, 0), (4, 0), (0, 3)]);
+
+  let rot = Transformacao::rotacao(Â90);
+  let esc = Transformacao::escala_xy(2.0, 1.0);
+  let gir = Transformacao::rotacao_em(Â45, b.center());
+  let mov = Transformacao::from_vetor(Vetor::new(1, 1));
+
+  println!("\n TRANSFORMAÇÕES: ");
+  println!(">> {} : {:?}", "rot p", rot.aplica_ponto(p));
+  println!(">> {} : {:?}", "rot b", rot.aplica_bloco(b));
+  println!(">> {} : {:?}", "esc b", esc.aplica_bloco(b));
+  println!(">> {} : {:?}", "gir b", gir.aplica_bloco(b));
+  println!(">> {} : {:?}", "mov c", mov.aplica_circulo(c));
+  println!(">> {} : {:?}", "esc+rot t", esc.seguida(rot).aplica_poligono(&t));
+
+  // a inversa desfaz a composição
+  let ida = esc.seguida(gir).seguida(mov);
+  let volta = ida.inversa().unwrap();
+  println!(">> {} : {:?}", "ida+volta p", ida.seguida(volta).aplica_ponto(p));
+  println!(">> {} : {:?}", "cis c", Transformacao::cisalhamento(1.0, 0.0).aplica_circulo(c));
+}
+
+/// Função teste para provar o empacotamento de quadriláteros
+fn empacotamento() {
+  let quads = [
+    Quadrilatero::new((0, 0), 4, 2),
+    Quadrilatero::new((0, 0), 2, 2),
+    Quadrilatero::new((0, 0), 1, 3),
+    Quadrilatero::new((0, 0), 3, 1),
+    Quadrilatero::new((0, 0), 2, 1),
+  ];
+
+  let heuristicas = [
+    Heuristica::Skyline,
+    Heuristica::MaxRects,
+    Heuristica::Guilhotina
+  ];
+
+  println!("\n EMPACOTAMENTO EM RECIPIENTE 5x4: ");
+  for h in heuristicas.iter() {
+    let e = pack_data::empacotar(&quads, Recipiente::Fixo(5, 4), *h, true);
+    println!(">> {:?} : desperdício {}, restantes {:?}", h, e.desperdicio, e.restantes);
+    for c in e.colocados.iter() {
+      println!("   {} : {:?} girado: {}", c.indice, c.q, c.girado);
+    }
+  }
+
+  println!("\n EMPACOTAMENTO EM RECIPIENTE CRESCENTE: ");
+  for h in heuristicas.iter() {
+    let e = pack_data::empacotar(&quads, Recipiente::Crescente(2, 2), *h, false);
+    println!(">> {:?} : {:?}, desperdício {}", h, e.recipiente, e.desperdicio);
+  }
+}
+
+/// Função teste para provar a envoltória convexa, com pontos
+/// repetidos e colineares
+fn envoltoria() {
+  let pontos: Vec<Ponto> = [(0, 0), (2, 0), (4, 0), (4, 4), (2, 2), (0, 4), (2, 2), (1, 3), (3, 1)]
+    .iter()
+    .map(|c| Ponto::new(c.0, c.1))
+    .collect();
+
+  println!("\n ENVOLTÓRIA CONVEXA: ");
+  println!(">> {} : {:?}", "pontos", pontos);
+  println!(">> {} : {:?}", "envoltória", hull_data::envoltoria_convexa(&pontos).pontos);
+
+  // pontos internos não mudam a envoltória
+  let mut e = Envoltoria::new();
+  for p in [Ponto::new(0, 0), Ponto::new(4, 0), Ponto::new(2, 1), Ponto::new(2, 5), Ponto::new(6, 2)] {
+    let mudou = e.inserir(p);
+    println!(">> inserir {:?} : {} {:?}", p, mudou, e.vertices());
+  }
+
+  // calibres rotativos sobre a envoltória
+  println!(">> {} : {:?}", "diâmetro", e.diametro());
+  println!(">> {} : {:?}", "largura", e.largura());
+  println!(">> {} : {:?}", "retângulo de menor área", e.retangulo_min_area());
+  println!(">> {} : {:?}", "retângulo de menor perímetro", e.retangulo_min_perimetro());
+
+  // círculos envolventes
+  let formas = [
+    Forma::Bloco(Bloco::new((0, 0), (2, 2))),
+    Forma::Circulo(Circulo::new((6, 1), 1.5)),
+  ];
+  println!(">> {} : {:?}", "círculo mínimo", hull_data::circulo_minimo(&pontos));
+  println!(">> {} : {:?}", "círculo mínimo das formas", hull_data::circulo_minimo_formas(&formas));
+}
+
+/// Função teste para provar as consultas de proximidade da
+/// árvore k-d e o par mais próximo
+fn proximidade() {
+  let pontos: Vec<Ponto> = [(0, 0), (5, 1), (2, 7), (8, 8), (3, 3), (9, 2), (4, 4), (1, 9)]
+    .iter()
+    .map(|c| Ponto::new(c.0, c.1))
+    .collect();
+  let arvore = ArvoreKd::new(&pontos);
+  let alvo = Ponto::new(4, 2);
+
+  println!("\n PROXIMIDADE: ");
+  println!(">> {} : {:?}", "pontos", pontos);
+  println!(">> {} {:?} : {:?}", "mais próximo de", alvo, arvore.mais_proximo(alvo));
+  println!(">> {} {:?} : {:?}", "3 mais próximos de", alvo, arvore.k_mais_proximos(alvo, 3));
+  println!(">> {} {:?} : {:?}", "raio 3 em torno de", alvo, arvore.no_raio(alvo, 3.0));
+  println!(">> {} : {:?}", "par mais próximo", kdtree_data::par_mais_proximo(&pontos));
+}
+
+/// Função teste para provar a triangulação de Delaunay, a
+/// adjacência entre triângulos e uma aresta restrita
+fn triangulacao() {
+  let pontos: Vec<Ponto> = [(0, 0), (6, 0), (6, 6), (0, 6), (3, 1), (3, 5), (1, 3), (5, 3)]
+    .iter()
+    .map(|c| Ponto::new(c.0, c.1))
+    .collect();
+  let mut t = Triangulacao::new(&pontos);
+
+  println!("\n TRIANGULAÇÃO: ");
+  println!(">> {} : {:?}", "pontos", pontos);
+  println!(">> {} : {:?}", "triângulos", t.triangulos());
+  println!(">> {} : {:?}", "adjacências", t.adjacencias().collect::<Vec<_>>());
+
+  // a aresta restrita substitui as que a cruzam
+  match t.restringir(6, 7) {
+    Ok(_)  => println!(">> {} : {:?}", "com a aresta (6, 7)", t.triangulos()),
+    Err(e) => println!(">> {} : {}", "com a aresta (6, 7)", e),
+  }
+  println!(">> {} : {:?}", "a aresta (4, 5) cruza", t.restringir(4, 5));
+}
+
+/// Função teste para provar o diagrama de Voronoi, com o
+/// território de cada sítio e o sítio mais próximo de um ponto
+fn voronoi() {
+  let sitios: Vec<Ponto> = [(2, 2), (8, 3), (5, 8), (1, 9), (9, 9)]
+    .iter()
+    .map(|c| Ponto::new(c.0, c.1))
+    .collect();
+  let v = Voronoi::new(&sitios, Bloco::new((0, 0), (10, 10)));
+  let alvo = Ponto::new(6, 5);
+
+  println!("\n VORONOI: ");
+  println!(">> {} : {:?}", "sítios", sitios);
+  for i in 0..v.len() {
+    println!(">> célula {} : área {:.2}, vizinhos {:?}", i, v.area(i), v.vizinhos(i));
+    println!("   {:?}", v.poligono(i).pontos);
+  }
+  println!(">> {} {:?} : {:?}", "sítio mais próximo de", alvo, v.localiza(alvo));
+}
+
+/// Função teste para provar a exportação em SVG, desenha os
+/// objetos da função de colisões
+fn svg() {
+  use svg_data::{Svg, Estilo};
+
+  let azul = Estilo::new("blue", "lightblue").opacidade(0.6);
+  let verm = Estilo::new("red", "pink").opacidade(0.6);
+  let verde = Estilo::new("green", "none").espessura(2.0);
+
+  let mut doc = Svg::new();
+  doc.bloco(Bloco::new((1, 1), (3, 3)), &azul);
+  doc.bloco(Bloco::new((2, 2), (5, 5)), &azul);
+  doc.bloco(Bloco::new((4, 4), (6, 6)), &azul);
+  doc.quad(Quadrilatero::new((3, 1), 3, 1), &verm);
+  doc.circulo(Circulo::new((1, 1), 2.0), &verde);
+  doc.orientado(QuadOrientado::new((3.0, 3.0), 1.0, 1.0, Â45), &verm);
+  doc.vetor(Ponto::new(0, 0), Vetor::new(2, 4), &verde);
+  doc.rotulo((1.0, 3.0), "b1", &Estilo::default());
+
+  let caminho = "colisoes.svg";
+  match doc.salvar(caminho) {
+    Ok(_)  => println!("\n SVG escrito em {}", caminho),
+    Err(e) => println!("\n Não foi possível escrever {}: {}", caminho, e),
+  }
+}
+
+/// Função teste para provar a rasterização em imagens PPM e PGM,
+/// desenha os objetos da função de colisões com 20 pixels por unidade
+fn imagem() {
+  use raster_data::Imagem;
+
+  let t = Transformacao::escala(20.0)
+    .seguida(Transformacao::translacao(20.0, 20.0));
+
+  let mut img = Imagem::new(160, 160, [255, 255, 255]);
+  img.suave = true;
+  img.forma(&Forma::Bloco(Bloco::new((1, 1), (3, 3))), t, [120, 160, 255], true);
+  img.forma(&Forma::Bloco(Bloco::new((2, 2), (5, 5))), t, [0, 0, 200], false);
+  img.forma(&Forma::Circulo(Circulo::new((5, 2), 1.0)), t, [0, 160, 0], true);
+  img.forma(&Forma::Orientado(QuadOrientado::new((2.0, 5.0), 1.0, 0.5, Â45)), t, [200, 0, 0], false);
+  img.linha(Ponto::new(0, 0), Ponto::new(159, 60), [0, 0, 0]);
+
+  for (caminho, bytes) in [("colisoes.ppm", img.ppm()), ("colisoes.pgm", img.pgm())].iter() {
+    match std::fs::write(caminho, bytes) {
+      Ok(_)  => println!("\n Imagem escrita em {}", caminho),
+      Err(e) => println!("\n Não foi possível escrever {}: {}", caminho, e),
+    }
+  }
+}
@@ -0,0 +1,684 @@
+/// Colisões em lote: leitura de formas em CSV e busca de todos
+/// os pares que colidem.
+///
+/// Cada linha do CSV tem o tipo, o identificador e os valores
+/// da forma, na mesma ordem das linhas de uma cena. Linhas
+/// vazias, tudo após um `#` e um cabeçalho iniciado por `tipo`
+/// ou `type` são ignorados.
+///
+/// ```text
+/// tipo,id,valores
+/// bloco,b1,1,1,3,3
+/// quad,q1,1,2,4,1
+/// circulo,c1,3,3,1.5
+/// ```
+///
+/// A fase ampla ordena as formas pela borda esquerda e varre o
+/// eixo x, testando apenas as formas cujos limites se cruzam.
+/// Os pares são repassados assim que encontrados, sem serem
+/// guardados. Com a entrada já ordenada pela borda esquerda a
+/// varredura acontece durante a leitura e apenas as formas
+/// ativas ficam em memória.
+///
+/// Fora de ordem, as formas são ordenadas em blocos de até
+/// [`BLOCO`] formas, gravados em arquivos temporários e
+/// intercalados na varredura. A memória fica limitada a um
+/// bloco durante a leitura e a uma forma por arquivo, além das
+/// ativas, durante a varredura.
+pub use super::object_data::{
+    Segmento as Segmento,
+    Forma as Forma,
+};
+use super::scene_data::{
+    Elemento,
+    ErroCena,
+    le_elemento,
+};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering as Atomica};
+
+
+/// Folga da fase ampla, para não descartar formas que apenas
+/// se tocam com erros de arredondamento
+static FOLGA: f64 = 1e-9;
+
+/// Formas ordenadas de cada vez na memória quando a entrada
+/// está fora de ordem
+pub const BLOCO: usize = 1 << 16;
+
+/// Arquivos temporários gerados por este processo, para nomes
+/// distintos entre as threads
+static PARTICOES: AtomicUsize = AtomicUsize::new(0);
+
+/// Nomes tentados para cada arquivo temporário antes de desistir,
+/// quando os anteriores já existem
+static TENTATIVAS: usize = 100;
+
+
+// Estruturas
+/// Forma lida de uma linha do CSV
+#[derive(Debug, Clone)]
+pub struct Registro {
+  /// Linha do arquivo, a partir de 1
+  pub linha: usize,
+
+  /// Identificador da forma
+  pub id: String,
+
+  /// Forma descrita na linha
+  pub forma: Forma
+}
+
+
+/// Leitor de formas em CSV, uma linha por vez
+pub struct LeitorCsv<R> {
+  entrada: R,
+  linha: usize,
+  texto: String
+}
+
+
+/// Totais de uma busca em lote
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Resumo {
+  /// Formas lidas
+  pub formas: usize,
+
+  /// Pares que colidem
+  pub pares: usize,
+
+  /// Pares testados pela fase estreita
+  pub testes: usize,
+
+  /// Maior quantidade de formas ativas na varredura
+  pub ativas: usize
+}
+
+
+/// Forma ativa na varredura, com os limites já calculados
+struct Ativa {
+  registro: Registro,
+  min: (f64, f64),
+  max: (f64, f64)
+}
+
+
+/// Bloco de formas ordenado em um arquivo temporário, que é
+/// removido ao fim
+struct Particao {
+  caminho: PathBuf,
+  leitor: BufReader<File>,
+  texto: String
+}
+
+
+/// Próxima forma de uma partição, a menor borda esquerda vem
+/// primeiro e os empates seguem a ordem das partições
+struct Cabeca {
+  x: f64,
+  particao: usize
+}
+
+
+/// Intercalação das partições em ordem da borda esquerda
+struct Intercalacao {
+  particoes: Vec<Particao>,
+  proximos: Vec<Option<Registro>>,
+  fila: BinaryHeap<Cabeca>
+}
+
+
+// Métodos relacionados ao leitor
+impl<R: BufRead> LeitorCsv<R> {
+
+
+  /// Gera um leitor sobre a entrada
+  pub fn new( entrada: R) -> Self {
+    Self {
+      entrada,
+      linha: 0,
+      texto: String::new()
+    }
+  }
+}
+
+
+impl<R: BufRead> Iterator for LeitorCsv<R> {
+  type Item = Result<Registro, ErroCena>;
+
+  fn next( &mut self ) -> Option<Self::Item> {
+    loop {
+      self.texto.clear();
+      match self.entrada.read_line(&mut self.texto) {
+        Ok(0)  => return None,
+        Ok(_)  => self.linha += 1,
+        Err(e) => return Some(Err(ErroCena::Io(e))),
+      }
+
+      let erro = |mensagem: String| ErroCena::Sintaxe { linha: self.linha, mensagem };
+
+      // comentários vão até o fim da linha
+      let conteudo = match self.texto.find('#') {
+        Some(c) => &self.texto[..c],
+        None    => &self.texto[..],
+      };
+
+      let campos: Vec<&str> = conteudo.split(',').map(str::trim).collect();
+      let tipo = campos[0];
+      if campos.len() == 1 && tipo.is_empty() {
+        continue;
+      }
+
+      // cabeçalho
+      if self.linha == 1 && (tipo == "tipo" || tipo == "type") {
+        continue;
+      }
+
+      let id = match campos.get(1) {
+        Some(id) if !id.is_empty() => id.to_string(),
+        _                          => return Some(Err(erro(format!("'{}' sem identificador", tipo)))),
+      };
+
+      let forma = match le_elemento(tipo, &campos[2..]) {
+        Ok(Elemento::Forma(f)) => f,
+        Ok(Elemento::Ponto(p)) => Forma::Segmento(Segmento::from(p, p)),
+        Ok(Elemento::Vetor(_)) => return Some(Err(erro("vetores não colidem".to_string()))),
+        Err(e)                 => return Some(Err(erro(e))),
+      };
+
+      return Some(Ok(Registro {
+        linha: self.linha,
+        id,
+        forma
+      }));
+    }
+  }
+}
+
+
+// Métodos relacionados às partições
+impl Particao {
+
+
+  /// Ordena e grava as formas em um novo arquivo, esvaziando o
+  /// bloco
+  fn grava( bloco: &mut Vec<(f64, Registro)>) -> Result<Self, ErroCena> {
+    bloco.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let (caminho, arquivo) = Particao::cria().map_err(ErroCena::Io)?;
+
+    // a partir daqui o arquivo é removido mesmo em caso de erro,
+    // e a escrita e a leitura usam o mesmo arquivo aberto
+    let escrita = arquivo.try_clone();
+    let mut particao = Self {
+      leitor: BufReader::new(arquivo),
+      caminho,
+      texto: String::new()
+    };
+
+    // a linha original, o tamanho do identificador, o
+    // identificador e a forma como nas linhas de uma cena
+    let mut escritor = BufWriter::new(escrita.map_err(ErroCena::Io)?);
+    for (_, r) in bloco.drain(..) {
+      let e = Elemento::Forma(r.forma);
+      writeln!(escritor, "{} {} {} {} {}", r.linha, r.id.len(), r.id, e.tipo(), e.valores()).map_err(ErroCena::Io)?;
+    }
+    escritor.flush().map_err(ErroCena::Io)?;
+    particao.leitor.seek(SeekFrom::Start(0)).map_err(ErroCena::Io)?;
+
+    return Ok(particao);
+  }
+
+
+  /// Cria um arquivo temporário novo, apenas para o usuário.
+  ///
+  /// O arquivo nunca é aberto se já existir, um nome já usado
+  /// no diretório temporário compartilhado, por acaso ou para
+  /// desviar a escrita, faz com que o próximo nome seja tentado.
+  fn cria() -> io::Result<(PathBuf, File)> {
+    let mut opcoes = OpenOptions::new();
+    opcoes.read(true).write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut opcoes, 0o600);
+
+    for _ in 0..TENTATIVAS {
+      let numero = PARTICOES.fetch_add(1, Atomica::Relaxed);
+      let caminho = std::env::temp_dir().join(format!("jeotry-lote-{}-{}.txt", std::process::id(), numero));
+      match opcoes.open(&caminho) {
+        Ok(arquivo)                                          => return Ok((caminho, arquivo)),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+        Err(e)                                               => return Err(e),
+      }
+    }
+
+    return Err(io::Error::new(
+      io::ErrorKind::AlreadyExists,
+      format!("nenhum arquivo temporário livre após {} tentativas", TENTATIVAS)
+    ));
+  }
+
+
+  /// Lê a próxima forma da partição
+  fn le( &mut self ) -> Result<Option<Registro>, ErroCena> {
+    self.texto.clear();
+    if self.leitor.read_line(&mut self.texto).map_err(ErroCena::Io)? == 0 {
+      return Ok(None);
+    }
+
+    let invalido = || ErroCena::Io(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("{}: linha inválida", self.caminho.display())
+    ));
+
+    let texto = self.texto.trim_end_matches('\n');
+    let (linha, resto) = texto.split_once(' ').ok_or_else(invalido)?;
+    let (tamanho, resto) = resto.split_once(' ').ok_or_else(invalido)?;
+    let linha = linha.parse::<usize>().map_err(|_| invalido())?;
+    let tamanho = tamanho.parse::<usize>().map_err(|_| invalido())?;
+    let id = resto.get(..tamanho).ok_or_else(invalido)?;
+    let mut campos = resto[tamanho..].split_whitespace();
+    let tipo = campos.next().ok_or_else(invalido)?;
+
+    let forma = match le_elemento(tipo, &campos.collect::<Vec<&str>>()) {
+      Ok(Elemento::Forma(f)) => f,
+      _                      => return Err(invalido()),
+    };
+
+    return Ok(Some(Registro {
+      linha,
+      id: id.to_string(),
+      forma
+    }));
+  }
+}
+
+
+impl Drop for Particao {
+  fn drop( &mut self ) {
+    let _ = std::fs::remove_file(&self.caminho);
+  }
+}
+
+
+impl PartialEq for Cabeca {
+  fn eq( &self, outra: &Self) -> bool {
+    self.cmp(outra) == Ordering::Equal
+  }
+}
+
+
+impl Eq for Cabeca {}
+
+
+impl PartialOrd for Cabeca {
+  fn partial_cmp( &self, outra: &Self) -> Option<Ordering> {
+    Some(self.cmp(outra))
+  }
+}
+
+
+impl Ord for Cabeca {
+  // invertida, a fila retorna a maior
+  fn cmp( &self, outra: &Self) -> Ordering {
+    outra.x.total_cmp(&self.x).then(outra.particao.cmp(&self.particao))
+  }
+}
+
+
+// Métodos relacionados à intercalação
+impl Intercalacao {
+
+
+  /// Gera a intercalação com a primeira forma de cada partição
+  fn new( mut particoes: Vec<Particao>) -> Result<Self, ErroCena> {
+    let mut proximos = Vec::with_capacity(particoes.len());
+    let mut fila = BinaryHeap::with_capacity(particoes.len());
+    for (i, p) in particoes.iter_mut().enumerate() {
+      let r = p.le()?;
+      if let Some(r) = &r {
+        fila.push(Cabeca { x: r.forma.limites().0.0, particao: i });
+      }
+      proximos.push(r);
+    }
+
+    return Ok(Self {
+      particoes,
+      proximos,
+      fila
+    });
+  }
+}
+
+
+impl Iterator for Intercalacao {
+  type Item = Result<Registro, ErroCena>;
+
+  fn next( &mut self ) -> Option<Self::Item> {
+    let i = self.fila.pop()?.particao;
+    let registro = self.proximos[i].take()?;
+
+    match self.particoes[i].le() {
+      Ok(Some(r)) => {
+        self.fila.push(Cabeca { x: r.forma.limites().0.0, particao: i });
+        self.proximos[i] = Some(r);
+      },
+      Ok(None)    => {},
+      Err(e)      => return Some(Err(e)),
+    }
+
+    return Some(Ok(registro));
+  }
+}
+
+
+// Funções
+/// Busca todos os pares de formas que colidem, chamando `par`
+/// para cada um deles na ordem da varredura.
+///
+/// Com `ordenado` as formas devem chegar em ordem crescente da
+/// borda esquerda e são processadas durante a leitura, uma
+/// forma fora de ordem é um erro. Sem `ordenado` as formas são
+/// ordenadas em blocos de [`BLOCO`], como em [`colisoes_em_blocos`].
+pub fn colisoes<I, F>( formas: I, ordenado: bool, mut par: F) -> Result<Resumo, ErroCena>
+where
+  I: IntoIterator<Item = Result<Registro, ErroCena>>,
+  F: FnMut(&Registro, &Registro) -> io::Result<()>,
+{
+  if ordenado {
+    return varre(formas, true, &mut par);
+  }
+
+  return colisoes_em_blocos(formas, BLOCO, par);
+}
+
+
+/// Busca os pares de formas fora de ordem, ordenando até `bloco`
+/// formas por vez na memória.
+///
+/// Cada bloco ordenado é gravado em um arquivo temporário e os
+/// arquivos são intercalados durante a varredura, que guarda
+/// uma forma por arquivo além das ativas. Uma entrada que cabe
+/// em um bloco é ordenada sem arquivos. Os pares são os mesmos
+/// de uma ordenação estável da entrada inteira.
+pub fn colisoes_em_blocos<I, F>( formas: I, bloco: usize, mut par: F) -> Result<Resumo, ErroCena>
+where
+  I: IntoIterator<Item = Result<Registro, ErroCena>>,
+  F: FnMut(&Registro, &Registro) -> io::Result<()>,
+{
+  let bloco = bloco.max(1);
+  let mut particoes = Vec::new();
+  let mut atual = Vec::new();
+  for registro in formas {
+    let registro = registro?;
+    if atual.len() == bloco {
+      particoes.push(Particao::grava(&mut atual)?);
+    }
+    atual.push((registro.forma.limites().0.0, registro));
+  }
+
+  if particoes.is_empty() {
+    atual.sort_by(|a, b| a.0.total_cmp(&b.0));
+    return varre(atual.into_iter().map(|(_, r)| Ok(r)), false, &mut par);
+  }
+
+  if !atual.is_empty() {
+    particoes.push(Particao::grava(&mut atual)?);
+  }
+  return varre(Intercalacao::new(particoes)?, false, &mut par);
+}
+
+
+/// Varredura no eixo x com a lista de formas ativas
+fn varre<I, F>( formas: I, confere: bool, par: &mut F) -> Result<Resumo, ErroCena>
+where
+  I: IntoIterator<Item = Result<Registro, ErroCena>>,
+  F: FnMut(&Registro, &Registro) -> io::Result<()>,
+{
+  let mut resumo = Resumo::default();
+  let mut ativas: Vec<Ativa> = Vec::new();
+  let mut ultima = f64::MIN;
+
+  for registro in formas {
+    let registro = registro?;
+    let (min, max) = registro.forma.limites();
+    resumo.formas += 1;
+
+    if confere && min.0 < ultima {
+      return Err(ErroCena::Sintaxe {
+        linha: registro.linha,
+        mensagem: format!("'{}' fora de ordem, a borda esquerda {} é menor que {}", registro.id, min.0, ultima)
+      });
+    }
+    ultima = min.0;
+
+    // formas que terminam antes desta não colidem com as próximas
+    ativas.retain(|a| a.max.0 + FOLGA >= min.0);
+
+    for a in ativas.iter() {
+      if a.max.1 + FOLGA < min.1 || max.1 + FOLGA < a.min.1 {
+        continue;
+      }
+
+      resumo.testes += 1;
+      if a.registro.forma.collide(&registro.forma) {
+        resumo.pares += 1;
+        par(&a.registro, &registro).map_err(ErroCena::Io)?;
+      }
+    }
+
+    ativas.push(Ativa {
+      registro,
+      min,
+      max
+    });
+    resumo.ativas = resumo.ativas.max(ativas.len());
+  }
+
+  return Ok(resumo);
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::random_data::entre;
+  use std::collections::BTreeSet;
+
+
+  /// Linhas de CSV com formas de todos os tipos
+  fn csv( estado: &mut u64, n: usize, lado: i32) -> String {
+    let mut texto = String::from("tipo,id,valores\n");
+    for i in 0..n {
+      let (x, y) = (entre(estado, 0, lado - 1), entre(estado, 0, lado - 1));
+      let (a, b) = (entre(estado, 0, 11), entre(estado, 0, 11));
+      let linha = match entre(estado, 0, 6) {
+        0 => format!("bloco,f{},{},{},{},{}", i, x, y, x + a, y + b),
+        1 => format!("quad,f{},{},{},{},{}", i, x, y, a, b),
+        2 => format!("circulo,f{},{},{},{}", i, x, y, a as f64 / 2.0),
+        3 => format!("orientado,f{},{},{},{},{},{}", i, x, y, a as f64 / 3.0, b as f64 / 3.0, a as f64 / 7.0),
+        4 => format!("segmento,f{},{},{},{},{}", i, x, y, x + a - 6, y + b - 6),
+        5 => format!("ponto,f{},{},{}", i, x, y),
+        _ => format!("poligono,f{},{},{},{},{},{},{}", i, x, y, x + a, y, x, y + b + 1),
+      };
+      texto.push_str(&linha);
+      texto.push('\n');
+    }
+    return texto;
+  }
+
+
+  fn registros( texto: &str) -> Vec<Registro> {
+    LeitorCsv::new(texto.as_bytes()).collect::<Result<_, _>>().unwrap()
+  }
+
+
+  /// Pares encontrados, cada um na ordem da varredura
+  fn pares<F>( busca: F) -> (Vec<(String, String)>, Resumo)
+  where
+    F: FnOnce(&mut dyn FnMut(&Registro, &Registro) -> io::Result<()>) -> Result<Resumo, ErroCena>,
+  {
+    let mut pares = Vec::new();
+    let resumo = busca(&mut |a: &Registro, b: &Registro| {
+      pares.push((a.id.clone(), b.id.clone()));
+      Ok(())
+    }).unwrap();
+    return (pares, resumo);
+  }
+
+
+  /// Pares sem ordem, para comparar com a força bruta
+  fn conjunto( pares: &[(String, String)]) -> BTreeSet<(String, String)> {
+    pares.iter()
+      .map(|(a, b)| if a < b { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) })
+      .collect()
+  }
+
+
+  #[test]
+  fn fase_ampla_encontra_os_pares_da_forca_bruta() {
+    let mut estado = 0x9e3779b97f4a7c15;
+    for rodada in 0..40 {
+      let n = 1 + entre(&mut estado, 0, 119) as usize;
+      let lado = 10 + entre(&mut estado, 0, 99);
+      let texto = csv(&mut estado, n, lado);
+      let formas = registros(&texto);
+
+      let mut esperado = BTreeSet::new();
+      for (i, a) in formas.iter().enumerate() {
+        for b in formas[i + 1..].iter() {
+          if a.forma.collide(&b.forma) {
+            esperado.insert(if a.id < b.id { (a.id.clone(), b.id.clone()) } else { (b.id.clone(), a.id.clone()) });
+          }
+        }
+      }
+
+      let (todos, resumo) = pares(|par| colisoes(LeitorCsv::new(texto.as_bytes()), false, par));
+      assert_eq!(conjunto(&todos), esperado, "rodada {}", rodada);
+      assert_eq!(todos.len(), esperado.len());
+      assert_eq!(resumo.formas, n);
+      assert_eq!(resumo.pares, esperado.len());
+      assert!(resumo.testes <= n * (n - 1) / 2);
+
+      // a entrada já ordenada dá os mesmos pares, na mesma ordem
+      let mut ordenadas = formas.clone();
+      ordenadas.sort_by(|a, b| a.forma.limites().0.0.total_cmp(&b.forma.limites().0.0));
+      let (em_ordem, _) = pares(|par| colisoes(ordenadas.into_iter().map(Ok), true, par));
+      assert_eq!(em_ordem, todos);
+
+      // assim como os blocos intercalados de qualquer tamanho
+      for bloco in [0, 1, 3, 16, n].iter() {
+        let (blocos, resumo) = pares(|par| colisoes_em_blocos(LeitorCsv::new(texto.as_bytes()), *bloco, par));
+        assert_eq!(blocos, todos, "bloco de {}", bloco);
+        assert_eq!(resumo.formas, n);
+      }
+    }
+  }
+
+
+  #[test]
+  fn particoes_preservam_as_formas() {
+    let texto = "\
+bloco,b,1,1,3,3
+orientado,o,0.1,0.2,1.5,0.25,0.7853981633974483
+circulo,c,0,0,2.5
+poligono,id com espaços,0,0,4,0,0,4
+segmento,s,-2,5,7,-1
+";
+    let mut bloco: Vec<(f64, Registro)> = registros(texto).into_iter().map(|r| (0.0, r)).collect();
+    let originais: Vec<Registro> = bloco.iter().map(|(_, r)| r.clone()).collect();
+
+    let mut particao = Particao::grava(&mut bloco).unwrap();
+    assert!(bloco.is_empty());
+    let caminho = particao.caminho.clone();
+    assert!(caminho.exists());
+
+    for original in originais.iter() {
+      let lido = particao.le().unwrap().unwrap();
+      assert_eq!(lido.linha, original.linha);
+      assert_eq!(lido.id, original.id);
+      assert_eq!(format!("{:?}", lido.forma), format!("{:?}", original.forma));
+    }
+    assert!(particao.le().unwrap().is_none());
+
+    drop(particao);
+    assert!(!caminho.exists());
+  }
+
+
+  #[test]
+  fn arquivos_existentes_nao_sao_sobrescritos() {
+    // arquivos alheios nos próximos nomes da sequência
+    let proximo = PARTICOES.load(Atomica::Relaxed);
+    let alheios: Vec<PathBuf> = (proximo..proximo + 10)
+      .map(|n| std::env::temp_dir().join(format!("jeotry-lote-{}-{}.txt", std::process::id(), n)))
+      .filter(|caminho| OpenOptions::new().write(true).create_new(true).open(caminho).is_ok())
+      .collect();
+    for caminho in alheios.iter() {
+      std::fs::write(caminho, "alheio").unwrap();
+    }
+
+    let mut bloco: Vec<(f64, Registro)> = registros("bloco,b,1,1,3,3\n").into_iter().map(|r| (0.0, r)).collect();
+    let mut particao = Particao::grava(&mut bloco).unwrap();
+    assert!(!alheios.contains(&particao.caminho));
+    assert_eq!(particao.le().unwrap().unwrap().id, "b");
+
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let modo = std::fs::metadata(&particao.caminho).unwrap().permissions().mode();
+      assert_eq!(modo & 0o777, 0o600);
+    }
+
+    drop(particao);
+    for caminho in alheios.iter() {
+      assert_eq!(std::fs::read_to_string(caminho).unwrap(), "alheio");
+      std::fs::remove_file(caminho).unwrap();
+    }
+  }
+
+
+  #[test]
+  fn entrada_fora_de_ordem_e_um_erro() {
+    let texto = "\
+bloco,a,0,0,2,2
+bloco,b,5,0,6,1
+circulo,c,4,0,2
+";
+    let erro = colisoes(LeitorCsv::new(texto.as_bytes()), true, |_, _| Ok(())).unwrap_err();
+    match erro {
+      ErroCena::Sintaxe { linha, mensagem } => {
+        assert_eq!(linha, 3);
+        assert_eq!(mensagem, "'c' fora de ordem, a borda esquerda 2 é menor que 5");
+      },
+      e => panic!("erro inesperado {:?}", e),
+    }
+
+    // sem exigir a ordem os pares são encontrados
+    let (todos, _) = pares(|par| colisoes(LeitorCsv::new(texto.as_bytes()), false, par));
+    assert_eq!(todos, vec![("a".to_string(), "c".to_string()), ("c".to_string(), "b".to_string())]);
+
+    // bordas iguais não estão fora de ordem
+    let iguais = "bloco,a,0,0,1,1\nbloco,b,0,5,1,6\n";
+    assert!(colisoes(LeitorCsv::new(iguais.as_bytes()), true, |_, _| Ok(())).is_ok());
+  }
+
+
+  #[test]
+  fn linhas_invalidas_indicam_a_linha() {
+    let casos = [
+      ("bloco,b,1,1,3\n", 1),
+      ("# comentário\n\nbloco,,1,1,3,3\n", 3),
+      ("tipo,id\nvetor,v,1,2\n", 2),
+    ];
+    for (texto, linha_esperada) in casos.iter() {
+      match colisoes(LeitorCsv::new(texto.as_bytes()), false, |_, _| Ok(())) {
+        Err(ErroCena::Sintaxe { linha, .. }) => assert_eq!(linha, *linha_esperada, "{}", texto),
+        r => panic!("{:?}: {:?}", texto, r.map(|r| r.pares)),
+      }
+    }
+  }
+}
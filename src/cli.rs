@@ -0,0 +1,500 @@
+/// Interface de linha de comando do executável.
+///
+/// Cada subcomando recebe os objetos como argumentos, no mesmo
+/// formato das linhas de uma cena com `:` após o tipo e os
+/// valores separados por vírgulas, como em `bloco:1,1,3,3` ou
+/// `circulo:3,3,1.5`. Com `--cena arquivo` os objetos também
+/// podem ser referenciados pelo nome.
+///
+/// A saída padrão é apenas o valor do resultado, `--json` a
+/// troca por um objeto JSON. Erros vão para a saída de erro.
+pub use jeotry::object_data::{
+    Vetor as Vetor,
+    Segmento as Segmento,
+    Forma as Forma,
+};
+use jeotry::vector_data::inner_angle;
+use std::convert::TryFrom;
+use jeotry::scene_data::{
+    Cena,
+    Elemento,
+    le_elemento,
+};
+use super::repl::Repl;
+use super::rpc::Servidor;
+use super::json_data::escapa;
+use super::server::{
+    Cliente,
+    Endereco,
+    Servico,
+    ENDERECO_PADRAO,
+};
+use jeotry::batch_data::{
+    self,
+    LeitorCsv,
+};
+use std::io::{self, BufRead, BufWriter, Write};
+
+
+/// Código de saída de sucesso, ou de uma colisão encontrada
+pub static SUCESSO: i32 = 0;
+
+/// Código de saída quando não há colisão ou o serviço
+/// responde com um erro
+pub static FALSO: i32 = 1;
+
+/// Código de saída de argumentos inválidos
+pub static ERRO: i32 = 2;
+
+/// Texto de ajuda
+pub static USO: &str = "\
+uso: jeotry <comando> [opções] [objetos]
+
+comandos:
+  collide  <a> <b>     analisa a colisão entre dois objetos (saída 0 ou 1)
+  distance <a> <b>     menor distância entre dois objetos
+  angle    <v> [<u>]   ângulo do vetor, ou entre dois vetores, em radianos
+  area     <a>         área de um objeto
+  batch    [arquivo]   todos os pares que colidem entre as formas de um CSV,
+                       lido da entrada padrão sem arquivo ou com '-'
+  rpc                  servidor JSON-RPC 2.0, uma requisição por linha na
+                       entrada padrão e uma resposta por linha na saída
+  serve                serviço de consultas compartilhado, por TCP local ou
+                       socket Unix, com um comando por linha
+  client   [comando]   envia um comando ao serviço, ou as linhas da entrada
+  repl                 terminal interativo com variáveis, ':ajuda' lista a sintaxe
+  help                 mostra esta ajuda
+
+opções:
+  --json               resultado em JSON
+  --cena <arquivo>     permite referenciar objetos da cena pelo nome, ou os
+                       define como variáveis no repl e no rpc
+  --ordenado           no batch, a entrada já está ordenada pela borda esquerda
+                       e é processada durante a leitura
+  --tcp <endereço>     endereço local do serviço, o padrão é 127.0.0.1:7878
+  --unix <caminho>     socket Unix do serviço
+
+objetos:
+  ponto:x,y  vetor:x,y  bloco:x1,y1,x2,y2  quad:x,y,tx,ty  circulo:x,y,r
+  orientado:cx,cy,hx,hy,ângulo  segmento:x1,y1,x2,y2  poligono:x,y,x,y,...";
+
+
+// Estruturas
+/// Opções comuns a todos os comandos
+struct Opcoes {
+  /// Resultado em JSON
+  json: bool,
+
+  /// Cena para referências por nome
+  cena: Option<Cena>,
+
+  /// Entrada do lote ordenada pela borda esquerda
+  ordenado: bool,
+
+  /// Endereço do serviço
+  endereco: Option<Endereco>
+}
+
+
+// Funções
+/// Executa os argumentos, sem o nome do programa, e retorna
+/// o código de saída
+pub fn executa( args: &[String]) -> i32 {
+  match roda(args, &mut io::stdout().lock()) {
+    Ok(codigo) => codigo,
+    Err(e)     => {
+      eprintln!("jeotry: {}", e);
+      eprintln!("use 'jeotry help' para ver os comandos");
+      ERRO
+    },
+  }
+}
+
+
+/// Interpreta os argumentos e executa o comando, escrevendo
+/// os resultados na saída dada
+fn roda<W: Write>( args: &[String], saida: &mut W) -> Result<i32, String> {
+  let mut opcoes = Opcoes {
+    json: false,
+    cena: None,
+    ordenado: false,
+    endereco: None
+  };
+
+  let mut posicionais = Vec::new();
+  let mut iter = args.iter();
+  while let Some(a) = iter.next() {
+    match a.as_str() {
+      "--json"           => opcoes.json = true,
+      "--ordenado"       => opcoes.ordenado = true,
+      "--cena"           => {
+        let caminho = iter.next().ok_or("--cena precisa de um arquivo")?;
+        let cena = Cena::carregar(caminho).map_err(|e| format!("{}: {}", caminho, e))?;
+        opcoes.cena = Some(cena);
+      },
+      "--tcp"            => {
+        let e = iter.next().ok_or("--tcp precisa de um endereço")?;
+        opcoes.endereco = Some(Endereco::Tcp(e.clone()));
+      },
+      "--unix"           => {
+        let caminho = iter.next().ok_or("--unix precisa de um caminho")?;
+        opcoes.endereco = Some(Endereco::Unix(caminho.into()));
+      },
+      "-h" | "--help"    => {
+        writeln!(saida, "{}", USO).map_err(|e| e.to_string())?;
+        return Ok(SUCESSO);
+      },
+      o if o.starts_with("--") => return Err(format!("opção desconhecida '{}'", o)),
+      _                  => posicionais.push(a.as_str()),
+    }
+  }
+
+  let (comando, resto) = match posicionais.split_first() {
+    Some(c) => c,
+    None    => return Err("nenhum comando informado".to_string()),
+  };
+
+  match *comando {
+    "collide" => {
+      let [a, b] = argumentos::<2>(comando, resto)?;
+      let a = opcoes.forma(a)?;
+      let b = opcoes.forma(b)?;
+      let colide = a.collide(&b);
+      opcoes.imprime(saida, comando, &colide.to_string(), &[])?;
+
+      if colide {
+        return Ok(SUCESSO);
+      }
+      return Ok(FALSO);
+    },
+
+    "distance" => {
+      let [a, b] = argumentos::<2>(comando, resto)?;
+      let d = opcoes.forma(a)?.distance(&opcoes.forma(b)?);
+      opcoes.imprime(saida, comando, &numero(d), &[])?;
+    },
+
+    "angle" => {
+      let â = match resto {
+        [v]    => angulo(opcoes.vetor(v)?, None)?,
+        [v, u] => angulo(opcoes.vetor(v)?, Some(opcoes.vetor(u)?))?,
+        _      => return Err(format!("'{}' espera um ou dois vetores", comando)),
+      };
+
+      let graus = numero(â.to_degrees());
+      opcoes.imprime(saida, comando, &numero(â), &[("graus", graus)])?;
+    },
+
+    "area" => {
+      let [a] = argumentos::<1>(comando, resto)?;
+      let area = opcoes.forma(a)?.area();
+      opcoes.imprime(saida, comando, &numero(area), &[])?;
+    },
+
+    "batch" => {
+      let resumo = match resto {
+        [] | ["-"] => opcoes.lote(io::stdin().lock(), saida)?,
+        [caminho]  => {
+          let arquivo = std::fs::File::open(caminho).map_err(|e| format!("{}: {}", caminho, e))?;
+          opcoes.lote(io::BufReader::new(arquivo), saida)?
+        },
+        _          => return Err("'batch' espera no máximo um arquivo".to_string()),
+      };
+
+      eprintln!("{} formas, {} pares, {} testes", resumo.formas, resumo.pares, resumo.testes);
+    },
+
+    "rpc" => {
+      argumentos::<0>(comando, resto)?;
+      let mut servidor = Servidor::from_cena(opcoes.cena.take().unwrap_or_default());
+      servidor.executa(io::stdin().lock(), &mut *saida).map_err(|e| e.to_string())?;
+    },
+
+    "serve" => {
+      argumentos::<0>(comando, resto)?;
+      let endereco = opcoes.endereco.take().unwrap_or_else(|| Endereco::Tcp(ENDERECO_PADRAO.to_string()));
+      let servico = Servico::new(opcoes.cena.take().unwrap_or_default());
+      servico.escuta(&endereco, |e| eprintln!("escutando em {}", e)).map_err(|e| e.to_string())?;
+    },
+
+    "client" => {
+      let endereco = opcoes.endereco.take().unwrap_or_else(|| Endereco::Tcp(ENDERECO_PADRAO.to_string()));
+      let mut cliente = Cliente::conecta(&endereco).map_err(|e| format!("{:?}: {}", endereco, e))?;
+
+      // um único comando nos argumentos
+      if !resto.is_empty() {
+        let resposta = cliente.pergunta(&resto.join(" ")).map_err(|e| e.to_string())?;
+        writeln!(saida, "{}", resposta).map_err(|e| e.to_string())?;
+        if resposta.starts_with("ok") {
+          return Ok(SUCESSO);
+        }
+        return Ok(FALSO);
+      }
+
+      for linha in io::stdin().lock().lines() {
+        let linha = linha.map_err(|e| e.to_string())?;
+        if linha.trim().is_empty() {
+          continue;
+        }
+        let resposta = cliente.pergunta(&linha).map_err(|e| e.to_string())?;
+        writeln!(saida, "{}", resposta).map_err(|e| e.to_string())?;
+      }
+    },
+
+    "repl" => {
+      argumentos::<0>(comando, resto)?;
+      let mut repl = Repl::new();
+      if let Some(cena) = opcoes.cena.as_ref() {
+        repl.carregar_cena(cena);
+      }
+
+      repl.executa(io::stdin().lock(), &mut *saida).map_err(|e| e.to_string())?;
+    },
+
+    "help" => writeln!(saida, "{}", USO).map_err(|e| e.to_string())?,
+
+    c => return Err(format!("comando desconhecido '{}'", c)),
+  }
+
+  return Ok(SUCESSO);
+}
+
+
+/// Confere a quantidade de objetos de um comando
+fn argumentos<'a, const N: usize>( comando: &str, resto: &[&'a str]) -> Result<[&'a str; N], String> {
+  match <[&str; N]>::try_from(resto) {
+    Ok(a)  => Ok(a),
+    Err(_) => Err(format!("'{}' espera {} objeto(s), recebeu {}", comando, N, resto.len())),
+  }
+}
+
+
+/// Ângulo de um vetor, ou entre dois vetores
+fn angulo( v: Vetor, u: Option<Vetor>) -> Result<f64, String> {
+  let nulo = |v: Vetor| v.x == 0 && v.y == 0;
+  if nulo(v) || u.is_some_and(nulo) {
+    return Err("vetores nulos não possuem ângulo".to_string());
+  }
+
+  match u {
+    Some(u) => Ok(inner_angle(v, u) as f64),
+    None    => Ok(v.angle() as f64),
+  }
+}
+
+
+/// Formata um número real, sem valores especiais do JSON
+fn numero( v: f64) -> String {
+  if v.is_finite() {
+    return v.to_string();
+  }
+
+  return "null".to_string();
+}
+
+
+/// Escreve um campo do CSV, entre aspas quando contém vírgulas,
+/// aspas ou quebras de linha, com as aspas dobradas (RFC 4180)
+pub fn campo_csv( campo: &str) -> String {
+  if campo.contains([',', '"', '\r', '\n']) {
+    return format!("\"{}\"", campo.replace('"', "\"\""));
+  }
+
+  return campo.to_string();
+}
+
+
+// Métodos relacionados às opções
+impl Opcoes {
+
+
+  /// Lê um elemento escrito como argumento ou pelo nome na cena
+  fn elemento( &self, arg: &str) -> Result<Elemento, String> {
+    if let Some((tipo, valores)) = arg.split_once(':') {
+      let valores: Vec<&str> = valores.split(',').map(str::trim).collect();
+      return le_elemento(tipo, &valores).map_err(|e| format!("'{}': {}", arg, e));
+    }
+
+    match self.cena.as_ref().and_then(|c| c.get(arg)) {
+      Some(e) => Ok(e.clone()),
+      None    => Err(format!("objeto inválido '{}'", arg)),
+    }
+  }
+
+
+  /// Lê uma forma, pontos são tratados como segmentos sem comprimento
+  fn forma( &self, arg: &str) -> Result<Forma, String> {
+    match self.elemento(arg)? {
+      Elemento::Forma(f) => Ok(f),
+      Elemento::Ponto(p) => Ok(Forma::Segmento(Segmento::from(p, p))),
+      Elemento::Vetor(_) => Err(format!("'{}' é um vetor, não uma forma", arg)),
+    }
+  }
+
+
+  /// Lê um vetor, que também pode ser escrito apenas como `x,y`
+  fn vetor( &self, arg: &str) -> Result<Vetor, String> {
+    if !arg.contains(':') && arg.contains(',') {
+      return self.vetor(&format!("vetor:{}", arg));
+    }
+
+    match self.elemento(arg)? {
+      Elemento::Vetor(v) => Ok(v),
+      _                  => Err(format!("'{}' não é um vetor", arg)),
+    }
+  }
+
+
+  /// Escreve os pares que colidem em CSV, ou uma linha JSON
+  /// por par
+  fn lote<R: BufRead, W: Write>( &self, entrada: R, saida: &mut W) -> Result<batch_data::Resumo, String> {
+    let mut saida = BufWriter::new(saida);
+    if !self.json {
+      writeln!(saida, "a,b").map_err(|e| e.to_string())?;
+    }
+
+    let json = self.json;
+    let resumo = batch_data::colisoes(LeitorCsv::new(entrada), self.ordenado, |a, b| {
+      if json {
+        writeln!(saida, "{{\"a\":{},\"b\":{}}}", escapa(&a.id), escapa(&b.id))
+      } else {
+        writeln!(saida, "{},{}", campo_csv(&a.id), campo_csv(&b.id))
+      }
+    }).map_err(|e| e.to_string())?;
+
+    saida.flush().map_err(|e| e.to_string())?;
+    return Ok(resumo);
+  }
+
+
+  /// Imprime o resultado, com campos extras apenas no JSON
+  fn imprime<W: Write>( &self, saida: &mut W, comando: &str, resultado: &str, extras: &[(&str, String)]) -> Result<(), String> {
+    if !self.json {
+      return writeln!(saida, "{}", resultado).map_err(|e| e.to_string());
+    }
+
+    let mut campos = vec![
+      format!("{}:{}", escapa("comando"), escapa(comando)),
+      format!("{}:{}", escapa("resultado"), resultado),
+    ];
+    for (chave, valor) in extras.iter() {
+      campos.push(format!("{}:{}", escapa(chave), valor));
+    }
+
+    writeln!(saida, "{{{}}}", campos.join(",")).map_err(|e| e.to_string())
+  }
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+
+
+  #[test]
+  fn campos_csv_seguem_a_rfc_4180() {
+    assert_eq!(campo_csv("b1"), "b1");
+    assert_eq!(campo_csv("a b"), "a b");
+    assert_eq!(campo_csv("a,b"), "\"a,b\"");
+    assert_eq!(campo_csv("diz \"oi\""), "\"diz \"\"oi\"\"\"");
+    assert_eq!(campo_csv("\""), "\"\"\"\"");
+    assert_eq!(campo_csv("linha\nnova"), "\"linha\nnova\"");
+    assert_eq!(campo_csv("fim\r"), "\"fim\r\"");
+    assert_eq!(campo_csv(""), "");
+  }
+
+
+  /// Roda os argumentos guardando a saída, os erros recebem o
+  /// código que `executa` retornaria
+  fn roda_texto( args: &[&str]) -> (i32, String) {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    let mut saida = Vec::new();
+    let codigo = roda(&args, &mut saida).unwrap_or(ERRO);
+    return (codigo, String::from_utf8(saida).unwrap());
+  }
+
+
+  /// Grava um arquivo temporário para os comandos que os leem
+  fn temporario( nome: &str, conteudo: &str) -> std::path::PathBuf {
+    let caminho = std::env::temp_dir().join(format!("jeotry-cli-{}-{}", std::process::id(), nome));
+    std::fs::write(&caminho, conteudo).unwrap();
+    return caminho;
+  }
+
+
+  #[test]
+  fn comandos_escrevem_o_resultado() {
+    // colisão sai com 0 e a ausência dela com 1
+    assert_eq!(roda_texto(&["collide", "bloco:0,0,2,2", "circulo:3,1,1.5"]), (SUCESSO, "true\n".to_string()));
+    assert_eq!(roda_texto(&["collide", "bloco:0,0,1,1", "bloco:5,5,6,6"]), (FALSO, "false\n".to_string()));
+    assert_eq!(roda_texto(&["distance", "bloco:0,0,1,1", "ponto:4,5"]), (SUCESSO, "5\n".to_string()));
+    assert_eq!(roda_texto(&["area", "quad:1,1,3,2"]), (SUCESSO, "6\n".to_string()));
+    assert_eq!(roda_texto(&["angle", "1,0", "vetor:1,0"]), (SUCESSO, "0\n".to_string()));
+    assert_eq!(roda_texto(&["help"]), (SUCESSO, format!("{}\n", USO)));
+    assert_eq!(roda_texto(&["collide", "--help"]), (SUCESSO, format!("{}\n", USO)));
+
+    // a opção pode vir em qualquer posição
+    assert_eq!(
+      roda_texto(&["collide", "--json", "bloco:0,0,1,1", "bloco:5,5,6,6"]),
+      (FALSO, "{\"comando\":\"collide\",\"resultado\":false}\n".to_string())
+    );
+    assert_eq!(
+      roda_texto(&["--json", "angle", "0,1", "0,1"]),
+      (SUCESSO, "{\"comando\":\"angle\",\"resultado\":0,\"graus\":0}\n".to_string())
+    );
+    assert_eq!(
+      roda_texto(&["--json", "area", "bloco:0,0,2,3"]),
+      (SUCESSO, "{\"comando\":\"area\",\"resultado\":6}\n".to_string())
+    );
+
+    // objetos pelo nome na cena
+    let cena = temporario("cena.txt", "bloco b 0 0 2 2\nponto p 1 1\nponto longe 9 9\n");
+    let caminho = cena.to_str().unwrap();
+    assert_eq!(roda_texto(&["--cena", caminho, "collide", "b", "p"]), (SUCESSO, "true\n".to_string()));
+    assert_eq!(roda_texto(&["--cena", caminho, "collide", "longe", "b"]), (FALSO, "false\n".to_string()));
+    std::fs::remove_file(&cena).unwrap();
+  }
+
+
+  #[test]
+  fn lote_em_csv_e_json() {
+    let csv = temporario("lote.csv", "tipo,id,valores\nbloco,a,0,0,2,2\nbloco,di\"z,1,1,3,3\ncirculo,c,10,10,1\nbloco,d,9,9,10,10\n");
+    let caminho = csv.to_str().unwrap();
+
+    let (codigo, texto) = roda_texto(&["batch", caminho]);
+    let mut linhas: Vec<&str> = texto.lines().collect();
+    linhas[1..].sort_unstable();
+    assert_eq!((codigo, linhas), (SUCESSO, vec!["a,b", "a,\"di\"\"z\"", "c,d"]));
+
+    let (codigo, texto) = roda_texto(&["batch", "--json", caminho]);
+    let mut linhas: Vec<&str> = texto.lines().collect();
+    linhas.sort_unstable();
+    assert_eq!((codigo, linhas), (SUCESSO, vec!["{\"a\":\"a\",\"b\":\"di\\\"z\"}", "{\"a\":\"c\",\"b\":\"d\"}"]));
+    std::fs::remove_file(&csv).unwrap();
+  }
+
+
+  #[test]
+  fn erros_de_uso_saem_com_codigo_2() {
+    let casos: [&[&str]; 11] = [
+      &[],
+      &["--json"],
+      &["voa"],
+      &["--verboso", "area", "bloco:0,0,1,1"],
+      &["collide", "bloco:0,0,1,1"],
+      &["collide", "bloco:0,0,1", "bloco:0,0,1,1"],
+      &["area", "vetor:1,2"],
+      &["angle", "0,0"],
+      &["angle", "ponto:1,1"],
+      &["--cena"],
+      &["batch", "/caminho/que/nao/existe.csv"],
+    ];
+    for args in casos.iter() {
+      assert_eq!(roda_texto(args), (ERRO, String::new()), "{:?}", args);
+    }
+
+    // nomes sem cena não são objetos
+    let args = vec!["collide".to_string(), "b".to_string(), "p".to_string()];
+    assert_eq!(roda(&args, &mut Vec::new()), Err("objeto inválido 'b'".to_string()));
+  }
+}
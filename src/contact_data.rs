@@ -0,0 +1,331 @@
+/// Rastreamento de contatos entre objetos identificados.
+///
+/// O rastreador recebe, a cada quadro, os pares de objetos que
+/// colidem naquele instante e os compara com o quadro anterior,
+/// emitindo eventos de entrada, permanência e saída de contato.
+///
+/// Cada objeto pode receber uma camada e uma máscara de colisão,
+/// os pares cujas camadas não se aceitam são descartados antes
+/// mesmo de serem testados.
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+
+/// Identificador de um objeto dentro do rastreador
+pub type Id = u32;
+
+
+// Estruturas
+/// Camada e máscara de colisão de um objeto.
+///
+/// Dois objetos só são testados quando a camada de cada um
+/// está presente na máscara do outro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Camada {
+  /// Bits das camadas às quais o objeto pertence
+  pub camada: u32,
+
+  /// Bits das camadas com as quais o objeto colide
+  pub mascara: u32
+}
+
+
+/// Evento de contato entre dois objetos, o par é sempre
+/// normalizado com o menor identificador à esquerda.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contato {
+  /// O contato começou neste quadro
+  Entrada(Id, Id),
+
+  /// O contato já existia no quadro anterior e continua
+  Permanencia(Id, Id),
+
+  /// O contato existia no quadro anterior e terminou
+  Saida(Id, Id)
+}
+
+
+/// Rastreador de contatos entre quadros.
+///
+/// Objetos sem camada registrada pertencem à primeira camada
+/// e colidem com todas as outras.
+#[derive(Debug, Clone, Default)]
+pub struct Rastreador {
+  /// Camadas registradas por objeto
+  camadas: BTreeMap<Id, Camada>,
+
+  /// Pares em contato no último quadro
+  ativos: BTreeSet<(Id, Id)>
+}
+
+
+// Métodos relacionados à camada
+impl Camada {
+
+
+  /// Gera uma nova camada através de seus bits e da máscara
+  pub fn new( camada: u32, mascara: u32) -> Self {
+    Self {
+      camada,
+      mascara
+    }
+  }
+
+
+  /// Analisa se dois objetos destas camadas podem colidir
+  pub fn aceita( self, c: Camada) -> bool {
+    self.camada & c.mascara != 0 &&
+    c.camada & self.mascara != 0
+  }
+}
+
+impl Default for Camada {
+  fn default() -> Self {
+    Camada::new(1, u32::MAX)
+  }
+}
+
+
+// Métodos relacionados ao contato
+impl Contato {
+
+
+  /// Extrai o par de objetos do evento
+  pub fn par( self ) -> (Id, Id) {
+    match self {
+      Contato::Entrada(a, b)     => (a, b),
+      Contato::Permanencia(a, b) => (a, b),
+      Contato::Saida(a, b)       => (a, b),
+    }
+  }
+}
+
+
+// Métodos relacionados ao rastreador
+impl Rastreador {
+
+
+  /// Gera um novo rastreador sem contatos
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+
+  /// Define a camada e a máscara de um objeto
+  pub fn definir( &mut self, id: Id, c: Camada) {
+    self.camadas.insert(id, c);
+  }
+
+
+  /// Retorna a camada de um objeto
+  pub fn camada( &self, id: Id) -> Camada {
+    match self.camadas.get(&id) {
+      Some(c) => *c,
+      None    => Camada::default(),
+    }
+  }
+
+
+  /// Remove um objeto do rastreador, retornando a saída
+  /// de todos os contatos em que ele estava presente
+  pub fn remover( &mut self, id: Id) -> Vec<Contato> {
+    self.camadas.remove(&id);
+
+    let saidas: Vec<(Id, Id)> = self.ativos.iter()
+      .filter(|(a, b)| *a == id || *b == id)
+      .copied()
+      .collect();
+
+    let mut eventos = Vec::with_capacity(saidas.len());
+    for par in saidas {
+      self.ativos.remove(&par);
+      eventos.push(Contato::Saida(par.0, par.1));
+    }
+
+    return eventos;
+  }
+
+
+  /// Analisa se o par de objetos deve ser testado
+  pub fn testa( &self, a: Id, b: Id) -> bool {
+    if a == b {
+      return false;
+    }
+
+    return self.camada(a).aceita(self.camada(b));
+  }
+
+
+  /// Analisa se dois objetos estavam em contato no último quadro
+  pub fn em_contato( &self, a: Id, b: Id) -> bool {
+    self.ativos.contains(&normaliza(a, b))
+  }
+
+
+  /// Retorna os pares em contato no último quadro
+  pub fn contatos( &self ) -> impl Iterator<Item = (Id, Id)> + '_ {
+    self.ativos.iter().copied()
+  }
+
+
+  /// Consome os pares em colisão de um quadro e retorna os
+  /// eventos gerados, pares filtrados pelas camadas são ignorados.
+  ///
+  /// Os eventos de entrada e permanência vêm primeiro, em ordem
+  /// de par, seguidos pelos de saída.
+  pub fn atualizar<I>( &mut self, pares: I) -> Vec<Contato>
+  where
+    I: IntoIterator<Item = (Id, Id)>
+  {
+    let mut atual = BTreeSet::new();
+    for (a, b) in pares {
+      if self.testa(a, b) {
+        atual.insert(normaliza(a, b));
+      }
+    }
+
+    let mut eventos = Vec::new();
+    for &(a, b) in atual.iter() {
+      if self.ativos.contains(&(a, b)) {
+        eventos.push(Contato::Permanencia(a, b));
+      } else {
+        eventos.push(Contato::Entrada(a, b));
+      }
+    }
+
+    for &(a, b) in self.ativos.difference(&atual) {
+      eventos.push(Contato::Saida(a, b));
+    }
+
+    self.ativos = atual;
+    return eventos;
+  }
+
+
+  /// Testa todos os pares de objetos aceitos pelas camadas
+  /// através da função de colisão e atualiza o quadro
+  pub fn detectar<T, F>( &mut self, objetos: &[(Id, T)], colide: F) -> Vec<Contato>
+  where
+    T: Copy,
+    F: Fn(T, T) -> bool
+  {
+    let mut pares = Vec::new();
+    for (i, &(a, oa)) in objetos.iter().enumerate() {
+      for &(b, ob) in objetos[i + 1..].iter() {
+        if self.testa(a, b) && colide(oa, ob) {
+          pares.push((a, b));
+        }
+      }
+    }
+
+    return self.atualizar(pares);
+  }
+}
+
+
+/// Ordena o par com o menor identificador à esquerda
+fn normaliza( a: Id, b: Id) -> (Id, Id) {
+  if a > b {
+    return (b, a);
+  }
+
+  return (a, b);
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use alloc::vec;
+
+
+  #[test]
+  fn entrada_permanencia_e_saida_entre_quadros() {
+    let mut r = Rastreador::new();
+    assert_eq!(r.atualizar(vec![(2, 1)]), vec![Contato::Entrada(1, 2)]);
+    assert!(r.em_contato(2, 1) && r.em_contato(1, 2));
+
+    // pares repetidos ou invertidos contam uma vez
+    assert_eq!(
+      r.atualizar(vec![(1, 2), (2, 1), (3, 1), (1, 3)]),
+      vec![Contato::Permanencia(1, 2), Contato::Entrada(1, 3)]
+    );
+    assert_eq!(
+      r.atualizar(vec![(3, 1), (4, 5)]),
+      vec![Contato::Permanencia(1, 3), Contato::Entrada(4, 5), Contato::Saida(1, 2)]
+    );
+    assert_eq!(r.contatos().collect::<Vec<_>>(), vec![(1, 3), (4, 5)]);
+
+    // um quadro vazio encerra todos, e o seguinte não gera nada
+    assert_eq!(r.atualizar(Vec::new()), vec![Contato::Saida(1, 3), Contato::Saida(4, 5)]);
+    assert!(r.atualizar(Vec::new()).is_empty());
+
+    // o contato que volta é uma nova entrada
+    assert_eq!(r.atualizar(vec![(1, 2)]), vec![Contato::Entrada(1, 2)]);
+    assert_eq!(Contato::Saida(1, 2).par(), (1, 2));
+  }
+
+
+  #[test]
+  fn remover_encerra_os_contatos_do_objeto() {
+    let mut r = Rastreador::new();
+    r.definir(2, Camada::new(0b11, u32::MAX));
+    r.atualizar(vec![(1, 2), (2, 3), (3, 4)]);
+    assert!(r.em_contato(1, 2));
+
+    assert_eq!(r.remover(3), vec![Contato::Saida(2, 3), Contato::Saida(3, 4)]);
+    assert!(r.remover(3).is_empty());
+    assert_eq!(r.remover(2), vec![Contato::Saida(1, 2)]);
+    assert_eq!(r.camada(2), Camada::default());
+    assert_eq!(r.atualizar(vec![(1, 2)]), vec![Contato::Entrada(1, 2)]);
+  }
+
+
+  #[test]
+  fn camadas_e_mascaras_filtram_os_pares() {
+    // jogador, inimigo, tiro do jogador e cenário
+    let (jogador, inimigo, tiro, cenario) = (1, 2, 3, 4);
+    let mut r = Rastreador::new();
+    r.definir(jogador, Camada::new(0b0001, 0b1010));
+    r.definir(inimigo, Camada::new(0b0010, 0b1101));
+    r.definir(tiro, Camada::new(0b0100, 0b1010));
+
+    // a camada de cada um precisa estar na máscara do outro
+    assert!(r.testa(jogador, inimigo));
+    assert!(r.testa(tiro, inimigo));
+    assert!(!r.testa(jogador, tiro));
+    assert!(!r.testa(inimigo, inimigo));
+
+    // sem camada registrada fica na primeira camada e colide
+    // apenas com quem a tem na máscara
+    assert_eq!(r.camada(cenario), Camada::new(1, u32::MAX));
+    assert!(!r.testa(cenario, jogador) && r.testa(cenario, inimigo) && !r.testa(cenario, tiro));
+    r.definir(tiro, Camada::new(0b0100, 0));
+    assert!(!r.testa(tiro, inimigo));
+
+    let pares = vec![(jogador, tiro), (tiro, inimigo), (inimigo, jogador), (cenario, tiro), (inimigo, cenario)];
+    assert_eq!(r.atualizar(pares), vec![Contato::Entrada(1, 2), Contato::Entrada(2, 4)]);
+    assert!(!Camada::new(1, 0).aceita(Camada::new(1, u32::MAX)));
+  }
+
+
+  #[test]
+  fn detectar_acompanha_objetos_em_movimento() {
+    // objetos em uma reta, em contato quando estão a até uma
+    // unidade de distância
+    let colide = |a: i32, b: i32| (a - b).abs() <= 1;
+    let mut r = Rastreador::new();
+    // o objeto 3 fica em uma camada que ignora os outros
+    r.definir(3, Camada::new(2, 2));
+
+    let quadros = [
+      (vec![(1, 0), (2, 5), (3, 0)], vec![]),
+      (vec![(1, 3), (2, 4), (3, 3)], vec![Contato::Entrada(1, 2)]),
+      (vec![(1, 4), (2, 4), (3, 4)], vec![Contato::Permanencia(1, 2)]),
+      (vec![(1, 9), (2, 4), (3, 4)], vec![Contato::Saida(1, 2)]),
+    ];
+    for (objetos, esperado) in quadros.iter() {
+      assert_eq!(&r.detectar(objetos, colide), esperado);
+    }
+  }
+}
@@ -0,0 +1,973 @@
+/// Triangulação de Delaunay de conjuntos de pontos, com arestas
+/// restritas e adjacência entre triângulos.
+///
+/// Os pontos são inseridos em ordem lexicográfica, cada um fora
+/// da envoltória atual: o ponto se liga às arestas visíveis da
+/// envoltória e as arestas ilegais são trocadas (flips de
+/// Lawson). Orientação e teste do círculo são exatos, calculados
+/// com inteiros de 128 e 256 bits em todo o intervalo de `i32`.
+///
+/// Triângulos são triplas de índices dos pontos originais em
+/// sentido anti-horário. Pontos repetidos usam o índice da
+/// primeira ocorrência e pontos todos colineares não geram
+/// triângulos.
+///
+/// Arestas restritas são inseridas depois da triangulação,
+/// trocando as arestas que as cruzam (algoritmo de Sloan) e
+/// refazendo a condição de Delaunay ao redor delas.
+pub use super::vector_data::Ponto as Ponto;
+use super::hull_data::orientacao;
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+
+// Estruturas
+/// Triangulação de Delaunay restrita
+#[derive(Debug, Clone, Default)]
+pub struct Triangulacao {
+  /// Pontos originais
+  pontos: Vec<Ponto>,
+
+  /// Vértices de cada triângulo em sentido anti-horário
+  triangulos: Vec<[usize; 3]>,
+
+  /// Triângulo vizinho oposto a cada vértice
+  vizinhos: Vec<[Option<usize>; 3]>,
+
+  /// Um triângulo que contém cada ponto, na envoltória o que
+  /// contém a aresta até o próximo ponto dela
+  triangulo_de: Vec<Option<usize>>,
+
+  /// Índice da primeira ocorrência de cada ponto
+  representante: Vec<usize>,
+
+  /// Arestas restritas, com o menor índice primeiro
+  restritas: BTreeSet<(usize, usize)>
+}
+
+
+/// Inteiro de 256 bits em complemento de dois, só o necessário
+/// para somar produtos de `i128` e ler o sinal
+#[derive(Debug, Clone, Copy)]
+struct Largo {
+  alto: i128,
+  baixo: u128
+}
+
+
+// Métodos relacionados à triangulação
+impl Triangulacao {
+
+
+  /// Triangula os pontos
+  pub fn new( pontos: &[Ponto]) -> Self {
+    let n = pontos.len();
+    let mut t = Self {
+      pontos: pontos.to_vec(),
+      triangulos: Vec::new(),
+      vizinhos: Vec::new(),
+      triangulo_de: alloc::vec![None; n],
+      representante: (0..n).collect(),
+      restritas: BTreeSet::new()
+    };
+
+    // ordem lexicográfica, repetidos apontam para o primeiro
+    let mut ordem: Vec<usize> = (0..n).collect();
+    ordem.sort_by_key(|&i| (pontos[i].x, pontos[i].y, i));
+    ordem.dedup_by(|i, j| {
+      let repetido = pontos[*i] == pontos[*j];
+      if repetido {
+        t.representante[*i] = *j;
+      }
+      repetido
+    });
+
+    t.constroi(&ordem);
+    return t;
+  }
+
+
+  /// Pontos originais
+  pub fn pontos( &self ) -> &[Ponto] {
+    &self.pontos
+  }
+
+
+  /// Triângulos como índices dos pontos em sentido anti-horário
+  pub fn triangulos( &self ) -> &[[usize; 3]] {
+    &self.triangulos
+  }
+
+
+  /// Quantidade de triângulos
+  pub fn len( &self ) -> usize {
+    self.triangulos.len()
+  }
+
+
+  /// Verifica se não há triângulos
+  pub fn is_empty( &self ) -> bool {
+    self.triangulos.is_empty()
+  }
+
+
+  /// Vizinhos do triângulo `t`, o de posição `k` é oposto ao
+  /// vértice `k` e `None` indica uma aresta da envoltória
+  pub fn vizinhos( &self, t: usize) -> [Option<usize>; 3] {
+    self.vizinhos[t]
+  }
+
+
+  /// Pares de triângulos vizinhos, com o menor índice primeiro
+  pub fn adjacencias( &self ) -> impl Iterator<Item = (usize, usize)> + '_ {
+    self.vizinhos.iter().enumerate().flat_map(|(t, v)| {
+      v.iter().filter_map(move |u| u.filter(|&u| t < u).map(|u| (t, u)))
+    })
+  }
+
+
+  /// Arestas da triangulação, cada uma uma única vez e com o
+  /// menor índice primeiro
+  pub fn arestas( &self ) -> impl Iterator<Item = (usize, usize)> + '_ {
+    self.triangulos.iter().enumerate().flat_map(move |(t, v)| {
+      (0..3).filter_map(move |k| {
+        let (a, b) = (v[(k + 1) % 3], v[(k + 2) % 3]);
+        match self.vizinhos[t][k] {
+          Some(u) if u < t => None,
+          _                => Some((a.min(b), a.max(b))),
+        }
+      })
+    })
+  }
+
+
+  /// Verifica se a aresta entre dois pontos é restrita
+  pub fn is_restrita( &self, a: usize, b: usize) -> bool {
+    match (self.representante.get(a), self.representante.get(b)) {
+      (Some(&a), Some(&b)) => self.restritas.contains(&(a.min(b), a.max(b))),
+      _                    => false,
+    }
+  }
+
+
+  /// Força a aresta entre os pontos `a` e `b` na triangulação.
+  /// Pontos sobre a aresta a dividem em partes, e uma aresta que
+  /// cruza outra restrita é um erro
+  pub fn restringir( &mut self, a: usize, b: usize) -> Result<(), String> {
+    let n = self.pontos.len();
+    if a >= n || b >= n {
+      return Err(format!("aresta ({}, {}) fora dos {} pontos", a, b, n));
+    }
+
+    let (a, b) = (self.representante[a], self.representante[b]);
+    if a == b {
+      return Err(format!("aresta ({}, {}) sem comprimento", a, b));
+    }
+    if self.triangulos.is_empty() {
+      return Err("a triangulação não possui triângulos".into());
+    }
+
+    // a triangulação só muda se a aresta inteira for válida
+    if let Some(&(u, v)) = self.restritas.iter().find(|&&(u, v)| self.cruza(a, b, u, v)) {
+      return Err(format!("a aresta ({}, {}) cruza a aresta restrita ({}, {})", a, b, u, v));
+    }
+
+    // pontos no interior da aresta, ordenados de a para b
+    let (pa, pb) = (self.pontos[a], self.pontos[b]);
+    let mut paradas: Vec<usize> = (0..n)
+      .filter(|&v| self.triangulo_de[v].is_some() && v != a && v != b)
+      .filter(|&v| {
+        let p = self.pontos[v];
+        orientacao(pa, pb, p) == 0 && produto_escalar(pa, pb, p) > 0 && produto_escalar(pb, pa, p) > 0
+      })
+      .collect();
+    paradas.sort_by_key(|&v| produto_escalar(pa, pb, self.pontos[v]));
+
+    let mut anterior = a;
+    for v in paradas.into_iter().chain(core::iter::once(b)) {
+      self.insere_restrita(anterior, v);
+      anterior = v;
+    }
+
+    return Ok(());
+  }
+
+
+  /// Insere uma aresta restrita sem pontos no seu interior
+  fn insere_restrita( &mut self, a: usize, b: usize) {
+    let chave = (a.min(b), a.max(b));
+    if self.encontra_aresta(a, b).is_some() {
+      self.restritas.insert(chave);
+      return;
+    }
+
+    let mut fila = self.cruzadas(a, b);
+
+    // troca as arestas cruzadas enquanto o quadrilátero for
+    // convexo, as que ainda cruzam voltam para a fila. Cada volta
+    // completa da fila troca ao menos uma aresta
+    let mut novas = Vec::new();
+    let mut sem_troca = 0;
+    while let Some((u, v)) = fila.pop_front() {
+      let (t, k) = match self.encontra_aresta(u, v) {
+        Some(e) => e,
+        None    => continue,
+      };
+      let x = self.triangulos[t][k];
+      let y = self.oposto(t, k);
+
+      let (px, py) = (self.pontos[x], self.pontos[y]);
+      let convexo = sinal(orientacao(px, py, self.pontos[u])) * sinal(orientacao(px, py, self.pontos[v])) < 0;
+      if !convexo {
+        sem_troca += 1;
+        debug_assert!(sem_troca <= fila.len(), "nenhuma aresta cruzada pode ser trocada");
+        fila.push_back((u, v));
+        continue;
+      }
+
+      sem_troca = 0;
+      self.troca(t, k);
+      if self.cruza(a, b, x, y) {
+        fila.push_back((x, y));
+      } else {
+        novas.push((x, y));
+      }
+    }
+
+    self.restritas.insert(chave);
+
+    // refaz a condição de Delaunay nas arestas criadas
+    let mut mudou = true;
+    while mudou {
+      mudou = false;
+      for aresta in novas.iter_mut() {
+        let (u, v) = *aresta;
+        if self.restritas.contains(&(u.min(v), u.max(v))) {
+          continue;
+        }
+
+        let (t, k) = match self.encontra_aresta(u, v) {
+          Some(e) => e,
+          None    => continue,
+        };
+        if self.ilegal(t, k) {
+          let x = self.triangulos[t][k];
+          let y = self.oposto(t, k);
+          self.troca(t, k);
+          *aresta = (x, y);
+          mudou = true;
+        }
+      }
+    }
+  }
+
+
+  /// Arestas cruzadas pelo segmento (a, b), que não passa por
+  /// outros pontos, na ordem em que o caminho de `a` até `b` as
+  /// atravessa
+  fn cruzadas( &self, a: usize, b: usize) -> VecDeque<(usize, usize)> {
+    let (pa, pb) = (self.pontos[a], self.pontos[b]);
+    let lado = |w: usize| sinal(orientacao(pa, pb, self.pontos[w]));
+
+    // o triângulo ao redor de `a` cujo ângulo contém `b`, girando
+    // nos dois sentidos como em `encontra_aresta`
+    let inicio = self.triangulo_de[a].expect("ponto da restrição fora da triangulação");
+    let mut primeiro = None;
+    'busca: for giro in [1, 2] {
+      let mut t = inicio;
+      loop {
+        let i = self.triangulos[t].iter().position(|&w| w == a).expect("triângulo sem o ponto");
+        let (u, v) = (self.triangulos[t][(i + 1) % 3], self.triangulos[t][(i + 2) % 3]);
+        if lado(u) < 0 && lado(v) > 0 {
+          primeiro = Some((t, i));
+          break 'busca;
+        }
+        match self.vizinhos[t][(i + giro) % 3] {
+          Some(proximo) if proximo != inicio => t = proximo,
+          _                                   => break,
+        }
+      }
+    }
+
+    // atravessa os triângulos até chegar a `b`, saindo de cada um
+    // pela aresta cujas pontas ficam em lados opostos
+    let (mut t, mut k) = primeiro.expect("restrição sem triângulo de saída");
+    let mut cruzadas = VecDeque::new();
+    loop {
+      let (u, v) = (self.triangulos[t][(k + 1) % 3], self.triangulos[t][(k + 2) % 3]);
+      cruzadas.push_back((u, v));
+
+      let w = self.oposto(t, k);
+      if w == b {
+        return cruzadas;
+      }
+      debug_assert!(lado(w) != 0, "ponto no interior da restrição");
+
+      t = self.vizinhos[t][k].expect("restrição fora da envoltória");
+      let fica = if lado(w) == lado(u) { u } else { v };
+      k = self.triangulos[t].iter().position(|&x| x == fica).expect("vizinhos sem a aresta");
+    }
+  }
+
+
+  /// Constrói a triangulação sobre os índices ordenados e sem
+  /// repetições
+  fn constroi( &mut self, ordem: &[usize]) {
+    if ordem.len() < 3 {
+      return;
+    }
+
+    // o primeiro ponto fora da reta dos dois primeiros
+    let (p0, p1) = (self.pontos[ordem[0]], self.pontos[ordem[1]]);
+    let k = match (2..ordem.len()).find(|&k| orientacao(p0, p1, self.pontos[ordem[k]]) != 0) {
+      Some(k) => k,
+      None    => return,
+    };
+    let topo = ordem[k];
+    let esquerda = orientacao(p0, p1, self.pontos[topo]) > 0;
+
+    // leque do ponto sobre os segmentos da reta inicial
+    for i in 0..k - 1 {
+      let (a, b) = (ordem[i], ordem[i + 1]);
+      let t = if esquerda { self.novo([a, b, topo]) } else { self.novo([b, a, topo]) };
+      if i > 0 {
+        // vizinho anterior pela aresta (a, topo)
+        let k_a = if esquerda { 1 } else { 0 };
+        self.vizinhos[t][k_a] = Some(t - 1);
+        let anterior = if esquerda { 0 } else { 1 };
+        self.vizinhos[t - 1][anterior] = Some(t);
+      }
+    }
+
+    // envoltória como lista circular em sentido anti-horário
+    let n = self.pontos.len();
+    let mut proximo = alloc::vec![usize::MAX; n];
+    let mut anterior = alloc::vec![usize::MAX; n];
+    for t in 0..self.triangulos.len() {
+      for j in 0..3 {
+        if self.vizinhos[t][j].is_none() {
+          let (u, v) = (self.triangulos[t][(j + 1) % 3], self.triangulos[t][(j + 2) % 3]);
+          proximo[u] = v;
+          anterior[v] = u;
+          self.triangulo_de[u] = Some(t);
+        }
+      }
+    }
+
+    let mut ultimo = topo;
+    for &p in ordem[k + 1..].iter() {
+      let alvo = self.pontos[p];
+      let visivel = |u: usize, v: usize| orientacao(self.pontos[u], self.pontos[v], alvo) < 0;
+
+      // o último ponto inserido é extremo e vê ao menos uma
+      // aresta, as visíveis formam uma cadeia contínua
+      let mut fim = ultimo;
+      while visivel(fim, proximo[fim]) {
+        fim = proximo[fim];
+      }
+      let mut inicio = ultimo;
+      while visivel(anterior[inicio], inicio) {
+        inicio = anterior[inicio];
+      }
+
+      // um triângulo novo para cada aresta visível
+      let mut pilha = Vec::new();
+      let mut u = inicio;
+      let mut ultimo_novo: Option<usize> = None;
+      while u != fim {
+        let v = proximo[u];
+        let (externo, k_externo) = match self.encontra_aresta(u, v) {
+          Some(e) => e,
+          None    => break,
+        };
+
+        let t = self.novo([v, u, p]);
+        self.vizinhos[t][2] = Some(externo);
+        self.vizinhos[externo][k_externo] = Some(t);
+        if let Some(anterior_t) = ultimo_novo {
+          // a aresta (u, p) é compartilhada com o triângulo anterior
+          self.vizinhos[t][0] = Some(anterior_t);
+          self.vizinhos[anterior_t][1] = Some(t);
+        }
+
+        ultimo_novo = Some(t);
+        pilha.push(t);
+        u = v;
+      }
+
+      // os vértices internos da cadeia saem da envoltória
+      if let (Some(&primeiro), Some(&ultimo_t)) = (pilha.first(), pilha.last()) {
+        self.triangulo_de[inicio] = Some(primeiro);
+        self.triangulo_de[p] = Some(ultimo_t);
+      }
+      proximo[inicio] = p;
+      anterior[p] = inicio;
+      proximo[p] = fim;
+      anterior[fim] = p;
+      ultimo = p;
+
+      self.legaliza(pilha, p);
+    }
+  }
+
+
+  /// Troca as arestas opostas a `p` enquanto forem ilegais
+  fn legaliza( &mut self, mut pilha: Vec<usize>, p: usize) {
+    while let Some(t) = pilha.pop() {
+      let k = match self.triangulos[t].iter().position(|&v| v == p) {
+        Some(k) => k,
+        None    => continue,
+      };
+
+      if self.ilegal(t, k) {
+        let u = self.vizinhos[t][k].expect("aresta ilegal sem vizinho");
+        self.troca(t, k);
+        pilha.push(t);
+        pilha.push(u);
+      }
+    }
+  }
+
+
+  /// Verifica se a aresta oposta ao vértice `k` de `t` fere a
+  /// condição de Delaunay e pode ser trocada
+  fn ilegal( &self, t: usize, k: usize) -> bool {
+    if self.vizinhos[t][k].is_none() {
+      return false;
+    }
+
+    let [a, b, c] = self.triangulos[t];
+    let (e1, e2) = (self.triangulos[t][(k + 1) % 3], self.triangulos[t][(k + 2) % 3]);
+    if self.restritas.contains(&(e1.min(e2), e1.max(e2))) {
+      return false;
+    }
+
+    let q = self.oposto(t, k);
+    dentro_circulo(self.pontos[a], self.pontos[b], self.pontos[c], self.pontos[q])
+  }
+
+
+  /// Vértice do vizinho que não está na aresta oposta a `k`
+  fn oposto( &self, t: usize, k: usize) -> usize {
+    let u = self.vizinhos[t][k].expect("aresta da envoltória sem vértice oposto");
+    let (e1, e2) = (self.triangulos[t][(k + 1) % 3], self.triangulos[t][(k + 2) % 3]);
+    self.triangulos[u].iter().copied().find(|&v| v != e1 && v != e2).expect("vizinho sem a aresta compartilhada")
+  }
+
+
+  /// Troca a diagonal do quadrilátero formado por `t` e seu
+  /// vizinho oposto ao vértice `k`. Os dois triângulos mantêm os
+  /// índices e o vértice `k` de `t` fica nos dois
+  fn troca( &mut self, t: usize, k: usize) {
+    let u = match self.vizinhos[t][k] {
+      Some(u) => u,
+      None    => return,
+    };
+
+    // t = [p, a, b] e u = [q, b, a]
+    self.gira(t, k);
+    let j = (0..3).find(|&j| self.vizinhos[u][j] == Some(t)).expect("vizinhos sem reciprocidade");
+    self.gira(u, j);
+
+    let [p, a, b] = self.triangulos[t];
+    let q = self.triangulos[u][0];
+    let [_, t1, t2] = self.vizinhos[t];
+    let [_, u1, u2] = self.vizinhos[u];
+
+    // t = [p, a, q] e u = [q, b, p]
+    self.triangulos[t] = [p, a, q];
+    self.vizinhos[t] = [u1, Some(u), t2];
+    self.triangulos[u] = [q, b, p];
+    self.vizinhos[u] = [t1, Some(t), u2];
+
+    if let Some(w) = u1 {
+      self.troca_vizinho(w, u, t);
+    }
+    if let Some(w) = t1 {
+      self.troca_vizinho(w, t, u);
+    }
+
+    // `a` e `b` perdem um triângulo e podem ter ganhado uma
+    // aresta da envoltória, `p` e `q` continuam nos dois
+    if u1.is_none() || self.triangulo_de[a] == Some(u) {
+      self.triangulo_de[a] = Some(t);
+    }
+    if t1.is_none() || self.triangulo_de[b] == Some(t) {
+      self.triangulo_de[b] = Some(u);
+    }
+  }
+
+
+  /// Gira os vértices de `t` para que o vértice `k` fique na
+  /// primeira posição
+  fn gira( &mut self, t: usize, k: usize) {
+    self.triangulos[t].rotate_left(k);
+    self.vizinhos[t].rotate_left(k);
+  }
+
+
+  /// Troca a referência de `w` ao vizinho `antigo` por `novo`
+  fn troca_vizinho( &mut self, w: usize, antigo: usize, novo: usize) {
+    for v in self.vizinhos[w].iter_mut() {
+      if *v == Some(antigo) {
+        *v = Some(novo);
+      }
+    }
+  }
+
+
+  /// Cria um triângulo sem vizinhos, que passa a ser o de seus
+  /// vértices que ainda não tinham um
+  fn novo( &mut self, vertices: [usize; 3]) -> usize {
+    let t = self.triangulos.len();
+    self.triangulos.push(vertices);
+    self.vizinhos.push([None; 3]);
+    for &v in vertices.iter() {
+      self.triangulo_de[v].get_or_insert(t);
+    }
+    return t;
+  }
+
+
+  /// Encontra um triângulo com a aresta (u, v) e a posição do
+  /// terceiro vértice, girando em torno de `u`
+  fn encontra_aresta( &self, u: usize, v: usize) -> Option<(usize, usize)> {
+    let inicio = self.triangulo_de[u]?;
+
+    // gira em um sentido até voltar ou chegar à envoltória, e
+    // então no outro sentido
+    for lado in [1, 2] {
+      let mut t = inicio;
+      loop {
+        let tri = self.triangulos[t];
+        if let Some(j) = tri.iter().position(|&w| w == v) {
+          let k = 3 - j - tri.iter().position(|&w| w == u)?;
+          return Some((t, k));
+        }
+
+        let i = tri.iter().position(|&w| w == u)?;
+        match self.vizinhos[t][(i + lado) % 3] {
+          Some(proximo) if proximo != inicio => t = proximo,
+          _                                   => break,
+        }
+      }
+    }
+
+    return None;
+  }
+
+
+  /// Verifica se o segmento (a, b) cruza a aresta (u, v) fora
+  /// das pontas
+  fn cruza( &self, a: usize, b: usize, u: usize, v: usize) -> bool {
+    let (pa, pb, pu, pv) = (self.pontos[a], self.pontos[b], self.pontos[u], self.pontos[v]);
+    sinal(orientacao(pa, pb, pu)) * sinal(orientacao(pa, pb, pv)) < 0
+      && sinal(orientacao(pu, pv, pa)) * sinal(orientacao(pu, pv, pb)) < 0
+  }
+}
+
+
+// Métodos relacionados ao inteiro largo
+impl Largo {
+
+
+  /// Produto exato de dois inteiros de 128 bits
+  fn produto( a: i128, b: i128) -> Self {
+    const MASCARA: u128 = u64::MAX as u128;
+    let (x, y) = (a.unsigned_abs(), b.unsigned_abs());
+    let (x1, x0) = (x >> 64, x & MASCARA);
+    let (y1, y0) = (y >> 64, y & MASCARA);
+
+    // multiplicação em palavras de 64 bits
+    let (p00, p01, p10, p11) = (x0 * y0, x0 * y1, x1 * y0, x1 * y1);
+    let meio = (p00 >> 64) + (p01 & MASCARA) + (p10 & MASCARA);
+    let baixo = (p00 & MASCARA) | (meio << 64);
+    let alto = p11 + (p01 >> 64) + (p10 >> 64) + (meio >> 64);
+
+    let modulo = Self { alto: alto as i128, baixo };
+    if (a < 0) != (b < 0) {
+      return modulo.neg();
+    }
+    return modulo;
+  }
+
+
+  /// Soma com o transporte da parte baixa
+  fn sum( self, o: Largo) -> Self {
+    let (baixo, transporte) = self.baixo.overflowing_add(o.baixo);
+    Self {
+      alto: self.alto.wrapping_add(o.alto).wrapping_add(transporte as i128),
+      baixo
+    }
+  }
+
+
+  /// Complemento de dois
+  fn neg( self ) -> Self {
+    let (baixo, transporte) = (!self.baixo).overflowing_add(1);
+    Self {
+      alto: (!self.alto).wrapping_add(transporte as i128),
+      baixo
+    }
+  }
+
+
+  /// Verifica se o número é maior que zero
+  fn positivo( self ) -> bool {
+    self.alto > 0 || (self.alto == 0 && self.baixo > 0)
+  }
+}
+
+
+// Funções
+/// Produto escalar de `ab` por `ac`
+fn produto_escalar( a: Ponto, b: Ponto, c: Ponto) -> i128 {
+  let (abx, aby) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+  let (acx, acy) = (c.x as i128 - a.x as i128, c.y as i128 - a.y as i128);
+  abx * acx + aby * acy
+}
+
+
+/// Sinal de um inteiro como -1, 0 ou 1
+fn sinal( v: i128) -> i32 {
+  v.signum() as i32
+}
+
+
+/// Verifica se `d` está estritamente dentro do círculo que passa
+/// por `a`, `b` e `c`, em sentido anti-horário
+fn dentro_circulo( a: Ponto, b: Ponto, c: Ponto, d: Ponto) -> bool {
+  let (adx, ady) = (a.x as i128 - d.x as i128, a.y as i128 - d.y as i128);
+  let (bdx, bdy) = (b.x as i128 - d.x as i128, b.y as i128 - d.y as i128);
+  let (cdx, cdy) = (c.x as i128 - d.x as i128, c.y as i128 - d.y as i128);
+
+  let alift = adx * adx + ady * ady;
+  let blift = bdx * bdx + bdy * bdy;
+  let clift = cdx * cdx + cdy * cdy;
+
+  // os produtos passam de 128 bits nas coordenadas extremas
+  Largo::produto(alift, bdx * cdy - cdx * bdy)
+    .sum(Largo::produto(blift, cdx * ady - adx * cdy))
+    .sum(Largo::produto(clift, adx * bdy - bdx * ady))
+    .positivo()
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::random_data::sorteio;
+  use super::super::hull_data::Envoltoria;
+
+
+  /// Pontos em uma grade pequena, com repetidos, colineares e
+  /// cocirculares, em um quadrado médio ou até os limites de `i32`
+  fn pontos( estado: &mut u64, n: usize, modo: u64) -> Vec<Ponto> {
+    (0..n).map(|_| {
+      let (x, y) = (sorteio(estado), sorteio(estado));
+      match modo {
+        0 => Ponto::new((x % 9) as i32 - 4, (y % 9) as i32 - 4),
+        1 => Ponto::new((x % 2001) as i32 - 1000, (y % 2001) as i32 - 1000),
+        _ => Ponto::new(x as i32, y as i32),
+      }
+    }).collect()
+  }
+
+
+  /// Dobro da área com sinal do triângulo `abc`
+  fn area2( a: Ponto, b: Ponto, c: Ponto) -> i128 {
+    (b.x as i128 - a.x as i128) * (c.y as i128 - a.y as i128)
+      - (b.y as i128 - a.y as i128) * (c.x as i128 - a.x as i128)
+  }
+
+
+  /// Teste do círculo direto em 128 bits, só para coordenadas
+  /// pequenas. Nas grandes vale o predicado da triangulação,
+  /// conferido em `circulo_em_coordenadas_extremas`
+  fn dentro( a: Ponto, b: Ponto, c: Ponto, d: Ponto) -> bool {
+    let pequena = [a, b, c, d].iter().all(|p| p.x.unsigned_abs() < 1 << 20 && p.y.unsigned_abs() < 1 << 20);
+    if !pequena {
+      return dentro_circulo(a, b, c, d);
+    }
+
+    let linha = |p: Ponto| {
+      let (x, y) = (p.x as i128 - d.x as i128, p.y as i128 - d.y as i128);
+      (x, y, x * x + y * y)
+    };
+    let ((ax, ay, al), (bx, by, bl), (cx, cy, cl)) = (linha(a), linha(b), linha(c));
+    ax * (by * cl - bl * cy) - ay * (bx * cl - bl * cx) + al * (bx * cy - by * cx) > 0
+  }
+
+
+  /// Confere a estrutura da triangulação: triângulos em sentido
+  /// anti-horário, vizinhos recíprocos, área igual à da
+  /// envoltória, todos os pontos distintos usados e a condição de
+  /// Delaunay nas arestas que não são restritas
+  fn confere( t: &Triangulacao) {
+    let p = t.pontos();
+    let tri = t.triangulos();
+
+    for (i, v) in tri.iter().enumerate() {
+      assert!(area2(p[v[0]], p[v[1]], p[v[2]]) > 0, "triângulo {} {:?}", i, v);
+
+      for k in 0..3 {
+        let aresta = [v[(k + 1) % 3], v[(k + 2) % 3]];
+        match t.vizinhos(i)[k] {
+          Some(u) => {
+            let j = (0..3).find(|&j| t.vizinhos(u)[j] == Some(i)).expect("vizinho sem reciprocidade");
+            let w = tri[u];
+            assert_eq!([w[(j + 2) % 3], w[(j + 1) % 3]], aresta);
+
+            // condição de Delaunay local, que vale também na
+            // triangulação restrita fora das arestas restritas
+            if !t.is_restrita(aresta[0], aresta[1]) {
+              assert!(!dentro(p[v[0]], p[v[1]], p[v[2]], p[w[j]]), "aresta {:?}", aresta);
+            }
+          },
+          None => {
+            // só arestas da envoltória ficam sem vizinho
+            let fora = (0..p.len()).any(|q| area2(p[aresta[0]], p[aresta[1]], p[q]) < 0);
+            assert!(!fora, "aresta {:?} sem vizinho no interior", aresta);
+          },
+        }
+      }
+    }
+
+    let adjacencias: Vec<_> = t.adjacencias().collect();
+    let internas = tri.len() * 3 - t.arestas().count();
+    assert_eq!(adjacencias.len(), internas);
+    assert!(adjacencias.iter().all(|&(a, b)| a < b && t.vizinhos(a).contains(&Some(b))));
+
+    // a soma das áreas é a área da envoltória
+    let envoltoria = Envoltoria::from(p);
+    let h = envoltoria.vertices();
+    let area: i128 = (0..h.len())
+      .map(|i| h[i].x as i128 * h[(i + 1) % h.len()].y as i128 - h[(i + 1) % h.len()].x as i128 * h[i].y as i128)
+      .sum();
+    let soma: i128 = tri.iter().map(|v| area2(p[v[0]], p[v[1]], p[v[2]])).sum();
+    assert_eq!(soma, if h.len() < 3 { 0 } else { area });
+
+    // os pontos distintos são todos vértices e os repetidos não
+    let mut usados = alloc::vec![false; p.len()];
+    for v in tri.iter().flatten() {
+      usados[*v] = true;
+    }
+    for i in 0..p.len() {
+      let primeiro = p.iter().position(|&q| q == p[i]) == Some(i);
+      assert_eq!(usados[i], primeiro && !tri.is_empty(), "ponto {}", i);
+    }
+
+    // Euler: 2n - h - 2 triângulos, com h pontos na fronteira
+    if !tri.is_empty() {
+      let distintos = usados.iter().filter(|&&u| u).count();
+      let fronteira = (0..p.len()).filter(|&i| usados[i]).filter(|&i| {
+        (0..h.len()).any(|j| {
+          let (a, b) = (h[j], h[(j + 1) % h.len()]);
+          area2(a, b, p[i]) == 0
+            && p[i].x >= a.x.min(b.x) && p[i].x <= a.x.max(b.x)
+            && p[i].y >= a.y.min(b.y) && p[i].y <= a.y.max(b.y)
+        })
+      }).count();
+      assert_eq!(tri.len(), 2 * distintos - fronteira - 2);
+    }
+  }
+
+
+  /// Verifica se o segmento (a, b) passa pelo interior de (u, v)
+  fn cruzam( a: Ponto, b: Ponto, u: Ponto, v: Ponto) -> bool {
+    area2(a, b, u).signum() * area2(a, b, v).signum() < 0
+      && area2(u, v, a).signum() * area2(u, v, b).signum() < 0
+  }
+
+
+  #[test]
+  fn triangulacoes_aleatorias() {
+    let mut estado = 0x5eed_0049;
+    for rodada in 0..150 {
+      let modo = rodada % 3;
+      let n = 3 + (sorteio(&mut estado) % 120) as usize;
+      let p = pontos(&mut estado, n, modo);
+      let t = Triangulacao::new(&p);
+      confere(&t);
+
+      // o círculo de cada triângulo não contém outros pontos
+      if modo < 2 {
+        for v in t.triangulos() {
+          let (a, b, c) = (p[v[0]], p[v[1]], p[v[2]]);
+          assert!(p.iter().all(|&d| !dentro(a, b, c, d)), "triângulo {:?}", v);
+        }
+      }
+    }
+  }
+
+
+  #[test]
+  fn conjuntos_degenerados() {
+    // vazio, um ponto, dois pontos e pontos todos colineares
+    for p in [
+      Vec::new(),
+      alloc::vec![Ponto::new(1, 1)],
+      alloc::vec![Ponto::new(1, 1), Ponto::new(3, 2)],
+      (0..20).map(|i| Ponto::new(3 * i - 7, -2 * i + 5)).collect(),
+      (0..20).map(|i| Ponto::new(i % 4, i % 4)).collect(),
+    ] {
+      let mut t = Triangulacao::new(&p);
+      assert!(t.is_empty());
+      assert_eq!(t.arestas().count(), 0);
+      confere(&t);
+      if p.len() >= 2 {
+        assert!(t.restringir(0, 1).is_err());
+      }
+    }
+
+    // colineares com um único ponto fora da reta
+    let mut p: Vec<Ponto> = (0..10).map(|i| Ponto::new(i, 0)).collect();
+    p.push(Ponto::new(4, 3));
+    let t = Triangulacao::new(&p);
+    assert_eq!(t.len(), 9);
+    confere(&t);
+
+    // repetidos usam a primeira ocorrência
+    let p = alloc::vec![
+      Ponto::new(0, 0), Ponto::new(4, 0), Ponto::new(0, 0),
+      Ponto::new(0, 4), Ponto::new(4, 0), Ponto::new(4, 4),
+    ];
+    let mut t = Triangulacao::new(&p);
+    assert_eq!(t.len(), 2);
+    confere(&t);
+    assert!(t.restringir(2, 2).is_err());
+    assert!(t.restringir(2, 0).is_err());
+    assert!(t.restringir(4, 3).is_ok());
+    assert!(t.is_restrita(1, 3) && t.is_restrita(3, 4));
+    confere(&t);
+  }
+
+
+  #[test]
+  fn pontos_cocirculares() {
+    // os 12 pontos inteiros do círculo de raio 5, com e sem o
+    // centro, e uma grade cheia
+    let mut p: Vec<Ponto> = [(5, 0), (4, 3), (3, 4), (0, 5)].iter()
+      .flat_map(|&(x, y)| [Ponto::new(x, y), Ponto::new(-y, x), Ponto::new(-x, -y), Ponto::new(y, -x)])
+      .collect();
+    let t = Triangulacao::new(&p);
+    assert_eq!(t.len(), 10);
+    confere(&t);
+
+    p.push(Ponto::new(0, 0));
+    let t = Triangulacao::new(&p);
+    assert_eq!(t.len(), 12);
+    confere(&t);
+
+    let grade: Vec<Ponto> = (0..100).map(|i| Ponto::new(i % 10, i / 10)).collect();
+    let t = Triangulacao::new(&grade);
+    assert_eq!(t.len(), 2 * 81);
+    confere(&t);
+  }
+
+
+  /// Pontos distintos sobre o segmento (a, b), de `a` até `b`
+  fn sobre( p: &[Ponto], a: usize, b: usize) -> Vec<usize> {
+    let (pa, pb) = (p[a], p[b]);
+    let mut sobre: Vec<usize> = (0..p.len())
+      .filter(|&i| p.iter().position(|&q| q == p[i]) == Some(i) && area2(pa, pb, p[i]) == 0)
+      .filter(|&i| produto_escalar(pa, pb, p[i]) >= 0 && produto_escalar(pb, pa, p[i]) >= 0)
+      .collect();
+    sobre.sort_by_key(|&i| produto_escalar(pa, pb, p[i]));
+    return sobre;
+  }
+
+
+  #[test]
+  fn arestas_restritas() {
+    let mut estado = 0x5eed_1049;
+    for rodada in 0..100 {
+      let modo = rodada % 3;
+      let n = 3 + (sorteio(&mut estado) % 80) as usize;
+      let p = pontos(&mut estado, n, modo);
+      let mut t = Triangulacao::new(&p);
+      let mut pecas: Vec<(usize, usize)> = Vec::new();
+
+      for _ in 0..8 {
+        let a = (sorteio(&mut estado) % n as u64) as usize;
+        let b = (sorteio(&mut estado) % n as u64) as usize;
+        let antes = t.triangulos().to_vec();
+        let cruza = pecas.iter().any(|&(u, v)| cruzam(p[a], p[b], p[u], p[v]));
+
+        match t.restringir(a, b) {
+          Ok(()) => {
+            assert!(!cruza && p[a] != p[b], "({}, {})", a, b);
+            pecas.extend(sobre(&p, a, b).windows(2).map(|par| (par[0], par[1])));
+          },
+          Err(_) => {
+            assert!(cruza || p[a] == p[b] || t.is_empty(), "({}, {})", a, b);
+            assert_eq!(t.triangulos(), &antes[..]);
+          },
+        }
+      }
+      confere(&t);
+
+      // cada restrição é uma cadeia de arestas da triangulação
+      // pelos pontos sobre ela
+      let arestas: BTreeSet<(usize, usize)> = t.arestas().collect();
+      for &(u, v) in pecas.iter() {
+        assert!(arestas.contains(&(u.min(v), u.max(v))), "({}, {})", u, v);
+        assert!(t.is_restrita(u, v));
+      }
+    }
+  }
+
+
+  #[test]
+  fn restricao_que_atravessa_varios_triangulos() {
+    // leque de pontos acima e abaixo do eixo, a restrição de um
+    // extremo ao outro cruza todas as arestas entre eles
+    let mut p = alloc::vec![Ponto::new(-100, 0), Ponto::new(100, 0)];
+    for i in -9..10 {
+      p.push(Ponto::new(10 * i + 3, 1 + (i * i) % 5));
+      p.push(Ponto::new(10 * i - 2, -1 - (i * i * i).rem_euclid(7)));
+    }
+    let mut t = Triangulacao::new(&p);
+    confere(&t);
+    assert!(t.restringir(0, 1).is_ok());
+    assert!(t.is_restrita(1, 0));
+    assert!(t.arestas().any(|e| e == (0, 1)));
+    confere(&t);
+
+    // outra restrição que cruza a primeira é um erro
+    assert!(t.restringir(2, 3).is_err());
+  }
+
+
+  #[test]
+  fn circulo_em_coordenadas_extremas() {
+    // o sinal do teste não muda ao multiplicar as coordenadas,
+    // então pontos pequenos conferem o cálculo de 256 bits
+    let mut estado = 0x5eed_2049;
+    let escala = 1 << 26;
+    for _ in 0..2000 {
+      let q = pontos(&mut estado, 4, 0);
+      let grande: Vec<Ponto> = q.iter().map(|p| Ponto::new(p.x * escala, p.y * escala)).collect();
+      assert_eq!(
+        dentro_circulo(grande[0], grande[1], grande[2], grande[3]),
+        dentro(q[0], q[1], q[2], q[3]),
+        "{:?}", q
+      );
+    }
+
+    // cantos de i32 e pontos quase cocirculares perto deles
+    let (min, max) = (i32::MIN, i32::MAX);
+    let mut p = alloc::vec![
+      Ponto::new(min, min), Ponto::new(max, min), Ponto::new(max, max), Ponto::new(min, max),
+      Ponto::new(0, 0), Ponto::new(max - 1, max), Ponto::new(min, min + 1), Ponto::new(1, -1),
+    ];
+    p.extend(pontos(&mut estado, 60, 2));
+    let mut t = Triangulacao::new(&p);
+    confere(&t);
+    assert!(t.restringir(0, 2).is_ok());
+    assert!(t.restringir(1, 3).is_err());
+    confere(&t);
+  }
+}
@@ -0,0 +1,990 @@
+/// Envoltória convexa de conjuntos de pontos, pela cadeia
+/// monótona de Andrew em O(n log n) ou por inserções
+/// incrementais.
+///
+/// As orientações são calculadas com inteiros de 128 bits, sem
+/// erros de arredondamento em todo o intervalo de `i32`. A
+/// envoltória resultante está em sentido anti-horário, começa
+/// no ponto mais à esquerda (e mais abaixo no empate) e não
+/// possui pontos repetidos nem pontos colineares sobre as
+/// arestas. Conjuntos degenerados geram envoltórias com um
+/// único ponto ou com os dois extremos de um segmento.
+///
+/// Sobre a envoltória, os calibres rotativos percorrem as
+/// arestas uma vez, em O(h), para encontrar o diâmetro, a
+/// largura e os menores retângulos envolventes.
+///
+/// O menor círculo envolvente de pontos ou formas é calculado
+/// pelo algoritmo de Welzl, em tempo linear esperado.
+pub use super::object_data::{
+    Ponto as Ponto,
+    Poligono as Poligono,
+    QuadOrientado as QuadOrientado,
+    Circulo as Circulo,
+    Forma as Forma,
+};
+use super::vector_data::fpitagoras;
+use super::random_data::sorteio;
+use alloc::vec::Vec;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Real;
+
+
+/// Folga relativa dos testes de contenção do algoritmo de Welzl
+const FOLGA: f64 = 1e-9;
+
+
+// Estruturas
+/// Envoltória convexa que cresce conforme pontos são inseridos
+#[derive(Debug, Clone, Default)]
+pub struct Envoltoria {
+  /// Vértices em sentido anti-horário
+  pontos: Vec<Ponto>
+}
+
+
+/// Disco real usado pelo algoritmo de Welzl: centro e raio,
+/// pontos são discos de raio zero
+#[derive(Debug, Clone, Copy)]
+struct Disco {
+  c: (f64, f64),
+  r: f64
+}
+
+
+// Métodos relacionados à envoltória
+impl Envoltoria {
+
+
+  /// Gera uma envoltória vazia
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+
+  /// Gera a envoltória de um conjunto de pontos
+  pub fn from( pontos: &[Ponto]) -> Self {
+    Self {
+      pontos: cadeia_monotona(pontos.to_vec())
+    }
+  }
+
+
+  /// Vértices em sentido anti-horário
+  pub fn vertices( &self ) -> &[Ponto] {
+    &self.pontos
+  }
+
+
+  /// Quantidade de vértices
+  pub fn len( &self ) -> usize {
+    self.pontos.len()
+  }
+
+
+  /// Verifica se a envoltória não possui pontos
+  pub fn is_empty( &self ) -> bool {
+    self.pontos.is_empty()
+  }
+
+
+  /// Verifica se o ponto está dentro ou na borda da envoltória
+  pub fn contains( &self, p: Ponto) -> bool {
+    let v = &self.pontos;
+    match v.len() {
+      0 => false,
+      1 => v[0] == p,
+      2 => orientacao(v[0], v[1], p) == 0 && no_segmento(v[0], v[1], p),
+      n => (0..n).all(|i| orientacao(v[i], v[(i + 1) % n], p) >= 0),
+    }
+  }
+
+
+  /// Insere um ponto em O(h), retornando se a envoltória mudou
+  pub fn inserir( &mut self, p: Ponto) -> bool {
+    if self.contains(p) {
+      return false;
+    }
+
+    // envoltórias degeneradas são refeitas por inteiro
+    let n = self.pontos.len();
+    if n < 3 {
+      let mut pontos = core::mem::take(&mut self.pontos);
+      pontos.push(p);
+      self.pontos = cadeia_monotona(pontos);
+      return true;
+    }
+
+    // as arestas visíveis pelo ponto formam um arco contínuo, as
+    // colineares fora da aresta também são trocadas para não
+    // deixar vértices colineares
+    let visivel = |i: usize| {
+      let (a, b) = (self.pontos[i], self.pontos[(i + 1) % n]);
+      let o = orientacao(a, b, p);
+      o < 0 || (o == 0 && !no_segmento(a, b, p))
+    };
+
+    let inicio = (0..n)
+      .find(|&i| visivel(i) && !visivel((i + n - 1) % n))
+      .unwrap_or(0);
+    let mut fim = inicio;
+    while visivel(fim) {
+      fim = (fim + 1) % n;
+    }
+
+    // mantém a cadeia do fim do arco até o início e fecha no ponto
+    let mut pontos = Vec::with_capacity(n + 1);
+    let mut i = fim;
+    loop {
+      pontos.push(self.pontos[i]);
+      if i == inicio {
+        break;
+      }
+      i = (i + 1) % n;
+    }
+    pontos.push(p);
+
+    // volta a começar no ponto mais à esquerda
+    let primeiro = (0..pontos.len()).min_by_key(|&i| (pontos[i].x, pontos[i].y)).unwrap_or(0);
+    pontos.rotate_left(primeiro);
+
+    self.pontos = pontos;
+    return true;
+  }
+
+
+  /// Retorna o par de vértices mais distantes entre si
+  pub fn diametro( &self ) -> Option<(Ponto, Ponto)> {
+    let v = &self.pontos;
+    let n = v.len();
+    if n < 3 {
+      return Some((*v.first()?, *v.last()?));
+    }
+
+    // apenas pares antipodais podem ser o diâmetro
+    let mut melhor = (v[0], v[1]);
+    let mut maior = distancia2(v[0], v[1]);
+    let mut confere = |a: Ponto, b: Ponto| {
+      let d = distancia2(a, b);
+      if d > maior {
+        maior = d;
+        melhor = (a, b);
+      }
+    };
+
+    self.calibres(|i, _, t, _| {
+      let (a, b) = self.aresta(i);
+      confere(a, v[t]);
+      confere(b, v[t]);
+
+      // uma aresta paralela do outro lado tem dois antipodais
+      let u = v[(t + 1) % n];
+      if orientacao(a, b, u) == orientacao(a, b, v[t]) {
+        confere(a, u);
+        confere(b, u);
+      }
+    });
+
+    return Some(melhor);
+  }
+
+
+  /// Retorna a menor distância entre duas retas paralelas que
+  /// contêm a envoltória
+  pub fn largura( &self ) -> Option<f64> {
+    if self.pontos.len() < 3 {
+      return if self.is_empty() { None } else { Some(0.0) };
+    }
+
+    let mut menor = f64::INFINITY;
+    self.calibres(|i, _, t, _| {
+      let (a, b) = self.aresta(i);
+      let altura = orientacao(a, b, self.pontos[t]) as f64 / comprimento(a, b);
+      menor = menor.min(altura);
+    });
+
+    return Some(menor);
+  }
+
+
+  /// Retorna o retângulo de menor área que contém a envoltória
+  pub fn retangulo_min_area( &self ) -> Option<QuadOrientado> {
+    self.retangulo(|hx, hy| hx * hy)
+  }
+
+
+  /// Retorna o retângulo de menor perímetro que contém a envoltória
+  pub fn retangulo_min_perimetro( &self ) -> Option<QuadOrientado> {
+    self.retangulo(|hx, hy| hx + hy)
+  }
+
+
+  /// Retângulo que minimiza o custo sobre as metades das
+  /// dimensões, um dos lados ótimos sempre contém uma aresta.
+  /// O ângulo do quadrilátero é guardado em `f32`, então os
+  /// cantos podem se afastar da envoltória em cerca de 10⁻⁷
+  /// vezes o seu tamanho
+  fn retangulo<F: Fn(f64, f64) -> f64>( &self, custo: F) -> Option<QuadOrientado> {
+    let v = &self.pontos;
+    if v.len() == 1 {
+      return Some(QuadOrientado::new((v[0].x as f64, v[0].y as f64), 0.0, 0.0, 0.0));
+    }
+
+    let mut melhor: Option<(f64, QuadOrientado)> = None;
+    self.calibres(|i, r, t, l| {
+      let (a, b) = self.aresta(i);
+      let len = comprimento(a, b);
+      let u = ((b.x as f64 - a.x as f64) / len, (b.y as f64 - a.y as f64) / len);
+      let normal = (-u.1, u.0);
+
+      // projeções exatas sobre a aresta, normalizadas no final
+      let fim = produto_escalar(a, b, v[r]) as f64 / len;
+      let inicio = produto_escalar(a, b, v[l]) as f64 / len;
+      let altura = orientacao(a, b, v[t]) as f64 / len;
+
+      let (hx, hy) = ((fim - inicio) / 2.0, altura / 2.0);
+      let c = (inicio + fim) / 2.0;
+      let centro = (
+        a.x as f64 + u.0 * c + normal.0 * hy,
+        a.y as f64 + u.1 * c + normal.1 * hy
+      );
+
+      let valor = custo(hx, hy);
+      if melhor.is_none_or(|(m, _)| valor < m) {
+        let â = u.1.atan2(u.0) as f32;
+        melhor = Some((valor, QuadOrientado::new(centro, hx, hy, â)));
+      }
+    });
+
+    return melhor.map(|(_, q)| q);
+  }
+
+
+  /// Percorre as arestas com os calibres: para a aresta `i`, `r`
+  /// é o vértice mais adiante na direção da aresta, `t` o mais
+  /// distante dela e `l` o mais atrás. Os três só avançam, então
+  /// a volta inteira custa O(h)
+  fn calibres<F: FnMut(usize, usize, usize, usize)>( &self, mut f: F) {
+    let v = &self.pontos;
+    let n = v.len();
+    if n < 2 {
+      return;
+    }
+
+    let prox = |k: usize| (k + 1) % n;
+    let (mut r, mut t, mut l) = (1, 1, 1);
+    for i in 0..n {
+      let (a, b) = self.aresta(i);
+      if i == 0 {
+        r = 1;
+      }
+      while produto_escalar(a, b, v[prox(r)]) > produto_escalar(a, b, v[r]) {
+        r = prox(r);
+      }
+
+      if i == 0 {
+        t = r;
+      }
+      while orientacao(a, b, v[prox(t)]) > orientacao(a, b, v[t]) {
+        t = prox(t);
+      }
+
+      if i == 0 {
+        l = t;
+      }
+      while produto_escalar(a, b, v[prox(l)]) < produto_escalar(a, b, v[l]) {
+        l = prox(l);
+      }
+
+      f(i, r, t, l);
+    }
+  }
+
+
+  /// Extremos da aresta `i`
+  fn aresta( &self, i: usize) -> (Ponto, Ponto) {
+    (self.pontos[i], self.pontos[(i + 1) % self.pontos.len()])
+  }
+
+
+  /// Converte a envoltória em um polígono
+  pub fn into_poligono( self ) -> Poligono {
+    Poligono::new(self.pontos)
+  }
+}
+
+
+impl Extend<Ponto> for Envoltoria {
+  fn extend<I: IntoIterator<Item = Ponto>>( &mut self, pontos: I) {
+    for p in pontos {
+      self.inserir(p);
+    }
+  }
+}
+
+
+// Métodos relacionados ao disco
+impl Disco {
+
+
+  /// Verifica se o disco contém outro, com uma folga relativa
+  /// para os erros de arredondamento
+  fn contains( &self, d: &Disco) -> bool {
+    let dist = fpitagoras(d.c.0 - self.c.0, d.c.1 - self.c.1);
+    dist + d.r <= self.r + FOLGA * (1.0 + self.r)
+  }
+
+
+  /// Menor disco que contém dois discos
+  fn de_dois( a: Disco, b: Disco) -> Disco {
+    let d = fpitagoras(b.c.0 - a.c.0, b.c.1 - a.c.1);
+    if d + b.r <= a.r {
+      return a;
+    }
+    if d + a.r <= b.r {
+      return b;
+    }
+
+    let r = (d + a.r + b.r) / 2.0;
+    let t = (r - a.r) / d;
+    Disco {
+      c: (a.c.0 + (b.c.0 - a.c.0) * t, a.c.1 + (b.c.1 - a.c.1) * t),
+      r
+    }
+  }
+
+
+  /// Menor disco tangente por dentro aos três discos (problema
+  /// de Apolônio), para pontos é o circuncírculo
+  fn de_tres( a: Disco, b: Disco, c: Disco) -> Disco {
+    // coordenadas relativas ao primeiro disco mantêm a precisão
+    let (x2, y2) = (b.c.0 - a.c.0, b.c.1 - a.c.1);
+    let (x3, y3) = (c.c.0 - a.c.0, c.c.1 - a.c.1);
+    let det = x2 * y3 - x3 * y2;
+
+    // cada diferença entre as equações dos círculos é linear:
+    // xi x + yi y = ki + mi R
+    let k2 = (x2 * x2 + y2 * y2 - b.r * b.r + a.r * a.r) / 2.0;
+    let k3 = (x3 * x3 + y3 * y3 - c.r * c.r + a.r * a.r) / 2.0;
+    let (m2, m3) = (b.r - a.r, c.r - a.r);
+
+    let escala = (x2 * x2 + y2 * y2).max(x3 * x3 + y3 * y3);
+    let candidato = if det.abs() > FOLGA * escala {
+      // x = ax + bx R e y = ay + by R, substituídos na primeira
+      // equação geram uma quadrática em R
+      let (ax, bx) = ((k2 * y3 - k3 * y2) / det, (m2 * y3 - m3 * y2) / det);
+      let (ay, by) = ((x2 * k3 - x3 * k2) / det, (x2 * m3 - x3 * m2) / det);
+
+      let qa = bx * bx + by * by - 1.0;
+      let qb = 2.0 * (ax * bx + ay * by + a.r);
+      let qc = ax * ax + ay * ay - a.r * a.r;
+      let maior = a.r.max(b.r).max(c.r);
+
+      let raizes = if qa.abs() < FOLGA {
+        [-qc / qb, f64::NAN]
+      } else {
+        let delta = (qb * qb - 4.0 * qa * qc).max(0.0).sqrt();
+        [(-qb - delta) / (2.0 * qa), (-qb + delta) / (2.0 * qa)]
+      };
+
+      raizes.iter()
+        .filter(|r| r.is_finite() && **r >= maior)
+        .min_by(|x, y| x.total_cmp(y))
+        .map(|&r| Disco { c: (a.c.0 + ax + bx * r, a.c.1 + ay + by * r), r })
+    } else {
+      None
+    };
+
+    // com os centros colineares um disco de dois já contém os
+    // três, e erros de arredondamento são descartados pelo teste
+    let pares = [Disco::de_dois(a, b), Disco::de_dois(a, c), Disco::de_dois(b, c)];
+    let maior = pares.iter().copied().fold(pares[0], |m, d| if d.r > m.r { d } else { m });
+    return candidato.into_iter()
+      .chain(pares.iter().copied())
+      .filter(|d| d.contains(&a) && d.contains(&b) && d.contains(&c))
+      .min_by(|x, y| x.r.total_cmp(&y.r))
+      .unwrap_or(maior);
+  }
+}
+
+
+// Funções
+/// Produto vetorial de `ab` por `ac`, positivo quando `c` está
+/// à esquerda de `ab`, negativo à direita e zero se colinear
+pub fn orientacao( a: Ponto, b: Ponto, c: Ponto) -> i128 {
+  let (abx, aby) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+  let (acx, acy) = (c.x as i128 - a.x as i128, c.y as i128 - a.y as i128);
+  abx * acy - aby * acx
+}
+
+
+/// Retorna o menor círculo que contém os pontos.
+///
+/// O centro de um `Circulo` é inteiro, então o centro real do
+/// menor círculo é trocado pelo vizinho inteiro que exige o
+/// menor raio, e o raio cresce até conter todos os pontos
+pub fn circulo_minimo( pontos: &[Ponto]) -> Option<Circulo> {
+  let discos = pontos.iter().map(|p| Disco { c: (p.x as f64, p.y as f64), r: 0.0 }).collect();
+  welzl(discos)
+}
+
+
+/// Retorna o menor círculo que contém as formas, círculos são
+/// considerados por inteiro e as outras formas pelos vértices
+pub fn circulo_minimo_formas( formas: &[Forma]) -> Option<Circulo> {
+  let mut discos = Vec::new();
+  for f in formas.iter() {
+    match f {
+      Forma::Circulo(c) => discos.push(Disco { c: (c.p.x as f64, c.p.y as f64), r: c.r }),
+      _                 => discos.extend(f.vertices().into_iter().map(|c| Disco { c, r: 0.0 })),
+    }
+  }
+  welzl(discos)
+}
+
+
+/// Algoritmo de Welzl na forma iterativa: um disco fora do
+/// círculo atual está na borda do próximo. A ordem aleatória
+/// garante o tempo linear esperado
+fn welzl( mut discos: Vec<Disco>) -> Option<Circulo> {
+  embaralha(&mut discos);
+
+  let mut atual = *discos.first()?;
+  for i in 1..discos.len() {
+    if atual.contains(&discos[i]) {
+      continue;
+    }
+
+    atual = discos[i];
+    for j in 0..i {
+      if atual.contains(&discos[j]) {
+        continue;
+      }
+
+      atual = Disco::de_dois(discos[i], discos[j]);
+      for k in 0..j {
+        if !atual.contains(&discos[k]) {
+          atual = Disco::de_tres(discos[i], discos[j], discos[k]);
+        }
+      }
+    }
+  }
+
+  // o vizinho inteiro do centro real que exige o menor raio
+  let raio = |c: Ponto| discos.iter()
+    .map(|d| fpitagoras(d.c.0 - c.x as f64, d.c.1 - c.y as f64) + d.r)
+    .fold(0.0, f64::max);
+  let (x, y) = (atual.c.0.floor() as i32, atual.c.1.floor() as i32);
+  let vizinhos = [
+    Ponto::new(x, y),
+    Ponto::new(x.saturating_add(1), y),
+    Ponto::new(x, y.saturating_add(1)),
+    Ponto::new(x.saturating_add(1), y.saturating_add(1)),
+  ];
+
+  return vizinhos.iter()
+    .map(|&p| Circulo::from(p, raio(p)))
+    .min_by(|a, b| a.r.total_cmp(&b.r));
+}
+
+
+/// Embaralha com um gerador xorshift de semente fixa, o mesmo
+/// conjunto sempre gera o mesmo resultado
+fn embaralha<T>( v: &mut [T]) {
+  let mut estado: u64 = 0x9E37_79B9_7F4A_7C15 ^ v.len() as u64;
+  for i in (1..v.len()).rev() {
+    v.swap(i, (sorteio(&mut estado) % (i as u64 + 1)) as usize);
+  }
+}
+
+
+/// Produto escalar de `ab` por `ac`
+fn produto_escalar( a: Ponto, b: Ponto, c: Ponto) -> i128 {
+  let (abx, aby) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+  let (acx, acy) = (c.x as i128 - a.x as i128, c.y as i128 - a.y as i128);
+  abx * acx + aby * acy
+}
+
+
+/// Quadrado da distância entre dois pontos, exato
+fn distancia2( a: Ponto, b: Ponto) -> i128 {
+  produto_escalar(a, b, b)
+}
+
+
+/// Comprimento do segmento `ab`
+fn comprimento( a: Ponto, b: Ponto) -> f64 {
+  (distancia2(a, b) as f64).sqrt()
+}
+
+
+/// Retorna a envoltória convexa dos pontos como um polígono em
+/// sentido anti-horário
+pub fn envoltoria_convexa( pontos: &[Ponto]) -> Poligono {
+  Poligono::new(cadeia_monotona(pontos.to_vec()))
+}
+
+
+/// Cadeia monótona: as metades inferior e superior são montadas
+/// sobre os pontos ordenados, descartando curvas à direita e
+/// pontos colineares
+fn cadeia_monotona( mut pontos: Vec<Ponto>) -> Vec<Ponto> {
+  pontos.sort_unstable_by_key(|p| (p.x, p.y));
+  pontos.dedup();
+  if pontos.len() < 3 {
+    return pontos;
+  }
+
+  let mut envoltoria: Vec<Ponto> = Vec::with_capacity(pontos.len() + 1);
+  let inferior = pontos.iter();
+  let superior = pontos.iter().rev().skip(1);
+  let mut base = 2;
+  for (i, &p) in inferior.chain(superior).enumerate() {
+    // a metade superior não desfaz a inferior
+    if i == pontos.len() {
+      base = envoltoria.len() + 1;
+    }
+
+    while envoltoria.len() >= base {
+      let k = envoltoria.len();
+      if orientacao(envoltoria[k - 2], envoltoria[k - 1], p) > 0 {
+        break;
+      }
+      envoltoria.pop();
+    }
+    envoltoria.push(p);
+  }
+
+  // o primeiro ponto volta ao fim da metade superior
+  envoltoria.pop();
+  return envoltoria;
+}
+
+
+/// Verifica se `p`, colinear a `ab`, está entre `a` e `b`
+fn no_segmento( a: Ponto, b: Ponto, p: Ponto) -> bool {
+  a.x.min(b.x) <= p.x && p.x <= a.x.max(b.x) && a.y.min(b.y) <= p.y && p.y <= a.y.max(b.y)
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+
+
+  /// Pontos aleatórios: em uma grade pequena, com muitos
+  /// repetidos e colineares, ou espalhados até os limites de `i32`
+  fn pontos( estado: &mut u64, n: usize) -> Vec<Ponto> {
+    let modo = sorteio(estado) % 3;
+    (0..n).map(|_| {
+      let (x, y) = (sorteio(estado), sorteio(estado));
+      match modo {
+        0 => Ponto::new((x % 7) as i32 - 3, (y % 7) as i32 - 3),
+        1 => Ponto::new((x % 2001) as i32 - 1000, (y % 2001) as i32 - 1000),
+        _ => Ponto::new(x as i32, y as i32),
+      }
+    }).collect()
+  }
+
+
+  /// Confere que `v` é a envoltória dos pontos: começa no mais à
+  /// esquerda, é estritamente convexa em sentido anti-horário,
+  /// usa apenas pontos da entrada e contém todos eles
+  fn confere( v: &[Ponto], pontos: &[Ponto]) {
+    let mut unicos = pontos.to_vec();
+    unicos.sort_unstable_by_key(|p| (p.x, p.y));
+    unicos.dedup();
+
+    assert!(v.iter().all(|p| pontos.contains(p)));
+    let primeiro = unicos.first().copied();
+    assert_eq!(v.first().copied(), primeiro);
+
+    match v.len() {
+      0 => assert!(pontos.is_empty()),
+      1 => assert_eq!(unicos.len(), 1),
+      2 => {
+        assert_ne!(v[0], v[1]);
+        assert!(pontos.iter().all(|&p| orientacao(v[0], v[1], p) == 0 && no_segmento(v[0], v[1], p)));
+      },
+      n => {
+        for i in 0..n {
+          assert!(orientacao(v[i], v[(i + 1) % n], v[(i + 2) % n]) > 0, "vértice {} não é convexo em {:?}", i, v);
+        }
+      },
+    }
+
+    let envoltoria = Envoltoria { pontos: v.to_vec() };
+    assert!(pontos.iter().all(|&p| envoltoria.contains(p)));
+  }
+
+
+  #[test]
+  fn cadeia_monotona_gera_a_envoltoria() {
+    let mut estado = 0x2545f4914f6cdd1d;
+    for n in (0..40).chain([100, 500, 2000].iter().copied()) {
+      for _ in 0..20 {
+        let pontos = pontos(&mut estado, n);
+        let envoltoria = Envoltoria::from(&pontos);
+        confere(envoltoria.vertices(), &pontos);
+        assert_eq!(envoltoria_convexa(&pontos).pontos, envoltoria.vertices());
+      }
+    }
+  }
+
+
+  #[test]
+  fn insercoes_geram_a_mesma_envoltoria() {
+    let mut estado = 0x853c49e6748fea9b;
+    for n in (0..40).chain([100, 500].iter().copied()) {
+      for _ in 0..20 {
+        let pontos = pontos(&mut estado, n);
+        let mut envoltoria = Envoltoria::new();
+        for (i, &p) in pontos.iter().enumerate() {
+          let antes = envoltoria.vertices().to_vec();
+          let mudou = envoltoria.inserir(p);
+          assert_eq!(mudou, antes != envoltoria.vertices());
+          assert_eq!(mudou, !Envoltoria { pontos: antes }.contains(p));
+          assert_eq!(envoltoria.vertices(), Envoltoria::from(&pontos[..=i]).vertices());
+        }
+        confere(envoltoria.vertices(), &pontos);
+
+        let mut estendida = Envoltoria::new();
+        estendida.extend(pontos.iter().copied());
+        assert_eq!(estendida.vertices(), envoltoria.vertices());
+      }
+    }
+  }
+
+
+  #[test]
+  fn conjuntos_degenerados() {
+    let p = Ponto::new;
+    assert!(Envoltoria::from(&[]).is_empty());
+    assert_eq!(Envoltoria::from(&[p(2, 3), p(2, 3)]).vertices(), &[p(2, 3)]);
+
+    // colineares ficam apenas com os extremos
+    let reta = [p(4, 4), p(0, 0), p(2, 2), p(-3, -3), p(1, 1)];
+    assert_eq!(Envoltoria::from(&reta).vertices(), &[p(-3, -3), p(4, 4)]);
+    let vertical = [p(0, 5), p(0, -1), p(0, 2)];
+    assert_eq!(Envoltoria::from(&vertical).vertices(), &[p(0, -1), p(0, 5)]);
+
+    // pontos sobre as arestas não viram vértices
+    let quadrado = [p(0, 0), p(2, 0), p(4, 0), p(4, 2), p(4, 4), p(2, 4), p(0, 4), p(0, 2), p(2, 2)];
+    assert_eq!(Envoltoria::from(&quadrado).vertices(), &[p(0, 0), p(4, 0), p(4, 4), p(0, 4)]);
+
+    // inserir um ponto colinear fora da aresta a estende
+    let mut e = Envoltoria::from(&[p(0, 0), p(4, 0), p(0, 4)]);
+    assert!(e.inserir(p(6, 0)));
+    assert_eq!(e.vertices(), &[p(0, 0), p(6, 0), p(0, 4)]);
+    assert!(!e.inserir(p(3, 0)));
+    assert!(!e.inserir(p(1, 1)));
+  }
+
+
+  #[test]
+  fn limites_de_i32() {
+    let (min, max) = (i32::MIN, i32::MAX);
+    let p = Ponto::new;
+    let cantos = [p(min, min), p(max, min), p(max, max), p(min, max), p(0, 0), p(max, 0), p(min + 1, max - 1)];
+    let envoltoria = Envoltoria::from(&cantos);
+    assert_eq!(envoltoria.vertices(), &[p(min, min), p(max, min), p(max, max), p(min, max)]);
+    confere(envoltoria.vertices(), &cantos);
+
+    // quase colineares, a diferença está no último bit
+    let fino = [p(min, min), p(max, max), p(max - 1, max)];
+    assert_eq!(Envoltoria::from(&fino).len(), 3);
+    let reta = [p(min, min), p(max, max), p(0, 0), p(-1, -1)];
+    assert_eq!(Envoltoria::from(&reta).vertices(), &[p(min, min), p(max, max)]);
+  }
+
+
+  /// Dimensões do retângulo alinhado à aresta `ab` que contém
+  /// os vértices, calculadas sobre todos eles
+  fn retangulo_da_aresta( v: &[Ponto], a: Ponto, b: Ponto) -> (f64, f64) {
+    let len = comprimento(a, b);
+    let ao_longo: Vec<f64> = v.iter().map(|&p| produto_escalar(a, b, p) as f64 / len).collect();
+    let largura = ao_longo.iter().fold(f64::MIN, |m, &x| m.max(x)) - ao_longo.iter().fold(f64::MAX, |m, &x| m.min(x));
+    let altura = v.iter().map(|&p| orientacao(a, b, p) as f64 / len).fold(0.0, f64::max);
+    (largura, altura)
+  }
+
+
+  /// Confere que o retângulo contém os pontos, com a folga do
+  /// ângulo guardado em `f32`
+  fn contem( q: &QuadOrientado, pontos: &[Ponto]) {
+    let (sin, cos) = (q.â as f64).sin_cos();
+    let escala = q.hx.max(q.hy).max(q.c.0.abs()).max(q.c.1.abs()).max(1.0);
+    for p in pontos.iter() {
+      let (dx, dy) = (p.x as f64 - q.c.0, p.y as f64 - q.c.1);
+      let (u, w) = (dx * cos + dy * sin, -dx * sin + dy * cos);
+      assert!(u.abs() <= q.hx + 1e-6 * escala && w.abs() <= q.hy + 1e-6 * escala, "{:?} fora de {:?}", p, q);
+    }
+  }
+
+
+  #[test]
+  fn calibres_concordam_com_a_forca_bruta() {
+    let mut estado = 0xda3e39cb94b95bdb;
+    for n in (1..30).chain([100, 1000].iter().copied()) {
+      for _ in 0..30 {
+        let pontos = pontos(&mut estado, n);
+        let envoltoria = Envoltoria::from(&pontos);
+        let v = envoltoria.vertices();
+
+        // diâmetro: o par mais distante entre todos os pontos
+        let (a, b) = envoltoria.diametro().unwrap();
+        let maior = pontos.iter()
+          .flat_map(|&p| pontos.iter().map(move |&q| distancia2(p, q)))
+          .max()
+          .unwrap();
+        assert_eq!(distancia2(a, b), maior);
+        assert!(v.contains(&a) && v.contains(&b));
+
+        if v.len() < 3 {
+          assert_eq!(envoltoria.largura(), Some(0.0));
+          continue;
+        }
+
+        // largura: a menor altura entre as arestas e o vértice mais distante
+        let arestas: Vec<(Ponto, Ponto)> = (0..v.len()).map(|i| envoltoria.aresta(i)).collect();
+        let largura = arestas.iter().map(|&(a, b)| retangulo_da_aresta(v, a, b).1).fold(f64::INFINITY, f64::min);
+        let calculada = envoltoria.largura().unwrap();
+        assert!((calculada - largura).abs() <= 1e-9 * largura.max(1.0), "{} != {}", calculada, largura);
+
+        // retângulos: o melhor entre os alinhados a cada aresta
+        let dimensoes: Vec<(f64, f64)> = arestas.iter().map(|&(a, b)| retangulo_da_aresta(v, a, b)).collect();
+        let area = dimensoes.iter().map(|&(l, h)| l * h).fold(f64::INFINITY, f64::min);
+        let perimetro = dimensoes.iter().map(|&(l, h)| 2.0 * (l + h)).fold(f64::INFINITY, f64::min);
+
+        let q = envoltoria.retangulo_min_area().unwrap();
+        assert!((4.0 * q.hx * q.hy - area).abs() <= 1e-9 * area.max(1.0), "{} != {}", 4.0 * q.hx * q.hy, area);
+        contem(&q, &pontos);
+
+        let q = envoltoria.retangulo_min_perimetro().unwrap();
+        assert!((4.0 * (q.hx + q.hy) - perimetro).abs() <= 1e-9 * perimetro.max(1.0));
+        contem(&q, &pontos);
+      }
+    }
+  }
+
+
+  #[test]
+  fn calibres_em_formas_conhecidas() {
+    let p = Ponto::new;
+    assert_eq!(Envoltoria::new().diametro(), None);
+    assert_eq!(Envoltoria::new().largura(), None);
+    assert!(Envoltoria::new().retangulo_min_area().is_none());
+
+    let unico = Envoltoria::from(&[p(3, 4)]);
+    assert_eq!(unico.diametro(), Some((p(3, 4), p(3, 4))));
+    assert_eq!(unico.largura(), Some(0.0));
+    let q = unico.retangulo_min_area().unwrap();
+    assert_eq!((q.c, q.hx, q.hy), ((3.0, 4.0), 0.0, 0.0));
+
+    let segmento = Envoltoria::from(&[p(0, 0), p(3, 4), p(6, 8)]);
+    assert_eq!(segmento.diametro(), Some((p(0, 0), p(6, 8))));
+    let q = segmento.retangulo_min_area().unwrap();
+    assert!((q.hx - 5.0).abs() < 1e-9 && q.hy.abs() < 1e-9);
+
+    // retângulo 6 x 2: a largura é a menor dimensão
+    let retangulo = Envoltoria::from(&[p(0, 0), p(6, 0), p(6, 2), p(0, 2), p(3, 1)]);
+    assert_eq!(retangulo.largura(), Some(2.0));
+    let (a, b) = retangulo.diametro().unwrap();
+    assert_eq!(distancia2(a, b), 40);
+    let q = retangulo.retangulo_min_area().unwrap();
+    assert!((q.c.0 - 3.0).abs() < 1e-9 && (q.c.1 - 1.0).abs() < 1e-9);
+    assert!((4.0 * q.hx * q.hy - 12.0).abs() < 1e-9);
+
+    // losango: o menor retângulo é o próprio losango, com área
+    // 18 contra os 36 do alinhado aos eixos
+    let losango = Envoltoria::from(&[p(0, -3), p(3, 0), p(0, 3), p(-3, 0)]);
+    let q = losango.retangulo_min_area().unwrap();
+    assert!((4.0 * q.hx * q.hy - 18.0).abs() < 1e-6);
+    assert!((losango.largura().unwrap() - 18f64.sqrt()).abs() < 1e-12);
+
+    // triângulo retângulo: a menor área é a do retângulo sobre um cateto
+    let triangulo = Envoltoria::from(&[p(0, 0), p(4, 0), p(0, 3)]);
+    let q = triangulo.retangulo_min_area().unwrap();
+    assert!((4.0 * q.hx * q.hy - 12.0).abs() < 1e-9);
+    assert!((triangulo.largura().unwrap() - 2.4).abs() < 1e-12);
+  }
+
+
+  #[test]
+  fn calibres_nos_limites_de_i32() {
+    let (min, max) = (i32::MIN, i32::MAX);
+    let p = Ponto::new;
+    let cantos = [p(min, min), p(max, min), p(max, max), p(min, max)];
+    let envoltoria = Envoltoria::from(&cantos);
+
+    let lado = max as f64 - min as f64;
+    let (a, b) = envoltoria.diametro().unwrap();
+    assert_eq!(distancia2(a, b), 2 * (lado as i128) * (lado as i128));
+    assert_eq!(envoltoria.largura(), Some(lado));
+
+    let q = envoltoria.retangulo_min_area().unwrap();
+    assert!((q.hx - lado / 2.0).abs() <= 1e-9 * lado && (q.hy - lado / 2.0).abs() <= 1e-9 * lado);
+    contem(&q, &cantos);
+    contem(&envoltoria.retangulo_min_perimetro().unwrap(), &cantos);
+  }
+
+
+  /// Menor círculo real que contém os pontos, entre os definidos
+  /// por dois ou três deles
+  fn circulo_exato( pontos: &[Ponto]) -> f64 {
+    let f = |p: Ponto| (p.x as f64, p.y as f64);
+    let mut candidatos = Vec::new();
+    for (i, &a) in pontos.iter().enumerate() {
+      candidatos.push((f(a), 0.0));
+      for (j, &b) in pontos.iter().enumerate().skip(i + 1) {
+        let c = ((f(a).0 + f(b).0) / 2.0, (f(a).1 + f(b).1) / 2.0);
+        candidatos.push((c, comprimento(a, b) / 2.0));
+        for &c in pontos[j + 1..].iter() {
+          // circuncentro relativo ao primeiro ponto
+          let (bx, by) = (f(b).0 - f(a).0, f(b).1 - f(a).1);
+          let (cx, cy) = (f(c).0 - f(a).0, f(c).1 - f(a).1);
+          let d = 2.0 * (bx * cy - by * cx);
+          if d != 0.0 {
+            let (b2, c2) = (bx * bx + by * by, cx * cx + cy * cy);
+            let (ux, uy) = ((cy * b2 - by * c2) / d, (bx * c2 - cx * b2) / d);
+            candidatos.push(((f(a).0 + ux, f(a).1 + uy), fpitagoras(ux, uy)));
+          }
+        }
+      }
+    }
+
+    return candidatos.iter()
+      .filter(|(c, r)| pontos.iter().all(|p| fpitagoras(p.x as f64 - c.0, p.y as f64 - c.1) <= r * (1.0 + 1e-12) + 1e-9))
+      .map(|&(_, r)| r)
+      .fold(f64::INFINITY, f64::min);
+  }
+
+
+  /// Distância do centro inteiro do círculo a um ponto real
+  fn distancia( c: &Circulo, p: (f64, f64)) -> f64 {
+    fpitagoras(p.0 - c.p.x as f64, p.1 - c.p.y as f64)
+  }
+
+
+  #[test]
+  fn circulo_minimo_contem_os_pontos() {
+    let mut estado = 0x6a09e667f3bcc909;
+    assert!(circulo_minimo(&[]).is_none());
+
+    for n in 1..12 {
+      for _ in 0..200 {
+        let pontos: Vec<Ponto> = pontos(&mut estado, n).into_iter().map(|p| Ponto::new(p.x % 50, p.y % 50)).collect();
+        let c = circulo_minimo(&pontos).unwrap();
+        assert!(pontos.iter().all(|p| distancia(&c, (p.x as f64, p.y as f64)) <= c.r), "{:?} não contém {:?}", c, pontos);
+
+        // o centro inteiro está a menos de meia diagonal do real
+        let exato = circulo_exato(&pontos);
+        assert!(c.r >= exato - 1e-9 && c.r <= exato + 0.5f64.sqrt() + 1e-9, "{} longe de {} em {:?}", c.r, exato, pontos);
+      }
+    }
+
+    // muitos pontos, inclusive nos limites
+    for n in [100, 1000, 10000].iter() {
+      let pontos = pontos(&mut estado, *n);
+      let c = circulo_minimo(&pontos).unwrap();
+      assert!(pontos.iter().all(|p| distancia(&c, (p.x as f64, p.y as f64)) <= c.r));
+    }
+  }
+
+
+  #[test]
+  fn circulo_minimo_conhecido() {
+    let p = Ponto::new;
+    let c = circulo_minimo(&[p(5, 5)]).unwrap();
+    assert_eq!((c.p, c.r), (p(5, 5), 0.0));
+
+    let c = circulo_minimo(&[p(-4, 0), p(4, 0), p(0, 1), p(1, -1)]).unwrap();
+    assert_eq!((c.p, c.r), (p(0, 0), 4.0));
+
+    // repetidos e colineares
+    let c = circulo_minimo(&[p(0, 0), p(10, 0), p(10, 0), p(5, 0), p(0, 0)]).unwrap();
+    assert_eq!((c.p, c.r), (p(5, 0), 5.0));
+
+    // triângulo agudo: o circuncírculo
+    let c = circulo_minimo(&[p(0, 0), p(6, 0), p(3, 5)]).unwrap();
+    assert!((c.r - circulo_exato(&[p(0, 0), p(6, 0), p(3, 5)])).abs() < 0.71);
+  }
+
+
+  #[test]
+  fn circulo_minimo_nos_limites_de_i32() {
+    let (min, max) = (i32::MIN, i32::MAX);
+    let p = Ponto::new;
+    let cantos = [p(min, min), p(max, max), p(min, max), p(max, min), p(0, 0)];
+    let c = circulo_minimo(&cantos).unwrap();
+    assert!(cantos.iter().all(|q| distancia(&c, (q.x as f64, q.y as f64)) <= c.r));
+    assert!((c.r - (max as f64 - min as f64) / 2.0 * 2f64.sqrt()).abs() < 1.0);
+
+    let canto = [p(max, max), p(max - 3, max)];
+    let c = circulo_minimo(&canto).unwrap();
+    assert!(canto.iter().all(|q| distancia(&c, (q.x as f64, q.y as f64)) <= c.r));
+    assert!(c.r <= 1.5 + 0.5f64.sqrt());
+  }
+
+
+  #[test]
+  fn circulo_minimo_contem_as_formas() {
+    use crate::object_data::{Bloco, Segmento};
+
+    let mut estado = 0xbb67ae8584caa73b;
+    assert!(circulo_minimo_formas(&[]).is_none());
+
+    for n in 1..15 {
+      for _ in 0..100 {
+        let formas: Vec<Forma> = (0..n).map(|_| {
+          let mut v = || (sorteio(&mut estado) % 200) as i32 - 100;
+          let (a, b) = (Ponto::new(v(), v()), Ponto::new(v(), v()));
+          match v().rem_euclid(3) {
+            0 => Forma::Circulo(Circulo::from(a, (b.x.abs() % 40) as f64 + 0.25)),
+            1 => Forma::Bloco(Bloco::from(a, b)),
+            _ => Forma::Segmento(Segmento::from(a, b)),
+          }
+        }).collect();
+
+        let c = circulo_minimo_formas(&formas).unwrap();
+        let mut discos = Vec::new();
+        for f in formas.iter() {
+          match f {
+            Forma::Circulo(d) => discos.push(((d.p.x as f64, d.p.y as f64), d.r)),
+            _                 => discos.extend(f.vertices().into_iter().map(|v| (v, 0.0))),
+          }
+        }
+        for (centro, r) in discos.iter() {
+          assert!(distancia(&c, *centro) + r <= c.r, "{:?} não contém {:?}", c, formas);
+        }
+
+        // nenhum círculo menor que a metade do maior afastamento
+        // entre dois discos contém ambos
+        let mut limite: f64 = 0.0;
+        for (i, a) in discos.iter().enumerate() {
+          limite = limite.max(a.1);
+          for b in discos[i + 1..].iter() {
+            limite = limite.max((fpitagoras(a.0.0 - b.0.0, a.0.1 - b.0.1) + a.1 + b.1) / 2.0);
+          }
+        }
+        assert!(c.r >= limite - 1e-9);
+      }
+    }
+
+    // pontos como formas dão o mesmo círculo dos pontos
+    let p = Ponto::new;
+    let pontos = [p(0, 0), p(9, 2), p(4, 7), p(-3, 5)];
+    let formas: Vec<Forma> = pontos.iter().map(|&q| Forma::Segmento(Segmento::from(q, q))).collect();
+    let (a, b) = (circulo_minimo(&pontos).unwrap(), circulo_minimo_formas(&formas).unwrap());
+    assert_eq!((a.p, a.r), (b.p, b.r));
+
+    // um círculo que contém os demais é o resultado
+    let formas = [
+      Forma::Circulo(Circulo::from(p(0, 0), 10.0)),
+      Forma::Circulo(Circulo::from(p(3, 3), 2.0)),
+      Forma::Bloco(Bloco::from(p(-2, -2), p(2, 2))),
+    ];
+    let c = circulo_minimo_formas(&formas).unwrap();
+    assert_eq!((c.p, c.r), (p(0, 0), 10.0));
+  }
+}
@@ -0,0 +1,654 @@
+/// Valores JSON, com leitura e escrita sem dependências.
+///
+/// A leitura segue a especificação, incluindo escapes `\u` com
+/// pares substitutos, e informa a posição do primeiro erro. A
+/// escrita é compacta, mantém a ordem das chaves dos objetos e
+/// troca números não finitos por `null`.
+///
+/// Inteiros escritos sem casas nem expoente são lidos sem perdas
+/// enquanto couberem em i128, o que cobre todo i64 e u64, e são
+/// reescritos com os mesmos dígitos. Identificadores acima de
+/// 2^53 não são alterados pelo caminho.
+use std::fmt;
+use std::str::FromStr;
+
+
+/// Profundidade máxima de listas e objetos aninhados
+static PROFUNDIDADE: usize = 128;
+
+
+// Estruturas
+/// Valor JSON
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+  Nulo,
+  Logico(bool),
+  Numero(f64),
+  Inteiro(i128),
+  Texto(String),
+  Lista(Vec<Json>),
+  Objeto(Vec<(String, Json)>)
+}
+
+
+/// Erro de leitura de um texto JSON
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErroJson {
+  /// Posição em caracteres, a partir de 0
+  pub posicao: usize,
+
+  /// Descrição do erro
+  pub mensagem: String
+}
+
+
+/// Leitor sobre os caracteres de um texto
+struct Leitor<'a> {
+  chars: std::iter::Peekable<std::str::Chars<'a>>,
+  posicao: usize,
+  profundidade: usize
+}
+
+
+// Métodos relacionados aos valores
+impl Json {
+
+
+  /// Gera um objeto a partir de pares de chave e valor
+  pub fn objeto( pares: Vec<(&str, Json)>) -> Self {
+    Json::Objeto(pares.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+  }
+
+
+  /// Retorna o valor de uma chave, se for um objeto
+  pub fn get( &self, chave: &str) -> Option<&Json> {
+    match self {
+      Json::Objeto(pares) => pares.iter().find(|(k, _)| k == chave).map(|(_, v)| v),
+      _                   => None,
+    }
+  }
+
+
+  /// Retorna o número, se for um
+  pub fn numero( &self ) -> Option<f64> {
+    match self {
+      Json::Numero(v)  => Some(*v),
+      Json::Inteiro(i) => Some(*i as f64),
+      _                => None,
+    }
+  }
+
+
+  /// Retorna o texto, se for um
+  pub fn texto( &self ) -> Option<&str> {
+    match self {
+      Json::Texto(s) => Some(s),
+      _              => None,
+    }
+  }
+
+
+  /// Retorna o valor lógico, se for um
+  pub fn logico( &self ) -> Option<bool> {
+    match self {
+      Json::Logico(b) => Some(*b),
+      _               => None,
+    }
+  }
+
+
+  /// Retorna os itens, se for uma lista
+  pub fn lista( &self ) -> Option<&[Json]> {
+    match self {
+      Json::Lista(l) => Some(l),
+      _              => None,
+    }
+  }
+
+
+  /// Nome do tipo do valor, para mensagens de erro
+  pub fn tipo( &self ) -> &'static str {
+    match self {
+      Json::Nulo       => "null",
+      Json::Logico(_)  => "boolean",
+      Json::Numero(_)  => "number",
+      Json::Inteiro(_) => "number",
+      Json::Texto(_)   => "string",
+      Json::Lista(_)   => "array",
+      Json::Objeto(_)  => "object",
+    }
+  }
+}
+
+
+impl From<f64> for Json {
+  fn from( v: f64) -> Self {
+    Json::Numero(v)
+  }
+}
+
+impl From<bool> for Json {
+  fn from( b: bool) -> Self {
+    Json::Logico(b)
+  }
+}
+
+impl From<&str> for Json {
+  fn from( s: &str) -> Self {
+    Json::Texto(s.to_string())
+  }
+}
+
+impl From<String> for Json {
+  fn from( s: String) -> Self {
+    Json::Texto(s)
+  }
+}
+
+impl From<Vec<Json>> for Json {
+  fn from( l: Vec<Json>) -> Self {
+    Json::Lista(l)
+  }
+}
+
+
+impl fmt::Display for Json {
+  fn fmt( &self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Json::Nulo                        => write!(f, "null"),
+      Json::Logico(b)                   => write!(f, "{}", b),
+      Json::Numero(v) if v.is_finite()  => write!(f, "{}", v),
+      Json::Numero(_)                   => write!(f, "null"),
+      Json::Inteiro(i)                  => write!(f, "{}", i),
+      Json::Texto(s)                    => write!(f, "{}", escapa(s)),
+      Json::Lista(l)                    => {
+        write!(f, "[")?;
+        for (i, v) in l.iter().enumerate() {
+          if i > 0 {
+            write!(f, ",")?;
+          }
+          write!(f, "{}", v)?;
+        }
+        write!(f, "]")
+      },
+      Json::Objeto(pares)               => {
+        write!(f, "{{")?;
+        for (i, (k, v)) in pares.iter().enumerate() {
+          if i > 0 {
+            write!(f, ",")?;
+          }
+          write!(f, "{}:{}", escapa(k), v)?;
+        }
+        write!(f, "}}")
+      },
+    }
+  }
+}
+
+
+impl FromStr for Json {
+  type Err = ErroJson;
+
+  fn from_str( texto: &str) -> Result<Self, Self::Err> {
+    let mut leitor = Leitor {
+      chars: texto.chars().peekable(),
+      posicao: 0,
+      profundidade: 0
+    };
+
+    let v = leitor.valor()?;
+    leitor.espacos();
+    if leitor.chars.peek().is_some() {
+      return Err(leitor.erro("conteúdo após o fim do valor"));
+    }
+
+    return Ok(v);
+  }
+}
+
+
+impl fmt::Display for ErroJson {
+  fn fmt( &self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "posição {}: {}", self.posicao, self.mensagem)
+  }
+}
+
+impl std::error::Error for ErroJson {}
+
+
+// Funções
+/// Escreve um texto entre aspas, com os escapes do JSON
+pub fn escapa( s: &str) -> String {
+  let mut saida = String::with_capacity(s.len() + 2);
+  saida.push('"');
+  for c in s.chars() {
+    match c {
+      '"'  => saida.push_str("\\\""),
+      '\\' => saida.push_str("\\\\"),
+      '\n' => saida.push_str("\\n"),
+      '\r' => saida.push_str("\\r"),
+      '\t' => saida.push_str("\\t"),
+      c if (c as u32) < 0x20 => saida.push_str(&format!("\\u{:04x}", c as u32)),
+      c    => saida.push(c),
+    }
+  }
+  saida.push('"');
+  return saida;
+}
+
+
+// Métodos relacionados ao leitor
+impl<'a> Leitor<'a> {
+
+
+  /// Gera um erro na posição atual
+  fn erro( &self, mensagem: &str) -> ErroJson {
+    ErroJson {
+      posicao: self.posicao,
+      mensagem: mensagem.to_string()
+    }
+  }
+
+
+  /// Consome o próximo caractere
+  fn avanca( &mut self ) -> Option<char> {
+    let c = self.chars.next();
+    if c.is_some() {
+      self.posicao += 1;
+    }
+    return c;
+  }
+
+
+  /// Consome o caractere esperado ou retorna um erro
+  fn espera( &mut self, esperado: char) -> Result<(), ErroJson> {
+    match self.avanca() {
+      Some(c) if c == esperado => Ok(()),
+      Some(c)                  => Err(self.erro(&format!("esperava '{}', encontrou '{}'", esperado, c))),
+      None                     => Err(self.erro(&format!("esperava '{}' no fim do texto", esperado))),
+    }
+  }
+
+
+  /// Ignora espaços em branco
+  fn espacos( &mut self ) {
+    while let Some(' ' | '\t' | '\n' | '\r') = self.chars.peek() {
+      self.avanca();
+    }
+  }
+
+
+  /// Lê um valor qualquer
+  fn valor( &mut self ) -> Result<Json, ErroJson> {
+    self.espacos();
+    match self.chars.peek().copied() {
+      Some('{')                     => self.aninhado(Leitor::objeto),
+      Some('[')                     => self.aninhado(Leitor::lista),
+      Some('"')                     => Ok(Json::Texto(self.texto()?)),
+      Some('t')                     => self.palavra("true", Json::Logico(true)),
+      Some('f')                     => self.palavra("false", Json::Logico(false)),
+      Some('n')                     => self.palavra("null", Json::Nulo),
+      Some(c) if c == '-' || c.is_ascii_digit() => self.numero(),
+      Some(c)                       => Err(self.erro(&format!("caractere inesperado '{}'", c))),
+      None                          => Err(self.erro("fim inesperado do texto")),
+    }
+  }
+
+
+  /// Lê uma lista ou objeto, limitando a profundidade
+  fn aninhado( &mut self, ler: fn(&mut Self) -> Result<Json, ErroJson>) -> Result<Json, ErroJson> {
+    if self.profundidade == PROFUNDIDADE {
+      return Err(self.erro("aninhamento profundo demais"));
+    }
+
+    self.profundidade += 1;
+    let v = ler(self);
+    self.profundidade -= 1;
+    return v;
+  }
+
+
+  /// Lê uma palavra reservada
+  fn palavra( &mut self, palavra: &str, v: Json) -> Result<Json, ErroJson> {
+    for esperado in palavra.chars() {
+      if self.avanca() != Some(esperado) {
+        return Err(self.erro(&format!("esperava '{}'", palavra)));
+      }
+    }
+
+    return Ok(v);
+  }
+
+
+  /// Lê um número, com a gramática estrita do JSON
+  fn numero( &mut self ) -> Result<Json, ErroJson> {
+    let mut texto = String::new();
+    let digitos = |leitor: &mut Self, texto: &mut String| {
+      let mut n = 0;
+      while let Some(c) = leitor.chars.peek().copied().filter(char::is_ascii_digit) {
+        texto.push(c);
+        leitor.avanca();
+        n += 1;
+      }
+      n
+    };
+
+    if self.chars.peek() == Some(&'-') {
+      texto.push('-');
+      self.avanca();
+    }
+
+    let inicio = self.chars.peek().copied();
+    let inteiros = digitos(self, &mut texto);
+    if inteiros == 0 || (inicio == Some('0') && inteiros > 1) {
+      return Err(self.erro("número inválido"));
+    }
+
+    if self.chars.peek() == Some(&'.') {
+      texto.push('.');
+      self.avanca();
+      if digitos(self, &mut texto) == 0 {
+        return Err(self.erro("número sem casas após o ponto"));
+      }
+    }
+
+    if let Some('e' | 'E') = self.chars.peek() {
+      texto.push('e');
+      self.avanca();
+      if let Some(c @ ('+' | '-')) = self.chars.peek().copied() {
+        texto.push(c);
+        self.avanca();
+      }
+      if digitos(self, &mut texto) == 0 {
+        return Err(self.erro("expoente sem dígitos"));
+      }
+    }
+
+    // inteiros ficam exatos, menos o -0 que perderia o sinal
+    if !texto.contains(['.', 'e']) && texto != "-0" {
+      if let Ok(i) = texto.parse::<i128>() {
+        return Ok(Json::Inteiro(i));
+      }
+    }
+
+    match texto.parse::<f64>() {
+      Ok(v)  => Ok(Json::Numero(v)),
+      Err(_) => Err(self.erro("número inválido")),
+    }
+  }
+
+
+  /// Lê um texto entre aspas
+  fn texto( &mut self ) -> Result<String, ErroJson> {
+    self.espera('"')?;
+    let mut s = String::new();
+
+    loop {
+      match self.avanca() {
+        Some('"')  => return Ok(s),
+        Some('\\') => {
+          let c = match self.avanca() {
+            Some('"')  => '"',
+            Some('\\') => '\\',
+            Some('/')  => '/',
+            Some('b')  => '\u{8}',
+            Some('f')  => '\u{c}',
+            Some('n')  => '\n',
+            Some('r')  => '\r',
+            Some('t')  => '\t',
+            Some('u')  => self.unicode()?,
+            _          => return Err(self.erro("escape inválido")),
+          };
+          s.push(c);
+        },
+        Some(c) if (c as u32) < 0x20 => return Err(self.erro("caractere de controle em um texto")),
+        Some(c)    => s.push(c),
+        None       => return Err(self.erro("texto sem aspas finais")),
+      }
+    }
+  }
+
+
+  /// Lê um escape `\u`, juntando pares substitutos
+  fn unicode( &mut self ) -> Result<char, ErroJson> {
+    let alto = self.hexadecimal()?;
+    if !(0xD800..0xDC00).contains(&alto) {
+      return char::from_u32(alto).ok_or_else(|| self.erro("escape unicode inválido"));
+    }
+
+    if self.avanca() != Some('\\') || self.avanca() != Some('u') {
+      return Err(self.erro("par substituto incompleto"));
+    }
+
+    let baixo = self.hexadecimal()?;
+    if !(0xDC00..0xE000).contains(&baixo) {
+      return Err(self.erro("par substituto inválido"));
+    }
+
+    let c = 0x10000 + ((alto - 0xD800) << 10) + (baixo - 0xDC00);
+    return char::from_u32(c).ok_or_else(|| self.erro("escape unicode inválido"));
+  }
+
+
+  /// Lê quatro dígitos hexadecimais
+  fn hexadecimal( &mut self ) -> Result<u32, ErroJson> {
+    let mut v = 0;
+    for _ in 0..4 {
+      match self.avanca().and_then(|c| c.to_digit(16)) {
+        Some(d) => v = v * 16 + d,
+        None    => return Err(self.erro("escape unicode inválido")),
+      }
+    }
+
+    return Ok(v);
+  }
+
+
+  /// Lê uma lista
+  fn lista( &mut self ) -> Result<Json, ErroJson> {
+    self.espera('[')?;
+    let mut itens = Vec::new();
+
+    self.espacos();
+    if self.chars.peek() == Some(&']') {
+      self.avanca();
+      return Ok(Json::Lista(itens));
+    }
+
+    loop {
+      itens.push(self.valor()?);
+      self.espacos();
+      match self.avanca() {
+        Some(',') => continue,
+        Some(']') => return Ok(Json::Lista(itens)),
+        _         => return Err(self.erro("esperava ',' ou ']'")),
+      }
+    }
+  }
+
+
+  /// Lê um objeto, chaves repetidas mantêm o último valor
+  fn objeto( &mut self ) -> Result<Json, ErroJson> {
+    self.espera('{')?;
+    let mut pares: Vec<(String, Json)> = Vec::new();
+
+    self.espacos();
+    if self.chars.peek() == Some(&'}') {
+      self.avanca();
+      return Ok(Json::Objeto(pares));
+    }
+
+    loop {
+      self.espacos();
+      if self.chars.peek() != Some(&'"') {
+        return Err(self.erro("esperava uma chave entre aspas"));
+      }
+      let chave = self.texto()?;
+      self.espacos();
+      self.espera(':')?;
+      let v = self.valor()?;
+
+      match pares.iter_mut().find(|(k, _)| *k == chave) {
+        Some(par) => par.1 = v,
+        None      => pares.push((chave, v)),
+      }
+
+      self.espacos();
+      match self.avanca() {
+        Some(',') => continue,
+        Some('}') => return Ok(Json::Objeto(pares)),
+        _         => return Err(self.erro("esperava ',' ou '}'")),
+      }
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+
+
+  fn le( texto: &str) -> Json {
+    texto.parse().unwrap_or_else(|e| panic!("{}: {}", texto, e))
+  }
+
+
+  fn erro( texto: &str) -> ErroJson {
+    match texto.parse::<Json>() {
+      Ok(v)  => panic!("{} foi lido como {:?}", texto, v),
+      Err(e) => e,
+    }
+  }
+
+
+  #[test]
+  fn escapes_de_texto() {
+    assert_eq!(le(r#""a\"b\\c\/d""#), Json::from("a\"b\\c/d"));
+    assert_eq!(le(r#""\b\f\n\r\t""#), Json::from("\u{8}\u{c}\n\r\t"));
+    assert_eq!(le(r#""\u0041\u00e9\u4e2d""#), Json::from("Aé中"));
+    assert_eq!(le(r#""\u0000""#), Json::from("\u{0}"));
+    assert_eq!(le("\"já é UTF-8 😀\""), Json::from("já é UTF-8 😀"));
+
+    // a escrita é relida sem perdas
+    let texto = "aspas \" barra \\ controle \u{1} \u{1f} tab \t fim";
+    assert_eq!(escapa(texto), r#""aspas \" barra \\ controle \u0001 \u001f tab \t fim""#);
+    assert_eq!(le(&escapa(texto)), Json::from(texto));
+  }
+
+
+  #[test]
+  fn pares_substitutos() {
+    assert_eq!(le(r#""\ud83d\ude00""#), Json::from("😀"));
+    assert_eq!(le(r#""\uD834\uDD1E""#), Json::from("𝄞"));
+    assert_eq!(le(r#""\udbff\udfff""#), Json::from("\u{10ffff}"));
+
+    assert_eq!(erro(r#""\ud83d""#).mensagem, "par substituto incompleto");
+    assert_eq!(erro(r#""\ud83dx""#).mensagem, "par substituto incompleto");
+    assert_eq!(erro(r#""\ud83d\u0041""#).mensagem, "par substituto inválido");
+    assert_eq!(erro(r#""\ud83d\ud83d""#).mensagem, "par substituto inválido");
+    assert_eq!(erro(r#""\ude00""#).mensagem, "escape unicode inválido");
+    assert_eq!(erro(r#""\u12g4""#).mensagem, "escape unicode inválido");
+    assert_eq!(erro(r#""\u12""#).mensagem, "escape unicode inválido");
+  }
+
+
+  #[test]
+  fn numeros() {
+    let casos = [
+      ("0", 0.0),
+      ("-0", -0.0),
+      ("7", 7.0),
+      ("-12.5", -12.5),
+      ("1e3", 1000.0),
+      ("1E+3", 1000.0),
+      ("2.5e-3", 0.0025),
+      ("0.1", 0.1),
+      ("123456789012345678901234567890", 123456789012345678901234567890.0),
+      ("1e400", f64::INFINITY),
+    ];
+    for (texto, v) in casos.iter() {
+      assert_eq!(le(texto).numero().map(f64::to_bits), Some(v.to_bits()), "{}", texto);
+    }
+
+    let invalidos = [
+      ("01", "número inválido"),
+      ("-", "número inválido"),
+      ("-a", "número inválido"),
+      ("1.", "número sem casas após o ponto"),
+      ("1.e3", "número sem casas após o ponto"),
+      ("1e", "expoente sem dígitos"),
+      ("1e+", "expoente sem dígitos"),
+      (".5", "caractere inesperado '.'"),
+      ("+1", "caractere inesperado '+'"),
+      ("0x10", "conteúdo após o fim do valor"),
+    ];
+    for (texto, mensagem) in invalidos.iter() {
+      assert_eq!(erro(texto).mensagem, *mensagem, "{}", texto);
+    }
+
+    // inteiros são relidos e reescritos sem perdas
+    let inteiros = ["9007199254740993", "-9223372036854775808", "18446744073709551615", "0", "-7"];
+    for texto in inteiros.iter() {
+      assert_eq!(le(texto), Json::Inteiro(texto.parse().unwrap()));
+      assert_eq!(le(texto).to_string(), *texto);
+    }
+    assert_eq!(le("[9007199254740993, 1.0]").to_string(), "[9007199254740993,1]");
+    assert_eq!(le("9007199254740993").numero(), Some(9007199254740992.0));
+    assert_eq!(le("1e2"), Json::Numero(100.0));
+    assert_eq!(le("-0"), Json::Numero(-0.0));
+
+    // números não finitos são escritos como null
+    assert_eq!(Json::Numero(f64::NAN).to_string(), "null");
+    assert_eq!(Json::Numero(-1.5).to_string(), "-1.5");
+  }
+
+
+  #[test]
+  fn aninhamento() {
+    let v = le(r#" { "a" : [1, {"b": [true, false, null]}, []], "c": {}, "a": "último" } "#);
+    assert_eq!(v, Json::objeto(vec![
+      ("a", Json::from("último")),
+      ("c", Json::Objeto(Vec::new())),
+    ]));
+
+    let v = le(r#"[[1, [2, [3]]], {"x": {"y": {"z": "fundo"}}}]"#);
+    assert_eq!(v.to_string(), r#"[[1,[2,[3]]],{"x":{"y":{"z":"fundo"}}}]"#);
+    assert_eq!(le(&v.to_string()), v);
+
+    // até o limite é aceito, depois dele é um erro sem esgotar a pilha
+    let raso = format!("{}{}", "[".repeat(PROFUNDIDADE), "]".repeat(PROFUNDIDADE));
+    assert_eq!(le(&raso).to_string(), raso);
+    let fundo = format!("{}{}", "[".repeat(PROFUNDIDADE + 1), "]".repeat(PROFUNDIDADE + 1));
+    assert_eq!(erro(&fundo), ErroJson { posicao: PROFUNDIDADE, mensagem: "aninhamento profundo demais".to_string() });
+    assert_eq!(erro(&"{\"a\":".repeat(100_000)).mensagem, "aninhamento profundo demais");
+  }
+
+
+  #[test]
+  fn textos_mal_formados() {
+    let casos = [
+      ("", 0, "fim inesperado do texto"),
+      ("   ", 3, "fim inesperado do texto"),
+      ("[1, 2", 5, "esperava ',' ou ']'"),
+      ("[1 2]", 4, "esperava ',' ou ']'"),
+      ("[1,]", 3, "caractere inesperado ']'"),
+      ("{\"a\" 1}", 6, "esperava ':', encontrou '1'"),
+      ("{\"a\": 1,}", 8, "esperava uma chave entre aspas"),
+      ("{a: 1}", 1, "esperava uma chave entre aspas"),
+      ("{\"a\": 1", 7, "esperava ',' ou '}'"),
+      ("\"sem fim", 8, "texto sem aspas finais"),
+      ("\"a\nb\"", 3, "caractere de controle em um texto"),
+      ("\"\\x\"", 3, "escape inválido"),
+      ("tru", 3, "esperava 'true'"),
+      ("nul1", 4, "esperava 'null'"),
+      ("true false", 5, "conteúdo após o fim do valor"),
+      ("'a'", 0, "caractere inesperado '''"),
+      ("[\"é\", x]", 6, "caractere inesperado 'x'"),
+    ];
+    for (texto, posicao, mensagem) in casos.iter() {
+      let e = erro(texto);
+      assert_eq!((e.posicao, e.mensagem.as_str()), (*posicao, *mensagem), "{:?}", texto);
+    }
+  }
+}
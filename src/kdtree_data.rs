@@ -0,0 +1,469 @@
+/// Consultas de proximidade sobre conjuntos de pontos: uma
+/// árvore k-d bidimensional e o par de pontos mais próximos.
+///
+/// A árvore é estática e balanceada, guardada em um único vetor
+/// em que cada intervalo tem a raiz na posição do meio e os eixos
+/// de corte se alternam entre x e y. A construção custa
+/// O(n log n) e, quando os pontos mudam, a árvore é reconstruída.
+///
+/// As consultas retornam os índices dos pontos na lista usada na
+/// construção. Distâncias são comparadas pelo seu quadrado em
+/// inteiros de 128 bits e, nas consultas da árvore, empates são
+/// decididos pelo menor índice.
+pub use super::vector_data::Ponto as Ponto;
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+
+// Estruturas
+/// Árvore k-d de pontos
+#[derive(Debug, Clone, Default)]
+pub struct ArvoreKd {
+  /// Pontos com seus índices originais, na ordem da árvore
+  itens: Vec<(Ponto, usize)>
+}
+
+
+// Métodos relacionados à árvore
+impl ArvoreKd {
+
+
+  /// Gera uma árvore sobre os pontos
+  pub fn new( pontos: &[Ponto]) -> Self {
+    let mut arvore = Self::default();
+    arvore.reconstruir(pontos);
+    return arvore;
+  }
+
+
+  /// Descarta a árvore atual e a constrói sobre outros pontos
+  pub fn reconstruir( &mut self, pontos: &[Ponto]) {
+    self.itens.clear();
+    self.itens.extend(pontos.iter().copied().zip(0..));
+    constroi(&mut self.itens, true);
+  }
+
+
+  /// Quantidade de pontos
+  pub fn len( &self ) -> usize {
+    self.itens.len()
+  }
+
+
+  /// Verifica se a árvore não possui pontos
+  pub fn is_empty( &self ) -> bool {
+    self.itens.is_empty()
+  }
+
+
+  /// Retorna o índice do ponto mais próximo do alvo
+  pub fn mais_proximo( &self, alvo: Ponto) -> Option<usize> {
+    let mut melhor: Option<(i128, usize)> = None;
+    self.visita(0, self.itens.len(), true, alvo, &mut |d, i| {
+      if melhor.is_none_or(|m| (d, i) < m) {
+        melhor = Some((d, i));
+      }
+      melhor.map_or(i128::MAX, |m| m.0)
+    });
+
+    return melhor.map(|(_, i)| i);
+  }
+
+
+  /// Retorna os índices dos `k` pontos mais próximos do alvo, do
+  /// mais próximo ao mais distante
+  pub fn k_mais_proximos( &self, alvo: Ponto, k: usize) -> Vec<usize> {
+    if k == 0 {
+      return Vec::new();
+    }
+
+    // o topo do heap é o pior dos k melhores até aqui
+    let mut melhores: BinaryHeap<(i128, usize)> = BinaryHeap::with_capacity(k + 1);
+    self.visita(0, self.itens.len(), true, alvo, &mut |d, i| {
+      if melhores.len() < k {
+        melhores.push((d, i));
+      } else if melhores.peek().is_some_and(|&m| (d, i) < m) {
+        melhores.pop();
+        melhores.push((d, i));
+      }
+
+      if melhores.len() < k {
+        return i128::MAX;
+      }
+      melhores.peek().map_or(i128::MAX, |m| m.0)
+    });
+
+    return melhores.into_sorted_vec().into_iter().map(|(_, i)| i).collect();
+  }
+
+
+  /// Retorna os índices dos pontos a uma distância de até `raio`
+  /// do alvo, em ordem crescente de índice
+  pub fn no_raio( &self, alvo: Ponto, raio: f64) -> Vec<usize> {
+    if raio.is_nan() || raio < 0.0 {
+      return Vec::new();
+    }
+
+    // distâncias ao quadrado são inteiras, basta a parte inteira
+    // do quadrado do raio
+    let limite = quadrado_inteiro(raio);
+
+    let mut dentro = Vec::new();
+    self.visita(0, self.itens.len(), true, alvo, &mut |d, i| {
+      if d <= limite {
+        dentro.push(i);
+      }
+      limite
+    });
+
+    dentro.sort_unstable();
+    return dentro;
+  }
+
+
+  /// Percorre os nós do intervalo [lo, hi) mais próximos primeiro.
+  /// `f` recebe a distância ao quadrado e o índice de cada ponto
+  /// visitado e retorna a maior distância ao quadrado que ainda
+  /// interessa, lados além dela são descartados
+  fn visita<F>( &self, lo: usize, hi: usize, eixo_x: bool, alvo: Ponto, f: &mut F) -> i128
+  where
+    F: FnMut(i128, usize) -> i128,
+  {
+    if lo >= hi {
+      return i128::MAX;
+    }
+
+    let meio = lo + (hi - lo) / 2;
+    let (p, i) = self.itens[meio];
+    let mut limite = f(distancia2(p, alvo), i);
+
+    let delta = if eixo_x {
+      alvo.x as i128 - p.x as i128
+    } else {
+      alvo.y as i128 - p.y as i128
+    };
+    let (perto, longe) = if delta < 0 {
+      ((lo, meio), (meio + 1, hi))
+    } else {
+      ((meio + 1, hi), (lo, meio))
+    };
+
+    limite = limite.min(self.visita(perto.0, perto.1, !eixo_x, alvo, f));
+    if delta * delta <= limite {
+      limite = limite.min(self.visita(longe.0, longe.1, !eixo_x, alvo, f));
+    }
+    return limite;
+  }
+}
+
+
+// Funções
+/// Retorna os índices `(i, j)`, com `i < j`, do par de pontos
+/// mais próximos entre si, por divisão e conquista em O(n log n)
+pub fn par_mais_proximo( pontos: &[Ponto]) -> Option<(usize, usize)> {
+  if pontos.len() < 2 {
+    return None;
+  }
+
+  let mut ids: Vec<usize> = (0..pontos.len()).collect();
+  ids.sort_unstable_by_key(|&i| (pontos[i].x, pontos[i].y, i));
+  let mut auxiliar = ids.clone();
+
+  let (_, (i, j)) = par_recursivo(pontos, &mut ids, &mut auxiliar);
+  return Some((i.min(j), i.max(j)));
+}
+
+
+/// Par mais próximo entre os índices, ordenados por x na entrada
+/// e deixados ordenados por y na saída
+fn par_recursivo( pontos: &[Ponto], ids: &mut [usize], auxiliar: &mut [usize]) -> (i128, (usize, usize)) {
+  let n = ids.len();
+  if n <= 3 {
+    let mut melhor = (i128::MAX, (ids[0], ids[0]));
+    for a in 0..n {
+      for b in a + 1..n {
+        melhor = melhor.min(candidato(pontos, ids[a], ids[b]));
+      }
+    }
+    ids.sort_unstable_by_key(|&i| pontos[i].y);
+    return melhor;
+  }
+
+  let meio = n / 2;
+  let corte = pontos[ids[meio]].x as i128;
+  let (esquerda, direita) = ids.split_at_mut(meio);
+  let (aux_esq, aux_dir) = auxiliar.split_at_mut(meio);
+  let mut melhor = par_recursivo(pontos, esquerda, aux_esq)
+    .min(par_recursivo(pontos, direita, aux_dir));
+
+  // intercala as metades por y
+  let (mut a, mut b) = (0, meio);
+  for destino in auxiliar.iter_mut() {
+    let usa_esquerda = b == n || (a < meio && pontos[ids[a]].y <= pontos[ids[b]].y);
+    *destino = if usa_esquerda { a += 1; ids[a - 1] } else { b += 1; ids[b - 1] };
+  }
+  ids.copy_from_slice(auxiliar);
+
+  // a faixa em torno do corte, por y, compara poucos vizinhos
+  let mut faixa = 0;
+  for &id in ids.iter() {
+    let p = pontos[id];
+    let dx = p.x as i128 - corte;
+    if dx * dx > melhor.0 {
+      continue;
+    }
+
+    for &j in auxiliar[..faixa].iter().rev() {
+      let dy = p.y as i128 - pontos[j].y as i128;
+      if dy * dy > melhor.0 {
+        break;
+      }
+      melhor = melhor.min(candidato(pontos, id, j));
+    }
+    auxiliar[faixa] = id;
+    faixa += 1;
+  }
+
+  return melhor;
+}
+
+
+/// Distância ao quadrado e par ordenado, para desempates estáveis
+fn candidato( pontos: &[Ponto], a: usize, b: usize) -> (i128, (usize, usize)) {
+  (distancia2(pontos[a], pontos[b]), (a.min(b), a.max(b)))
+}
+
+
+/// Parte inteira do quadrado de um raio não negativo, exata
+/// mesmo quando o quadrado não cabe em um `f64`. Raios muito
+/// maiores que qualquer distância entre pontos saturam
+fn quadrado_inteiro( raio: f64) -> i128 {
+  if raio < 1.0 {
+    return 0;
+  }
+  if raio >= 1e19 {
+    return i128::MAX;
+  }
+
+  // raio = mantissa * 2^expoente, sem arredondamentos
+  let bits = raio.to_bits();
+  let mantissa = ((bits & ((1 << 52) - 1)) | (1 << 52)) as i128;
+  let expoente = ((bits >> 52) & 0x7ff) as i32 - 1075;
+  if expoente >= 0 {
+    let inteiro = raio as i128;
+    return inteiro * inteiro;
+  }
+
+  return (mantissa * mantissa) >> (-2 * expoente);
+}
+
+
+/// Quadrado da distância entre dois pontos, exato
+fn distancia2( a: Ponto, b: Ponto) -> i128 {
+  let dx = a.x as i128 - b.x as i128;
+  let dy = a.y as i128 - b.y as i128;
+  dx * dx + dy * dy
+}
+
+
+/// Ordena os itens como uma árvore: a mediana do eixo vai para
+/// o meio e cada metade é ordenada com o outro eixo
+fn constroi( itens: &mut [(Ponto, usize)], eixo_x: bool) {
+  if itens.len() <= 1 {
+    return;
+  }
+
+  let meio = itens.len() / 2;
+  if eixo_x {
+    itens.select_nth_unstable_by_key(meio, |(p, i)| (p.x, p.y, *i));
+  } else {
+    itens.select_nth_unstable_by_key(meio, |(p, i)| (p.y, p.x, *i));
+  }
+
+  let (esquerda, direita) = itens.split_at_mut(meio);
+  constroi(esquerda, !eixo_x);
+  constroi(&mut direita[1..], !eixo_x);
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::random_data::sorteio;
+
+
+  /// Pontos em uma grade pequena, com repetidos, ou espalhados
+  /// até os limites de `i32`
+  fn pontos( estado: &mut u64, n: usize, modo: u64) -> Vec<Ponto> {
+    (0..n).map(|_| {
+      let (x, y) = (sorteio(estado), sorteio(estado));
+      match modo {
+        0 => Ponto::new((x % 9) as i32 - 4, (y % 9) as i32 - 4),
+        1 => Ponto::new((x % 2001) as i32 - 1000, (y % 2001) as i32 - 1000),
+        _ => Ponto::new(x as i32, y as i32),
+      }
+    }).collect()
+  }
+
+
+  /// Índices ordenados pela distância ao alvo e depois pelo índice
+  fn por_distancia( pontos: &[Ponto], alvo: Ponto) -> Vec<(i128, usize)> {
+    let mut ordem: Vec<(i128, usize)> = pontos.iter().enumerate().map(|(i, &p)| (distancia2(p, alvo), i)).collect();
+    ordem.sort_unstable();
+    return ordem;
+  }
+
+
+  #[test]
+  fn consultas_concordam_com_a_forca_bruta() {
+    let mut estado = 0x9b05688c2b3e6c1f;
+    for modo in 0..3 {
+      for n in (0..20).chain([100, 700].iter().copied()) {
+        let pontos = pontos(&mut estado, n, modo);
+        let arvore = ArvoreKd::new(&pontos);
+        assert_eq!(arvore.len(), n);
+
+        let alvos = self::pontos(&mut estado, 30, modo);
+        for &alvo in alvos.iter().chain(pontos.iter().take(10)) {
+          let ordem = por_distancia(&pontos, alvo);
+          assert_eq!(arvore.mais_proximo(alvo), ordem.first().map(|&(_, i)| i));
+
+          for &k in [0, 1, 2, 5, n / 2, n, n + 3].iter() {
+            let esperado: Vec<usize> = ordem.iter().take(k).map(|&(_, i)| i).collect();
+            assert_eq!(arvore.k_mais_proximos(alvo, k), esperado, "k = {}", k);
+          }
+
+          // raios inteiros, com pontos exatamente na borda
+          for &(d, _) in ordem.iter().step_by(1 + n / 5) {
+            let raio = (d as f64).sqrt();
+            if modo == 2 || raio.fract() != 0.0 {
+              continue;
+            }
+            let mut esperado: Vec<usize> = ordem.iter().filter(|&&(e, _)| e <= d).map(|&(_, i)| i).collect();
+            esperado.sort_unstable();
+            assert_eq!(arvore.no_raio(alvo, raio), esperado);
+          }
+
+          // raios reais só incluem distâncias até o seu quadrado
+          for &raio in [0.0, 0.5, 1.5, 2.9, 40.25, 1e3, 1e12].iter() {
+            let mut esperado: Vec<usize> = ordem.iter().filter(|&&(d, _)| (d as f64).sqrt() <= raio).map(|&(_, i)| i).collect();
+            esperado.sort_unstable();
+            if modo < 2 {
+              assert_eq!(arvore.no_raio(alvo, raio), esperado, "raio {}", raio);
+            }
+          }
+        }
+      }
+    }
+  }
+
+
+  #[test]
+  fn empates_e_arvores_vazias() {
+    let vazia = ArvoreKd::new(&[]);
+    assert!(vazia.is_empty());
+    assert_eq!(vazia.mais_proximo(Ponto::new(0, 0)), None);
+    assert!(vazia.k_mais_proximos(Ponto::new(0, 0), 3).is_empty());
+    assert!(vazia.no_raio(Ponto::new(0, 0), 10.0).is_empty());
+
+    // pontos repetidos e equidistantes ficam com o menor índice
+    let p = Ponto::new;
+    let pontos = [p(1, 0), p(0, 1), p(-1, 0), p(0, -1), p(0, 1), p(5, 5)];
+    let arvore = ArvoreKd::new(&pontos);
+    assert_eq!(arvore.mais_proximo(p(0, 0)), Some(0));
+    assert_eq!(arvore.mais_proximo(p(0, 1)), Some(1));
+    assert_eq!(arvore.k_mais_proximos(p(0, 0), 5), vec![0, 1, 2, 3, 4]);
+    assert_eq!(arvore.no_raio(p(0, 0), 1.0), vec![0, 1, 2, 3, 4]);
+    assert!(arvore.no_raio(p(0, 0), -1.0).is_empty());
+    assert!(arvore.no_raio(p(0, 0), f64::NAN).is_empty());
+    assert_eq!(arvore.no_raio(p(0, 0), f64::INFINITY).len(), 6);
+
+    let mut arvore = arvore;
+    arvore.reconstruir(&[p(9, 9)]);
+    assert_eq!(arvore.len(), 1);
+    assert_eq!(arvore.mais_proximo(p(0, 0)), Some(0));
+  }
+
+
+  #[test]
+  fn consultas_nos_limites_de_i32() {
+    let (min, max) = (i32::MIN, i32::MAX);
+    let p = Ponto::new;
+    let pontos = [p(min, min), p(max, max), p(min, max), p(max, min), p(0, 0), p(max, max - 1)];
+    let arvore = ArvoreKd::new(&pontos);
+
+    assert_eq!(arvore.mais_proximo(p(min + 1, min)), Some(0));
+    assert_eq!(arvore.mais_proximo(p(max, max)), Some(1));
+    assert_eq!(arvore.k_mais_proximos(p(max, max), 3), vec![1, 5, 4]);
+    assert_eq!(arvore.k_mais_proximos(p(min, max), 6), vec![2, 4, 0, 1, 5, 3]);
+
+    // a diagonal inteira cabe no raio
+    let diagonal = (max as f64 - min as f64) * 2f64.sqrt();
+    assert_eq!(arvore.no_raio(p(min, min), diagonal), vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(arvore.no_raio(p(min, min), max as f64 - min as f64), vec![0, 2, 3, 4]);
+
+    let mut estado = 0x1f83d9abfb41bd6b;
+    let pontos = self::pontos(&mut estado, 500, 2);
+    let arvore = ArvoreKd::new(&pontos);
+    for alvo in self::pontos(&mut estado, 50, 2) {
+      let ordem = por_distancia(&pontos, alvo);
+      assert_eq!(arvore.mais_proximo(alvo), Some(ordem[0].1));
+      let esperado: Vec<usize> = ordem.iter().take(7).map(|&(_, i)| i).collect();
+      assert_eq!(arvore.k_mais_proximos(alvo, 7), esperado);
+    }
+  }
+
+
+  #[test]
+  fn quadrados_de_raios_sem_arredondamento() {
+    assert_eq!(quadrado_inteiro(0.0), 0);
+    assert_eq!(quadrado_inteiro(0.999), 0);
+    assert_eq!(quadrado_inteiro(1.0), 1);
+    assert_eq!(quadrado_inteiro(1.5), 2);
+    assert_eq!(quadrado_inteiro(2.5), 6);
+    // o `f64` mais próximo de √2 fica um pouco acima dele
+    assert_eq!(quadrado_inteiro(2f64.sqrt()), 2);
+    assert_eq!(quadrado_inteiro(f64::from_bits(2f64.sqrt().to_bits() - 1)), 1);
+    assert_eq!(quadrado_inteiro(f64::INFINITY), i128::MAX);
+
+    // quadrados acima de 2⁵³ que o `f64` arredondaria
+    let lado = u32::MAX as f64;
+    assert_eq!(quadrado_inteiro(lado), (u32::MAX as i128) * (u32::MAX as i128));
+    assert!(((lado * lado) as i128) < quadrado_inteiro(lado));
+    let quase = 94906265.625;
+    assert_eq!(quadrado_inteiro(quase), 9007199254883056);
+    assert_eq!(quadrado_inteiro(1e18), 1_000_000_000_000_000_000_000_000_000_000_000_000);
+  }
+
+
+  #[test]
+  fn par_mais_proximo_concorda_com_a_forca_bruta() {
+    let mut estado = 0x5be0cd19137e2179;
+    assert_eq!(par_mais_proximo(&[]), None);
+    assert_eq!(par_mais_proximo(&[Ponto::new(1, 1)]), None);
+
+    for modo in 0..3 {
+      for n in (2..40).chain([200, 1000].iter().copied()) {
+        for _ in 0..5 {
+          let pontos = pontos(&mut estado, n, modo);
+          let (i, j) = par_mais_proximo(&pontos).unwrap();
+          assert!(i < j && j < n);
+
+          let menor = (0..n)
+            .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+            .map(|(a, b)| distancia2(pontos[a], pontos[b]))
+            .min()
+            .unwrap();
+          assert_eq!(distancia2(pontos[i], pontos[j]), menor, "{:?}", pontos);
+        }
+      }
+    }
+
+    // os extremos de `i32` e um par repetido
+    let (min, max) = (i32::MIN, i32::MAX);
+    let p = Ponto::new;
+    assert_eq!(par_mais_proximo(&[p(min, min), p(max, max)]), Some((0, 1)));
+    assert_eq!(par_mais_proximo(&[p(min, min), p(max, max), p(min, max), p(max, max - 1)]), Some((1, 3)));
+    assert_eq!(par_mais_proximo(&[p(0, 0), p(7, 7), p(3, 3), p(7, 7)]), Some((1, 3)));
+  }
+}
@@ -0,0 +1,70 @@
+//! JEOTRY: geometria 2D com vetores, formas e colisões.
+//!
+//! Os tipos mais usados ficam em [`prelude`]. Subsistemas
+//! opcionais são separados em features:
+//!
+//! - `std`: cenas, leitura de arquivos e colisões em lote
+//! - `render`: escrita em SVG, desenho no terminal e imagens
+//! - `physics`: rastreamento de contatos entre objetos
+//! - `serde`: `Serialize` e `Deserialize` com validação
+//!
+//! Sem a feature `std` a biblioteca é `no_std` e precisa apenas
+//! de `alloc`, com vetores, formas, transformações, medidas,
+//! empacotamento, envoltórias, árvores k-d, triangulações,
+//! diagramas de Voronoi, contatos e `serde` disponíveis.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![allow(
+  clippy::needless_return,
+  clippy::should_implement_trait
+)]
+
+extern crate alloc;
+
+// as constantes do fdlibm são mantidas como publicadas, nos
+// testes o módulo é comparado com o `std`
+#[cfg(any(not(feature = "std"), test))]
+#[allow(clippy::excessive_precision)]
+mod math;
+
+mod random_data;
+
+pub mod vector_data;
+
+pub mod object_data;
+
+pub mod transform_data;
+
+pub mod measure_data;
+
+pub mod pack_data;
+
+pub mod hull_data;
+
+pub mod kdtree_data;
+
+pub mod delaunay_data;
+
+pub mod voronoi_data;
+
+#[cfg(feature = "std")]
+pub mod scene_data;
+
+#[cfg(feature = "std")]
+pub mod batch_data;
+
+#[cfg(feature = "render")]
+pub mod svg_data;
+
+#[cfg(feature = "render")]
+pub mod term_data;
+
+#[cfg(feature = "render")]
+pub mod raster_data;
+
+#[cfg(feature = "physics")]
+pub mod contact_data;
+
+#[cfg(feature = "serde")]
+mod serde_data;
+
+pub mod prelude;
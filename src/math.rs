@@ -0,0 +1,650 @@
+/// Funções matemáticas para os alvos sem `std`.
+///
+/// O `core` não possui raiz quadrada, trigonometria nem
+/// arredondamentos dos números reais, que ficam no `std`. Sem a
+/// feature `std` o trait `Real` oferece esses métodos com os
+/// mesmos nomes, então o restante do código não muda.
+///
+/// Seno, cosseno e arco tangente seguem os polinômios do fdlibm,
+/// com erro de poucos ulps em todo o domínio. Argumentos a partir
+/// de 2²⁰ são reduzidos pelo método de Payne–Hanek, com os bits
+/// de 2/π necessários para qualquer `f64` finito. A raiz
+/// quadrada usa iterações de Newton e pode diferir do valor
+/// corretamente arredondado no último bit.
+use core::f64::consts::{FRAC_2_PI, FRAC_PI_2, FRAC_PI_4, PI};
+
+
+/// Métodos dos números reais que o `core` não possui
+pub(crate) trait Real: Sized {
+  fn sqrt( self ) -> Self;
+  fn sin( self ) -> Self;
+  fn cos( self ) -> Self;
+  fn sin_cos( self ) -> (Self, Self);
+  fn acos( self ) -> Self;
+  fn atan2( self, x: Self) -> Self;
+  fn hypot( self, y: Self) -> Self;
+  fn floor( self ) -> Self;
+  fn ceil( self ) -> Self;
+  fn round( self ) -> Self;
+}
+
+
+impl Real for f64 {
+  fn sqrt( self ) -> Self {
+    sqrt(self)
+  }
+
+  fn sin( self ) -> Self {
+    sin(self)
+  }
+
+  fn cos( self ) -> Self {
+    cos(self)
+  }
+
+  fn sin_cos( self ) -> (Self, Self) {
+    (sin(self), cos(self))
+  }
+
+  fn acos( self ) -> Self {
+    acos(self)
+  }
+
+  fn atan2( self, x: Self) -> Self {
+    atan2(self, x)
+  }
+
+  fn hypot( self, y: Self) -> Self {
+    hypot(self, y)
+  }
+
+  fn floor( self ) -> Self {
+    floor(self)
+  }
+
+  fn ceil( self ) -> Self {
+    ceil(self)
+  }
+
+  fn round( self ) -> Self {
+    round(self)
+  }
+}
+
+
+impl Real for f32 {
+  fn sqrt( self ) -> Self {
+    sqrt(self as f64) as f32
+  }
+
+  fn sin( self ) -> Self {
+    sin(self as f64) as f32
+  }
+
+  fn cos( self ) -> Self {
+    cos(self as f64) as f32
+  }
+
+  fn sin_cos( self ) -> (Self, Self) {
+    (sin(self as f64) as f32, cos(self as f64) as f32)
+  }
+
+  fn acos( self ) -> Self {
+    acos(self as f64) as f32
+  }
+
+  fn atan2( self, x: Self) -> Self {
+    atan2(self as f64, x as f64) as f32
+  }
+
+  fn hypot( self, y: Self) -> Self {
+    hypot(self as f64, y as f64) as f32
+  }
+
+  fn floor( self ) -> Self {
+    floor(self as f64) as f32
+  }
+
+  fn ceil( self ) -> Self {
+    ceil(self as f64) as f32
+  }
+
+  fn round( self ) -> Self {
+    round(self as f64) as f32
+  }
+}
+
+
+/// A partir de 2⁵² todo real é inteiro
+const INTEIRO: f64 = 4503599627370496.0;
+
+/// A partir de 2²⁰ a redução usa os bits de 2/π
+const GRANDE: f64 = 1048576.0;
+
+/// Os primeiros 1280 bits de 2/π, o suficiente para o maior
+/// expoente de um `f64` com a janela de 192 bits
+const DOIS_SOBRE_PI: [u64; 20] = [
+  0xA2F9836E4E441529, 0xFC2757D1F534DDC0, 0xDB6295993C439041, 0xFE5163ABDEBBC561,
+  0xB7246E3A424DD2E0, 0x06492EEA09D1921C, 0xFE1DEB1CB129A73E, 0xE88235F52EBB4484,
+  0xE99C7026B45F7E41, 0x3991D639835339F4, 0x9C845F8BBDF9283B, 0x1FF897FFDE05980F,
+  0xEF2F118B5A0A6D1F, 0x6D367ECF27CB09B7, 0x4F463F669E5FEA2D, 0x7527BAC7EBE5F17B,
+  0x3D0739F78A5292EA, 0x6BFB5FB11F8D5D08, 0x56033046FC7B6BAB, 0xF0CFBC209AF4361D,
+];
+
+
+/// Descarta a parte fracionária
+fn trunc( x: f64) -> f64 {
+  if x.is_nan() || x.abs() >= INTEIRO {
+    return x;
+  }
+
+  // o sinal de -0.5 para -0.0 é mantido
+  let t = (x as i64) as f64;
+  return if t == 0.0 { 0.0f64.copysign(x) } else { t };
+}
+
+
+fn floor( x: f64) -> f64 {
+  let t = trunc(x);
+  if t > x {
+    return t - 1.0;
+  }
+  return t;
+}
+
+
+fn ceil( x: f64) -> f64 {
+  let t = trunc(x);
+  if t < x {
+    return t + 1.0;
+  }
+  return t;
+}
+
+
+/// Arredonda metades para longe do zero, como o `std`
+fn round( x: f64) -> f64 {
+  let t = trunc(x);
+  if (x - t).abs() >= 0.5 {
+    return t + 1.0f64.copysign(x);
+  }
+  return t;
+}
+
+
+fn sqrt( x: f64) -> f64 {
+  if x.is_nan() || x < 0.0 {
+    return f64::NAN;
+  }
+  if x == 0.0 || x.is_infinite() {
+    return x;
+  }
+
+  // subnormais são escalados para o método convergir
+  if x < f64::MIN_POSITIVE {
+    return sqrt(x * 324518553658426726783156020576256.0) / 18014398509481984.0;
+  }
+
+  // metade do expoente como estimativa inicial, erro abaixo de 6%
+  let mut r = f64::from_bits((x.to_bits() >> 1) + 0x1FF8_0000_0000_0000);
+  for _ in 0..5 {
+    r = 0.5 * (r + x / r);
+  }
+  return r;
+}
+
+
+fn hypot( x: f64, y: f64) -> f64 {
+  let (x, y) = (x.abs(), y.abs());
+  if x.is_infinite() || y.is_infinite() {
+    return f64::INFINITY;
+  }
+
+  let (a, b) = if x > y { (x, y) } else { (y, x) };
+  if a == 0.0 || b.is_nan() {
+    return a + b;
+  }
+
+  // a razão evita o estouro dos quadrados
+  let r = b / a;
+  return a * sqrt(1.0 + r * r);
+}
+
+
+/// Reduz `x` ao intervalo [-π/4, π/4], retornando o quadrante e
+/// o resto em duas partes
+fn reduz( x: f64) -> (i64, f64, f64) {
+  if x.abs() >= GRANDE {
+    return reduz_grande(x);
+  }
+
+  const PIO2_1: f64 = 1.57079632673412561417e+00;
+  const PIO2_1T: f64 = 6.07710050650619224932e-11;
+  const PIO2_2: f64 = 6.07710050630396597660e-11;
+  const PIO2_2T: f64 = 2.02226624879595063154e-21;
+  const PIO2_3: f64 = 2.02226624871116645580e-21;
+  const PIO2_3T: f64 = 8.47842766036889956997e-32;
+
+  let expoente = |v: f64| ((v.to_bits() >> 52) & 0x7ff) as i64;
+
+  let n = round(x * FRAC_2_PI);
+  let mut r = x - n * PIO2_1;
+  let mut w = n * PIO2_1T;
+  let mut y0 = r - w;
+
+  // cancelamentos pedem mais bits de π/2
+  if expoente(x) - expoente(y0) > 16 {
+    let t = r;
+    w = n * PIO2_2;
+    r = t - w;
+    w = n * PIO2_2T - ((t - r) - w);
+    y0 = r - w;
+
+    if expoente(x) - expoente(y0) > 49 {
+      let t = r;
+      w = n * PIO2_3;
+      r = t - w;
+      w = n * PIO2_3T - ((t - r) - w);
+      y0 = r - w;
+    }
+  }
+
+  let y1 = (r - y0) - w;
+  return (n as i64, y0, y1);
+}
+
+
+/// Reduz argumentos grandes pelo método de Payne–Hanek. Com
+/// `x = m·2ᵉ`, os bits de 2/π antes da posição `e - 1` só somam
+/// múltiplos de 4 a `x·2/π`, então basta multiplicar `m` pelos
+/// 192 bits seguintes, em inteiros, para obter o quadrante e uma
+/// fração com bits de sobra mesmo nos piores cancelamentos
+fn reduz_grande( x: f64) -> (i64, f64, f64) {
+  // 2⁻¹²⁸, a fração é lida como um inteiro de 128 bits
+  const ESCALA: f64 = 2.938735877055719e-39;
+  const PIO2_LO: f64 = 6.123233995736766035868820147291818e-17;
+
+  let bits = x.abs().to_bits();
+  let m = (bits & 0x000F_FFFF_FFFF_FFFF) | 0x0010_0000_0000_0000;
+  let e = ((bits >> 52) & 0x7ff) as i64 - 1075;
+
+  // produto de 256 bits com a palavra alta primeiro, igual a
+  // x·2/π vezes 2¹⁹⁰: o quadrante fica nos dois bits altos da
+  // segunda palavra e a fração nos 128 bits seguintes
+  let janela = janela(e - 1);
+  let mut produto = [0u64; 4];
+  let mut vai = 0u128;
+  for i in (0..3).rev() {
+    let t = m as u128 * janela[i] as u128 + vai;
+    produto[i + 1] = t as u64;
+    vai = t >> 64;
+  }
+  produto[0] = vai as u64;
+
+  // frações a partir de 1/2 arredondam para o próximo quadrante
+  let alta = (produto[1] << 2) | (produto[2] >> 62);
+  let baixa = (produto[2] << 2) | (produto[3] >> 62);
+  let fracao = (((alta as u128) << 64) | baixa as u128) as i128;
+  let n = ((produto[1] >> 62) as i64 + (fracao < 0) as i64) & 3;
+
+  let alto = fracao as f64;
+  let baixo = fracao.wrapping_sub(alto as i128) as f64;
+  let (alto, baixo) = (alto * ESCALA, baixo * ESCALA);
+
+  // (alto + baixo)·π/2 com o erro do produto principal
+  let p = alto * FRAC_PI_2;
+  let cauda = erro_do_produto(alto, FRAC_PI_2, p) + (alto * PIO2_LO + baixo * FRAC_PI_2);
+  let y0 = p + cauda;
+  let y1 = cauda - (y0 - p);
+
+  if x < 0.0 {
+    return (-n, -y0, -y1);
+  }
+  return (n, y0, y1);
+}
+
+
+/// Bits de 2/π a partir do bit `i` depois da vírgula, contando
+/// do 1, em três palavras com a mais significativa primeiro. Os
+/// bits antes da vírgula são zeros
+fn janela( i: i64) -> [u64; 3] {
+  let palavra = |k: i64| if (0..20).contains(&k) { DOIS_SOBRE_PI[k as usize] } else { 0 };
+  let (q, r) = ((i - 1).div_euclid(64), (i - 1).rem_euclid(64) as u32);
+
+  let mut janela = [0u64; 3];
+  for (j, w) in janela.iter_mut().enumerate() {
+    let k = q + j as i64;
+    *w = if r == 0 { palavra(k) } else { (palavra(k) << r) | (palavra(k + 1) >> (64 - r)) };
+  }
+  return janela;
+}
+
+
+/// Erro de arredondamento de `p = a·b`, pela divisão de Dekker
+/// dos fatores em metades de 26 bits
+fn erro_do_produto( a: f64, b: f64, p: f64) -> f64 {
+  let divide = |v: f64| {
+    let c = 134217729.0 * v;
+    let alto = c - (c - v);
+    (alto, v - alto)
+  };
+
+  let ((a1, a2), (b1, b2)) = (divide(a), divide(b));
+  return ((a1 * b1 - p) + a1 * b2 + a2 * b1) + a2 * b2;
+}
+
+
+/// Seno em [-π/4, π/4], com `y` a cauda de `x`
+fn k_sin( x: f64, y: f64) -> f64 {
+  const S1: f64 = -1.66666666666666324348e-01;
+  const S2: f64 = 8.33333333332248946124e-03;
+  const S3: f64 = -1.98412698298579493134e-04;
+  const S4: f64 = 2.75573137070700676789e-06;
+  const S5: f64 = -2.50507602534068634195e-08;
+  const S6: f64 = 1.58969099521155010221e-10;
+
+  let z = x * x;
+  let r = S2 + z * (S3 + z * (S4 + z * (S5 + z * S6)));
+  let v = z * x;
+  return x - ((z * (0.5 * y - v * r) - y) - v * S1);
+}
+
+
+/// Cosseno em [-π/4, π/4], com `y` a cauda de `x`
+fn k_cos( x: f64, y: f64) -> f64 {
+  const C1: f64 = 4.16666666666666019037e-02;
+  const C2: f64 = -1.38888888888741095749e-03;
+  const C3: f64 = 2.48015872894767294178e-05;
+  const C4: f64 = -2.75573143513906633035e-07;
+  const C5: f64 = 2.08757232129817482790e-09;
+  const C6: f64 = -1.13596475577881948265e-11;
+
+  let z = x * x;
+  let r = z * (C1 + z * (C2 + z * (C3 + z * (C4 + z * (C5 + z * C6)))));
+  let hz = 0.5 * z;
+  let w = 1.0 - hz;
+  return w + (((1.0 - w) - hz) + (z * r - x * y));
+}
+
+
+fn sin( x: f64) -> f64 {
+  if !x.is_finite() {
+    return f64::NAN;
+  }
+  if x.abs() <= FRAC_PI_4 {
+    return k_sin(x, 0.0);
+  }
+
+  let (n, y0, y1) = reduz(x);
+  match n & 3 {
+    0 => k_sin(y0, y1),
+    1 => k_cos(y0, y1),
+    2 => -k_sin(y0, y1),
+    _ => -k_cos(y0, y1),
+  }
+}
+
+
+fn cos( x: f64) -> f64 {
+  if !x.is_finite() {
+    return f64::NAN;
+  }
+  if x.abs() <= FRAC_PI_4 {
+    return k_cos(x, 0.0);
+  }
+
+  let (n, y0, y1) = reduz(x);
+  match n & 3 {
+    0 => k_cos(y0, y1),
+    1 => -k_sin(y0, y1),
+    2 => -k_cos(y0, y1),
+    _ => k_sin(y0, y1),
+  }
+}
+
+
+fn atan( x: f64) -> f64 {
+  const ATANHI: [f64; 4] = [
+    4.63647609000806093515e-01,
+    FRAC_PI_4,
+    9.82793723247329054082e-01,
+    FRAC_PI_2,
+  ];
+  const ATANLO: [f64; 4] = [
+    2.26987774529616870924e-17,
+    3.06161699786838301793e-17,
+    1.39033110312309984516e-17,
+    6.12323399573676603587e-17,
+  ];
+  const AT: [f64; 11] = [
+    3.33333333333329318027e-01,
+    -1.99999999998764832476e-01,
+    1.42857142725034663711e-01,
+    -1.11111104054623557880e-01,
+    9.09088713343650656196e-02,
+    -7.69187620504482999495e-02,
+    6.66107313738753120669e-02,
+    -5.83357013379057348645e-02,
+    4.97687799461593236017e-02,
+    -3.65315727442169155270e-02,
+    1.62858201153657823623e-02,
+  ];
+
+  if x.is_nan() {
+    return x;
+  }
+
+  let a = x.abs();
+  if a >= 7.378697629483821e19 {
+    return (ATANHI[3] + ATANLO[3]).copysign(x);
+  }
+
+  // a tangente de um ângulo conhecido aproxima o argumento
+  let (id, v) = if a < 0.4375 {
+    if a < 7.450580596923828e-9 {
+      return x;
+    }
+    (None, x)
+  } else if a < 0.6875 {
+    (Some(0), (2.0 * a - 1.0) / (2.0 + a))
+  } else if a < 1.1875 {
+    (Some(1), (a - 1.0) / (a + 1.0))
+  } else if a < 2.4375 {
+    (Some(2), (a - 1.5) / (1.0 + 1.5 * a))
+  } else {
+    (Some(3), -1.0 / a)
+  };
+
+  let z = v * v;
+  let w = z * z;
+  let s1 = z * (AT[0] + w * (AT[2] + w * (AT[4] + w * (AT[6] + w * (AT[8] + w * AT[10])))));
+  let s2 = w * (AT[1] + w * (AT[3] + w * (AT[5] + w * (AT[7] + w * AT[9]))));
+
+  match id {
+    None    => v - v * (s1 + s2),
+    Some(i) => (ATANHI[i] - ((v * (s1 + s2) - ATANLO[i]) - v)).copysign(x),
+  }
+}
+
+
+fn atan2( y: f64, x: f64) -> f64 {
+  const PI_LO: f64 = 1.2246467991473531772e-16;
+
+  if x.is_nan() || y.is_nan() {
+    return f64::NAN;
+  }
+
+  if y == 0.0 {
+    if x > 0.0 || (x == 0.0 && x.is_sign_positive()) {
+      return y;
+    }
+    return PI.copysign(y);
+  }
+
+  if x == 0.0 {
+    return FRAC_PI_2.copysign(y);
+  }
+
+  if x.is_infinite() {
+    let a = match (y.is_infinite(), x > 0.0) {
+      (true, true)   => FRAC_PI_4,
+      (true, false)  => 3.0 * FRAC_PI_4,
+      (false, true)  => 0.0,
+      (false, false) => PI,
+    };
+    return a.copysign(y);
+  }
+
+  if y.is_infinite() {
+    return FRAC_PI_2.copysign(y);
+  }
+
+  // quocientes enormes ficam no eixo y
+  let q = (y / x).abs();
+  if q > 1.152921504606847e18 {
+    return FRAC_PI_2.copysign(y);
+  }
+
+  let z = atan(q);
+  if x > 0.0 {
+    return z.copysign(y);
+  }
+  return (PI - (z - PI_LO)).copysign(y);
+}
+
+
+fn acos( x: f64) -> f64 {
+  if x.is_nan() || x.abs() > 1.0 {
+    return f64::NAN;
+  }
+
+  // (1 - x)(1 + x) mantém a precisão perto de ±1
+  return atan2(sqrt((1.0 - x) * (1.0 + x)), x);
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::random_data::sorteio;
+
+
+  /// Distância em ulps entre dois reais, zero quando ambos são NaN
+  fn ulps( a: f64, b: f64) -> u64 {
+    if a.is_nan() && b.is_nan() {
+      return 0;
+    }
+    if a.is_nan() || b.is_nan() {
+      return u64::MAX;
+    }
+
+    // a ordem dos bits com sinal segue a ordem dos reais
+    let ordem = |v: f64| {
+      let b = v.to_bits() as i64;
+      if b < 0 { i64::MIN - b } else { b }
+    };
+    return ordem(a).abs_diff(ordem(b));
+  }
+
+
+  /// Argumentos especiais e reais sorteados de todas as magnitudes
+  fn argumentos() -> Vec<f64> {
+    let mut v = vec![
+      0.0, -0.0, 1.0, -1.0, 0.5, -0.5, 1.5, -2.5, 0.49999999999999994,
+      f64::MIN_POSITIVE, -f64::MIN_POSITIVE, 5e-324, -5e-324, 2.225e-308,
+      f64::MAX, f64::MIN, f64::INFINITY, f64::NEG_INFINITY, f64::NAN,
+      FRAC_PI_4, FRAC_PI_2, PI, 1e6, 1048575.9, GRANDE, 1e15, -1e15, 1e19, 1e22,
+      4503599627370495.5, 4503599627370497.0, 6381956970095103.0 * 2f64.powi(797),
+    ];
+
+    let mut estado = 0x5eed_0043;
+    for _ in 0..20000 {
+      v.push(f64::from_bits(sorteio(&mut estado)));
+    }
+    for _ in 0..20000 {
+      // magnitudes até 2⁶⁴, onde os argumentos costumam estar
+      let r = sorteio(&mut estado);
+      v.push((r >> 11) as f64 / (1u64 << 53) as f64 * 2f64.powi((r % 64) as i32 - 8));
+    }
+    return v;
+  }
+
+
+  #[test]
+  fn arredondamentos_iguais_ao_std() {
+    for &x in argumentos().iter() {
+      for (nome, nosso, padrao) in [("floor", floor(x), x.floor()), ("ceil", ceil(x), x.ceil()), ("round", round(x), x.round())] {
+        assert!(nosso.to_bits() == padrao.to_bits() || (nosso.is_nan() && padrao.is_nan()), "{}({:e}) = {:e}, std {:e}", nome, x, nosso, padrao);
+      }
+    }
+  }
+
+
+  #[test]
+  fn raiz_e_hipotenusa_proximas_do_std() {
+    let v = argumentos();
+    for &x in v.iter() {
+      assert!(ulps(sqrt(x), x.sqrt()) <= 1, "sqrt({:e}) = {:e}, std {:e}", x, sqrt(x), x.sqrt());
+    }
+
+    for par in v.windows(2) {
+      let (x, y) = (par[0], par[1]);
+      assert!(ulps(hypot(x, y), x.hypot(y)) <= 2, "hypot({:e}, {:e}) = {:e}, std {:e}", x, y, hypot(x, y), x.hypot(y));
+    }
+  }
+
+
+  #[test]
+  fn trigonometria_proxima_do_std() {
+    // perto dos zeros o próprio std perde alguns ulps, então
+    // resultados minúsculos são comparados em valor absoluto
+    let perto = |a: f64, b: f64| ulps(a, b) <= 2 || (a - b).abs() <= 1e-30;
+
+    let v = argumentos();
+    for &x in v.iter() {
+      assert!(perto(sin(x), x.sin()), "sin({:e}) = {:e}, std {:e}", x, sin(x), x.sin());
+      assert!(perto(cos(x), x.cos()), "cos({:e}) = {:e}, std {:e}", x, cos(x), x.cos());
+      assert!(sin(x).is_nan() || sin(x).abs() <= 1.0);
+
+      // acos recebe valores em [-1, 1] além dos próprios argumentos
+      for a in [x, x.sin()] {
+        assert!(ulps(acos(a), a.acos()) <= 2, "acos({:e}) = {:e}, std {:e}", a, acos(a), a.acos());
+      }
+    }
+
+    for par in v.windows(2) {
+      let (y, x) = (par[0], par[1]);
+      assert!(ulps(atan2(y, x), y.atan2(x)) <= 2, "atan2({:e}, {:e}) = {:e}, std {:e}", y, x, atan2(y, x), y.atan2(x));
+    }
+
+    // os casos da revisão, onde a redução antiga se perdia
+    assert!(ulps(sin(1e19), 1e19f64.sin()) <= 1);
+    assert!(ulps(sin(1e15), 1e15f64.sin()) <= 1);
+
+    // a 2⁻⁶¹ de um múltiplo de π/2, valor correto com 3000 bits
+    assert_eq!(cos(5.319372648326541e255), -4.687165924254628e-19);
+  }
+
+
+  #[test]
+  fn metodos_do_trait() {
+    for &x in [0.3, -2.5, 1e300, f64::NAN].iter() {
+      let iguais = |a: f64, b: f64| a.to_bits() == b.to_bits();
+      assert!(iguais(Real::sqrt(x), sqrt(x)) && iguais(Real::hypot(x, 2.0), hypot(x, 2.0)));
+      assert!(iguais(Real::sin(x), sin(x)) && iguais(Real::cos(x), cos(x)));
+      assert!(iguais(Real::sin_cos(x).0, sin(x)) && iguais(Real::sin_cos(x).1, cos(x)));
+      assert!(iguais(Real::acos(x), acos(x)) && iguais(Real::atan2(x, 2.0), atan2(x, 2.0)));
+      assert!(iguais(Real::floor(x), floor(x)) && iguais(Real::ceil(x), ceil(x)) && iguais(Real::round(x), round(x)));
+
+      // os f32 passam pelas funções em f64
+      let y = x as f32;
+      let iguais = |a: f32, b: f64| a.to_bits() == (b as f32).to_bits();
+      let z = y as f64;
+      assert!(iguais(Real::sqrt(y), sqrt(z)) && iguais(Real::hypot(y, 2.0), hypot(z, 2.0)));
+      assert!(iguais(Real::sin(y), sin(z)) && iguais(Real::cos(y), cos(z)));
+      assert!(iguais(Real::sin_cos(y).0, sin(z)) && iguais(Real::sin_cos(y).1, cos(z)));
+      assert!(iguais(Real::acos(y), acos(z)) && iguais(Real::atan2(y, 2.0), atan2(z, 2.0)));
+      assert!(iguais(Real::floor(y), floor(z)) && iguais(Real::ceil(y), ceil(z)) && iguais(Real::round(y), round(z)));
+    }
+  }
+}
@@ -0,0 +1,266 @@
+/// Medidas de conjuntos de blocos sobrepostos, como a área
+/// total coberta e o perímetro da união (problema de Klee).
+///
+/// Ambas as medidas são calculadas por uma linha de varredura
+/// que percorre o eixo x enquanto uma árvore de segmentos
+/// mantém o comprimento coberto no eixo y, em O(n log n).
+pub use super::object_data::Bloco as Bloco;
+use alloc::{vec, vec::Vec};
+
+
+// Estruturas
+/// Evento da linha de varredura: a borda esquerda (+1) ou
+/// direita (-1) de um bloco.
+#[derive(Debug, Clone, Copy)]
+struct Evento {
+  /// Posição no eixo de varredura
+  x: i64,
+
+  /// +1 ao entrar no bloco, -1 ao sair
+  delta: i32,
+
+  /// Intervalo coberto no outro eixo
+  y1: i64,
+  y2: i64
+}
+
+
+/// Árvore de segmentos sobre as coordenadas comprimidas do
+/// eixo y, cada folha corresponde ao intervalo entre duas
+/// coordenadas consecutivas.
+struct Arvore {
+  /// Coordenadas ordenadas e sem repetição
+  ys: Vec<i64>,
+
+  /// Quantos blocos cobrem o nó inteiro
+  cont: Vec<u32>,
+
+  /// Comprimento coberto dentro do nó
+  coberto: Vec<i64>
+}
+
+
+// Funções
+/// Retorna a área total coberta pelos blocos, regiões
+/// sobrepostas são contadas uma única vez
+pub fn area_coberta( blocos: &[Bloco]) -> i64 {
+  let eventos = eventos(blocos, false);
+  let mut arvore = match Arvore::new(&eventos) {
+    Some(a) => a,
+    None    => return 0,
+  };
+
+  let mut area = 0;
+  let mut anterior = eventos[0].x;
+  for e in eventos.iter() {
+    // a faixa entre dois eventos tem cobertura constante
+    area += arvore.total() * (e.x - anterior);
+    arvore.atualiza(e.y1, e.y2, e.delta);
+    anterior = e.x;
+  }
+
+  return area;
+}
+
+
+/// Retorna o perímetro da região coberta pelos blocos,
+/// incluindo as bordas de buracos internos
+pub fn perimetro_coberto( blocos: &[Bloco]) -> i64 {
+  // as bordas verticais surgem na varredura em x e as
+  // horizontais na varredura em y
+  varre_bordas(blocos, false) + varre_bordas(blocos, true)
+}
+
+
+/// Soma as variações do comprimento coberto ao longo da
+/// varredura, que correspondem às bordas perpendiculares
+fn varre_bordas( blocos: &[Bloco], transposto: bool) -> i64 {
+  let eventos = eventos(blocos, transposto);
+  let mut arvore = match Arvore::new(&eventos) {
+    Some(a) => a,
+    None    => return 0,
+  };
+
+  let mut bordas = 0;
+  for e in eventos.iter() {
+    let antes = arvore.total();
+    arvore.atualiza(e.y1, e.y2, e.delta);
+    bordas += (arvore.total() - antes).abs();
+  }
+
+  return bordas;
+}
+
+
+/// Gera os eventos ordenados dos blocos com área, entradas
+/// vêm antes de saídas na mesma posição para que blocos
+/// encostados não gerem bordas entre si
+fn eventos( blocos: &[Bloco], transposto: bool) -> Vec<Evento> {
+  let mut eventos = Vec::with_capacity(blocos.len() * 2);
+
+  for b in blocos.iter().filter(|b| b.area() > 0) {
+    let (x1, x2, y1, y2) = if transposto {
+      (b.p1.y, b.p2.y, b.p1.x, b.p2.x)
+    } else {
+      (b.p1.x, b.p2.x, b.p1.y, b.p2.y)
+    };
+
+    let (x1, x2, y1, y2) = (x1 as i64, x2 as i64, y1 as i64, y2 as i64);
+    eventos.push(Evento { x: x1, delta:  1, y1, y2 });
+    eventos.push(Evento { x: x2, delta: -1, y1, y2 });
+  }
+
+  eventos.sort_by_key(|e| (e.x, -e.delta));
+  return eventos;
+}
+
+
+// Métodos relacionados à árvore
+impl Arvore {
+
+
+  /// Gera uma árvore vazia sobre as coordenadas y dos eventos
+  fn new( eventos: &[Evento]) -> Option<Self> {
+    if eventos.is_empty() {
+      return None;
+    }
+
+    let mut ys: Vec<i64> = eventos.iter()
+      .flat_map(|e| [e.y1, e.y2])
+      .collect();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let folhas = ys.len() - 1;
+    return Some(Self {
+      ys,
+      cont: vec![0; 4 * folhas],
+      coberto: vec![0; 4 * folhas],
+    });
+  }
+
+
+  /// Comprimento total coberto
+  fn total( &self ) -> i64 {
+    self.coberto[0]
+  }
+
+
+  /// Soma `delta` à cobertura do intervalo [y1, y2)
+  fn atualiza( &mut self, y1: i64, y2: i64, delta: i32) {
+    // as coordenadas sempre existem, vieram dos próprios eventos
+    let l = self.ys.binary_search(&y1).unwrap_or(0);
+    let r = self.ys.binary_search(&y2).unwrap_or(0);
+    let folhas = self.ys.len() - 1;
+    self.atualiza_no(0, 0, folhas, l, r, delta);
+  }
+
+
+  /// Atualiza o nó que cobre as folhas [nl, nr) com o intervalo [l, r)
+  fn atualiza_no( &mut self, no: usize, nl: usize, nr: usize, l: usize, r: usize, delta: i32) {
+    if r <= nl || nr <= l {
+      return;
+    }
+
+    if l <= nl && nr <= r {
+      self.cont[no] = (self.cont[no] as i32 + delta) as u32;
+    } else {
+      let meio = (nl + nr) / 2;
+      self.atualiza_no(2 * no + 1, nl, meio, l, r, delta);
+      self.atualiza_no(2 * no + 2, meio, nr, l, r, delta);
+    }
+
+    // um nó coberto por inteiro ignora os filhos
+    if self.cont[no] > 0 {
+      self.coberto[no] = self.ys[nr] - self.ys[nl];
+    } else if nr - nl == 1 {
+      self.coberto[no] = 0;
+    } else {
+      self.coberto[no] = self.coberto[2 * no + 1] + self.coberto[2 * no + 2];
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::random_data::{entre, sorteio};
+
+
+  /// Área e perímetro pela contagem das células unitárias da
+  /// grade e das arestas entre células cobertas e vazias
+  fn forca_bruta( blocos: &[Bloco]) -> (i64, i64) {
+    let coberta = |x: i32, y: i32| blocos.iter().any(|b| b.p1.x <= x && x < b.p2.x && b.p1.y <= y && y < b.p2.y);
+
+    let (mut area, mut perimetro) = (0, 0);
+    for x in -12..12 {
+      for y in -12..12 {
+        if coberta(x, y) {
+          area += 1;
+          perimetro += [(1, 0), (-1, 0), (0, 1), (0, -1)].iter()
+            .filter(|(dx, dy)| !coberta(x + dx, y + dy))
+            .count() as i64;
+        }
+      }
+    }
+
+    return (area, perimetro);
+  }
+
+
+  #[test]
+  fn medidas_concordam_com_a_grade() {
+    let mut estado = 0x5eed_0030;
+    for rodada in 0..400 {
+      let n = (sorteio(&mut estado) % 10) as usize;
+      let mut blocos: Vec<Bloco> = (0..n)
+        .map(|_| {
+          let mut c = || entre(&mut estado, -10, 10);
+          Bloco::new((c(), c()), (c(), c()))
+        })
+        .collect();
+
+      // blocos aninhados, repetidos e encostados
+      if let Some(&b) = blocos.first() {
+        match rodada % 3 {
+          0 => blocos.push(b.inflate(-1)),
+          1 => blocos.push(b),
+          _ => blocos.push(Bloco::new((b.p2.x, b.p1.y), (b.p2.x + 1, b.p2.y))),
+        }
+      }
+
+      let (area, perimetro) = forca_bruta(&blocos);
+      assert_eq!(area_coberta(&blocos), area, "{:?}", blocos);
+      assert_eq!(perimetro_coberto(&blocos), perimetro, "{:?}", blocos);
+    }
+  }
+
+
+  #[test]
+  fn formas_conhecidas() {
+    assert_eq!(area_coberta(&[]), 0);
+    assert_eq!(perimetro_coberto(&[]), 0);
+
+    // dois quadrados com um canto em comum
+    let a = Bloco::new((0, 0), (4, 4));
+    let b = Bloco::new((2, 2), (6, 6));
+    assert_eq!(area_coberta(&[a, b]), 28);
+    assert_eq!(perimetro_coberto(&[a, b]), 24);
+
+    // um bloco dentro do outro não muda nada
+    assert_eq!(area_coberta(&[a, Bloco::new((1, 1), (2, 3))]), 16);
+    assert_eq!(perimetro_coberto(&[Bloco::new((1, 1), (2, 3)), a]), 16);
+
+    // uma moldura tem as bordas do buraco no perímetro
+    let moldura = [
+      Bloco::new((0, 0), (5, 1)), Bloco::new((0, 4), (5, 5)),
+      Bloco::new((0, 1), (1, 4)), Bloco::new((4, 1), (5, 4)),
+    ];
+    assert_eq!(area_coberta(&moldura), 16);
+    assert_eq!(perimetro_coberto(&moldura), 20 + 12);
+
+    // blocos sem área são ignorados e encostados se fundem
+    assert_eq!(perimetro_coberto(&[Bloco::new((0, 0), (0, 9)), Bloco::new((0, 0), (2, 1)), Bloco::new((2, 0), (4, 1))]), 10);
+  }
+}
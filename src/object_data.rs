@@ -13,6 +13,13 @@ pub use super::vector_data::{
     Ponto as Ponto,
     Vetor as Vetor,
 };
+use super::vector_data::{
+    Modulo,
+    fpitagoras,
+};
+use alloc::{vec, vec::Vec};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Real;
 
 
 // Estruturas 
@@ -26,6 +33,8 @@ pub use super::vector_data::{
 /// como colisões ou movimentações, o quadrilátero armazena
 /// informações mais óptimas para estes tipos de trabalho.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serde_data::BlocoBruto"))]
 pub struct Bloco {
   /// Primeira coordenada xy: Esquerda inferior
   pub p1: Ponto,
@@ -40,6 +49,8 @@ pub struct Bloco {
 /// A estrutura é normalizada de forma que sua coordenada seja 
 /// se posicione no canto esquerdo inferior.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serde_data::QuadBruto"))]
 pub struct Quadrilatero {
   /// Coordenada xy: Esquerda inferior
   pub p: Ponto,
@@ -57,6 +68,8 @@ pub struct Quadrilatero {
 /// A estrutura é normalizada de forma que sua coordenada seja
 /// posicionada em seu centro.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serde_data::CirculoBruto"))]
 pub struct Circulo {
   /// Coordenada xy : Centro
   pub p: Ponto,
@@ -66,6 +79,70 @@ pub struct Circulo {
 }
 
 
+/// Quadrilátero rotacionado, determinado por seu centro, metade
+/// de suas dimensões e seu ângulo de inclinação.
+/// 
+/// Diferente dos outros objetos suas coordenadas são reais, 
+/// já que os cantos de um quadrilátero rotacionado raramente
+/// caem sobre valores inteiros.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serde_data::OrientadoBruto"))]
+pub struct QuadOrientado {
+  /// Coordenada xy : Centro
+  pub c: (f64, f64),
+
+  /// Metade do tamanho x
+  pub hx: f64,
+
+  /// Metade do tamanho y
+  pub hy: f64,
+
+  /// Ângulo de inclinação em radianos
+  #[cfg_attr(feature = "serde", serde(rename = "angulo"))]
+  pub â: f32
+}
+
+
+/// Segmento de reta determinado por dois pontos no espaço.
+/// 
+/// Diferente do bloco a estrutura não é normalizada, a ordem
+/// dos pontos determina o sentido do segmento.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segmento {
+  /// Coordenada xy : Início
+  pub p1: Ponto,
+
+  /// Coordenada xy : Fim
+  pub p2: Ponto
+}
+
+
+/// Polígono determinado por seus vértices em sequência,
+/// o último vértice se liga ao primeiro.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Poligono {
+  /// Vértices em ordem
+  pub pontos: Vec<Ponto>
+}
+
+
+/// Qualquer um dos objetos do módulo, para coleções e
+/// operações que não dependem do tipo do objeto.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Forma {
+  Bloco(Bloco),
+  Quadrilatero(Quadrilatero),
+  Circulo(Circulo),
+  Orientado(QuadOrientado),
+  Segmento(Segmento),
+  Poligono(Poligono)
+}
+
+
 // Métodos relacionados ao Bloco
 impl Bloco {
 
@@ -153,8 +230,8 @@ impl Bloco {
   
   /// Extrai as coordenadas centrais do bloco
   pub fn center( self ) -> (f64, f64) {
-    let tx = (self.p2.x - self.p1.x) as f64 / 2.0;
-    let ty = (self.p2.y - self.p1.y) as f64 / 2.0;
+    let tx = (self.p2.x as f64 - self.p1.x as f64) / 2.0;
+    let ty = (self.p2.y as f64 - self.p1.y as f64) / 2.0;
     let px = self.p1.x as f64 + tx;
     let py = self.p1.y as f64 + ty;
     (px, py)
@@ -192,6 +269,54 @@ impl Bloco {
   }
 
 
+  /// Retorna o menor bloco que contém todos os pontos, ou
+  /// `None` quando não há pontos
+  pub fn from_points<I: IntoIterator<Item = Ponto>>( pontos: I) -> Option<Self> {
+    let mut pontos = pontos.into_iter();
+    let primeiro = pontos.next()?;
+
+    let (mut min, mut max) = (primeiro, primeiro);
+    for p in pontos {
+      min = Ponto::new(min.x.min(p.x), min.y.min(p.y));
+      max = Ponto::new(max.x.max(p.x), max.y.max(p.y));
+    }
+
+    return Some(Self {
+      p1: min,
+      p2: max
+    });
+  }
+
+
+  /// Retorna o menor bloco que contém todas as formas, ou
+  /// `None` quando não há formas
+  pub fn from_shapes<'a, I: IntoIterator<Item = &'a Forma>>( formas: I) -> Option<Self> {
+    formas.into_iter()
+      .map(Forma::to_block)
+      .reduce(Bloco::merge)
+  }
+
+
+  /// Retorna o bloco com as margens somadas a cada lado, margens
+  /// negativas encolhem o bloco até, no máximo, um ponto dentro
+  /// dele, o centro quando as margens são iguais
+  pub fn expand( self, esquerda: i32, baixo: i32, direita: i32, cima: i32) -> Self {
+    let (x1, x2) = margens(self.p1.x, self.p2.x, esquerda, direita);
+    let (y1, y2) = margens(self.p1.y, self.p2.y, baixo, cima);
+
+    Self {
+      p1: Ponto::new(x1, y1),
+      p2: Ponto::new(x2, y2)
+    }
+  }
+
+
+  /// Retorna o bloco com a mesma margem em todos os lados
+  pub fn inflate( self, margem: i32) -> Self {
+    self.expand(margem, margem, margem, margem)
+  }
+
+
   /// Extrai um Quadrilátero do bloco
   pub fn into_quad( self ) -> Quadrilatero {
     let tamx = self.p2.x - self.p1.x;
@@ -246,6 +371,101 @@ impl Bloco {
     return false;
 
   } 
+
+
+  /// Analisa a colisão com um quadrilátero orientado
+  pub fn collide_orient( self, o: QuadOrientado) -> bool {
+    o.collide_block(self)
+  }
+
+
+  /// Retorna a área do bloco, limitada a `i64::MAX` nos blocos
+  /// que cobrem quase todo o plano de `i32`
+  pub fn area( self ) -> i64 {
+    let tx = self.p2.x as i64 - self.p1.x as i64;
+    let ty = self.p2.y as i64 - self.p1.y as i64;
+    tx.saturating_mul(ty)
+  }
+
+
+  /// Retorna a região comum aos dois blocos, blocos
+  /// que apenas se tocam não possuem interseção
+  pub fn intersect( self, b: Bloco) -> Option<Bloco> {
+    let x1 = self.p1.x.max(b.p1.x);
+    let y1 = self.p1.y.max(b.p1.y);
+    let x2 = self.p2.x.min(b.p2.x);
+    let y2 = self.p2.y.min(b.p2.y);
+
+    if x1 >= x2 || y1 >= y2 {
+      return None;
+    }
+
+    return Some(Bloco {
+      p1: Ponto::new(x1, y1),
+      p2: Ponto::new(x2, y2),
+    });
+  }
+
+
+  /// Retorna a região deste bloco que não pertence ao bloco `b`,
+  /// descrita por até quatro blocos sem sobreposição. Um bloco
+  /// sem área não possui região
+  pub fn difference( self, b: Bloco) -> Vec<Bloco> {
+    if self.area() == 0 {
+      return Vec::new();
+    }
+
+    let i = match self.intersect(b) {
+      Some(i) => i,
+      None    => return vec![self],
+    };
+
+    //  ___________
+    // |    cima   |
+    // |___ _i_ ___|
+    // |esq|___|dir|
+    // |   baixo   |
+    // |___________|
+    let partes = [
+      Bloco::new((self.p1.x, self.p1.y), (self.p2.x, i.p1.y)),
+      Bloco::new((self.p1.x, i.p2.y),    (self.p2.x, self.p2.y)),
+      Bloco::new((self.p1.x, i.p1.y),    (i.p1.x,    i.p2.y)),
+      Bloco::new((i.p2.x,    i.p1.y),    (self.p2.x, i.p2.y)),
+    ];
+
+    return partes.iter()
+      .filter(|p| p.area() > 0)
+      .copied()
+      .collect();
+  }
+
+
+  /// Retorna a região coberta pelos dois blocos, descrita 
+  /// por blocos sem sobreposição
+  pub fn union( self, b: Bloco) -> Vec<Bloco> {
+    Bloco::union_all(&[self, b])
+  }
+
+
+  /// Retorna a região coberta por todos os blocos, descrita
+  /// por blocos sem sobreposição
+  pub fn union_all( blocos: &[Bloco]) -> Vec<Bloco> {
+    let mut uniao: Vec<Bloco> = Vec::new();
+
+    for b in blocos.iter().filter(|b| b.area() > 0) {
+      // apenas o que ainda não foi coberto é adicionado
+      let mut resto = vec![*b];
+      for u in uniao.iter() {
+        resto = resto.into_iter()
+          .flat_map(|r| r.difference(*u))
+          .collect();
+      }
+
+      uniao.extend(resto);
+    }
+
+    return uniao;
+  }
 }
 
 
@@ -379,6 +599,55 @@ impl Quadrilatero {
 
   } 
 
+
+  /// Analisa a colisão com um quadrilátero orientado
+  pub fn collide_orient( self, o: QuadOrientado) -> bool {
+    o.collide_quad(self)
+  }
+
+
+  /// Retorna a área do quadrilátero
+  pub fn area( self ) -> i64 {
+    self.tx as i64 * self.ty as i64
+  }
+
+
+  /// Retorna a região comum aos dois quadriláteros, quadriláteros
+  /// que apenas se tocam não possuem interseção
+  pub fn intersect( self, q: Quadrilatero) -> Option<Quadrilatero> {
+    self.into_block()
+      .intersect(q.into_block())
+      .map(Bloco::into_quad)
+  }
+
+
+  /// Retorna a região deste quadrilátero que não pertence ao 
+  /// quadrilátero `q`, descrita por até quatro quadriláteros
+  pub fn difference( self, q: Quadrilatero) -> Vec<Quadrilatero> {
+    self.into_block()
+      .difference(q.into_block())
+      .into_iter()
+      .map(Bloco::into_quad)
+      .collect()
+  }
+
+
+  /// Retorna a região coberta pelos dois quadriláteros, descrita
+  /// por quadriláteros sem sobreposição
+  pub fn union( self, q: Quadrilatero) -> Vec<Quadrilatero> {
+    Quadrilatero::union_all(&[self, q])
+  }
+
+
+  /// Retorna a região coberta por todos os quadriláteros, descrita
+  /// por quadriláteros sem sobreposição
+  pub fn union_all( quads: &[Quadrilatero]) -> Vec<Quadrilatero> {
+    let blocos: Vec<Bloco> = quads.iter().map(|q| q.into_block()).collect();
+    Bloco::union_all(&blocos)
+      .into_iter()
+      .map(Bloco::into_quad)
+      .collect()
+  }
 }
 
 /// Métodos relacionados ao círculo
@@ -430,4 +699,1002 @@ impl Circulo {
 
     return true;
   }
+
+
+  /// Retorna o menor bloco que contém o círculo, com os lados
+  /// limitados ao intervalo de `i32`
+  pub fn into_block( self ) -> Bloco {
+    let (x, y) = (self.p.x as f64, self.p.y as f64);
+    Bloco::new(
+      ((x - self.r).floor() as i32, (y - self.r).floor() as i32),
+      ((x + self.r).ceil() as i32, (y + self.r).ceil() as i32)
+    )
+  }
+
+
+  /// Analisa a colisão com um quadrilátero orientado
+  pub fn collide_orient( self, o: QuadOrientado) -> bool {
+    o.collide_circle(self)
+  }
+}
+
+
+/// Métodos relacionados ao quadrilátero orientado
+impl QuadOrientado {
+
+
+  /// Gera um novo quadrilátero orientado através de seu centro,
+  /// metade de suas dimensões e seu ângulo
+  pub fn new( c: (f64, f64), hx: f64, hy: f64, â: f32) -> Self {
+    Self {
+      c,
+      hx: hx.abs(),
+      hy: hy.abs(),
+      â
+    }
+  }
+
+
+  /// Gera um novo quadrilátero orientado com a inclinação de um módulo
+  pub fn from_modulo( c: (f64, f64), hx: f64, hy: f64, m: Modulo) -> Self {
+    QuadOrientado::new(c, hx, hy, m.â)
+  }
+
+
+  /// Rotaciona um quadrilátero em torno de seu centro
+  pub fn from_quad( q: Quadrilatero, â: f32) -> Self {
+    QuadOrientado::new(q.center(), q.tx as f64 / 2.0, q.ty as f64 / 2.0, â)
+  }
+
+
+  /// Rotaciona um bloco em torno de seu centro
+  pub fn from_block( b: Bloco, â: f32) -> Self {
+    QuadOrientado::from_quad(b.into_quad(), â)
+  }
+
+
+  /// Move o quadrilátero orientado
+  pub fn mov( &mut self, v: Vetor) {
+    self.c.0 += v.x as f64;
+    self.c.1 += v.y as f64;
+  }
+
+
+  /// Retorna um quadrilátero orientado movido sem alterar o original
+  pub fn sum( mut self, v: Vetor) -> Self {
+    self.mov(v);
+    return self;
+  }
+
+
+  /// Retorna a coordenada central
+  pub fn center( self ) -> (f64, f64) {
+    self.c
+  }
+
+
+  /// Retorna os eixos locais x e y do quadrilátero, ambos unitários
+  pub fn eixos( self ) -> [(f64, f64); 2] {
+    let (sin, cos) = (self.â as f64).sin_cos();
+    [(cos, sin), (-sin, cos)]
+  }
+
+
+  /// Extrai os quatro cantos em sentido anti-horário, 
+  /// começando pela esquerda-inferior local
+  pub fn corners( self ) -> [(f64, f64); 4] {
+    let [ex, ey] = self.eixos();
+    let ax = (ex.0 * self.hx, ex.1 * self.hx);
+    let ay = (ey.0 * self.hy, ey.1 * self.hy);
+    let (cx, cy) = self.c;
+
+    [
+      (cx - ax.0 - ay.0, cy - ax.1 - ay.1),
+      (cx + ax.0 - ay.0, cy + ax.1 - ay.1),
+      (cx + ax.0 + ay.0, cy + ax.1 + ay.1),
+      (cx - ax.0 + ay.0, cy - ax.1 + ay.1),
+    ]
+  }
+
+
+  /// Retorna o menor bloco que contém o quadrilátero orientado
+  pub fn into_block( self ) -> Bloco {
+    let cantos = self.corners();
+    let mut min = cantos[0];
+    let mut max = cantos[0];
+    for p in cantos.iter() {
+      min = (min.0.min(p.0), min.1.min(p.1));
+      max = (max.0.max(p.0), max.1.max(p.1));
+    }
+
+    Bloco::new(
+      (min.0.floor() as i32, min.1.floor() as i32),
+      (max.0.ceil() as i32, max.1.ceil() as i32)
+    )
+  }
+
+
+  /// Analisa a colisão com outro quadrilátero orientado 
+  /// pelo teorema do eixo separador
+  pub fn collide_orient( self, o: QuadOrientado) -> bool {
+    let c1 = self.corners();
+    let c2 = o.corners();
+    let [a1, a2] = self.eixos();
+    let [a3, a4] = o.eixos();
+
+    for eixo in [a1, a2, a3, a4].iter() {
+      let (min1, max1) = projeta(&c1, *eixo);
+      let (min2, max2) = projeta(&c2, *eixo);
+
+      // basta um eixo em que as sombras não se tocam
+      if max1 < min2 - EPS || max2 < min1 - EPS {
+        return false;
+      }
+    }
+
+    return true;
+  }
+
+
+  /// Analisa a colisão com um bloco
+  pub fn collide_block( self, b: Bloco) -> bool {
+    self.collide_orient(QuadOrientado::from_block(b, 0.0))
+  }
+
+
+  /// Analisa a colisão com um quadrilátero
+  pub fn collide_quad( self, q: Quadrilatero) -> bool {
+    self.collide_orient(QuadOrientado::from_quad(q, 0.0))
+  }
+
+
+  /// Analisa a colisão com um círculo
+  pub fn collide_circle( self, c: Circulo) -> bool {
+    // o centro do círculo é levado ao espaço local do quadrilátero
+    let [ex, ey] = self.eixos();
+    let dx = c.p.x as f64 - self.c.0;
+    let dy = c.p.y as f64 - self.c.1;
+    let lx = dx * ex.0 + dy * ex.1;
+    let ly = dx * ey.0 + dy * ey.1;
+
+    // ponto do quadrilátero mais próximo do centro
+    let px = lx.max(-self.hx).min(self.hx);
+    let py = ly.max(-self.hy).min(self.hy);
+
+    let diff = fpitagoras(lx - px, ly - py);
+    if diff > c.r + EPS {
+      return false;
+    }
+
+    return true;
+  }
+}
+
+
+
+/// Métodos relacionados ao segmento
+impl Segmento {
+
+
+  /// Gera um novo segmento através de duas coordenadas
+  pub fn new( p1: (i32, i32), p2: (i32, i32)) -> Self {
+    Self {
+      p1: Ponto::new(p1.0, p1.1),
+      p2: Ponto::new(p2.0, p2.1)
+    }
+  }
+
+
+  /// Gera um novo segmento através de dois pontos
+  pub fn from( p1: Ponto, p2: Ponto) -> Self {
+    Self {
+      p1,
+      p2
+    }
+  }
+
+
+  /// Move o segmento
+  pub fn mov( &mut self, v: Vetor) {
+    self.p1.mov(v);
+    self.p2.mov(v);
+  }
+
+
+  /// Retorna um segmento movido sem alterar o original
+  pub fn sum( mut self, v: Vetor) -> Self {
+    self.mov(v);
+    return self;
+  }
+
+
+  /// Extrai o vetor do início ao fim do segmento
+  pub fn into_vetor( self ) -> Vetor {
+    Vetor::from(self.p1, self.p2)
+  }
+
+
+  /// Retorna o comprimento do segmento
+  pub fn len( self ) -> f64 {
+    Ponto::diff(self.p1, self.p2)
+  }
+
+
+  /// Retorna a distância de uma coordenada real ao segmento
+  pub fn diff_f( self, p: (f64, f64)) -> f64 {
+    distancia_segmento(p, ponto_f(self.p1), ponto_f(self.p2))
+  }
+}
+
+
+/// Métodos relacionados ao polígono
+impl Poligono {
+
+
+  /// Gera um novo polígono através de seus vértices
+  pub fn new( pontos: Vec<Ponto>) -> Self {
+    Self {
+      pontos
+    }
+  }
+
+
+  /// Gera um novo polígono através de coordenadas
+  pub fn from( coords: &[(i32, i32)]) -> Self {
+    Self {
+      pontos: coords.iter().map(|c| Ponto::new(c.0, c.1)).collect()
+    }
+  }
+
+
+  /// Move o polígono
+  pub fn mov( &mut self, v: Vetor) {
+    for p in self.pontos.iter_mut() {
+      p.mov(v);
+    }
+  }
+
+
+  /// Retorna um polígono movido sem alterar o original
+  pub fn sum( mut self, v: Vetor) -> Self {
+    self.mov(v);
+    return self;
+  }
+
+
+  /// Retorna as arestas do polígono como segmentos
+  pub fn arestas( &self ) -> Vec<Segmento> {
+    let n = self.pontos.len();
+    (0..n)
+      .map(|i| Segmento::from(self.pontos[i], self.pontos[(i + 1) % n]))
+      .collect()
+  }
+
+
+  /// Retorna a área do polígono pela fórmula do cadarço
+  pub fn area( &self ) -> f64 {
+    let n = self.pontos.len();
+    let mut soma: i64 = 0;
+    for i in 0..n {
+      let a = self.pontos[i];
+      let b = self.pontos[(i + 1) % n];
+      soma += a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64;
+    }
+
+    (soma as f64 / 2.0).abs()
+  }
+}
+
+
+/// Métodos relacionados à forma
+impl Forma {
+
+
+  /// Move a forma
+  pub fn mov( &mut self, v: Vetor) {
+    match self {
+      Forma::Bloco(b)        => b.mov(v),
+      Forma::Quadrilatero(q) => q.mov(v),
+      Forma::Circulo(c)      => c.mov(v),
+      Forma::Orientado(o)    => o.mov(v),
+      Forma::Segmento(s)     => s.mov(v),
+      Forma::Poligono(p)     => p.mov(v),
+    }
+  }
+
+
+  /// Retorna uma forma movida sem alterar a original
+  pub fn sum( mut self, v: Vetor) -> Self {
+    self.mov(v);
+    return self;
+  }
+
+
+  /// Analisa se uma coordenada real está dentro da forma ou
+  /// sobre a sua borda
+  pub fn contains( &self, p: (f64, f64)) -> bool {
+    match self {
+      Forma::Bloco(b) => {
+        b.p1.x as f64 <= p.0 && p.0 <= b.p2.x as f64 &&
+        b.p1.y as f64 <= p.1 && p.1 <= b.p2.y as f64
+      },
+
+      Forma::Quadrilatero(q) => Forma::Bloco(q.into_block()).contains(p),
+
+      Forma::Circulo(c) => {
+        fpitagoras(p.0 - c.p.x as f64, p.1 - c.p.y as f64) <= c.r + EPS
+      },
+
+      Forma::Orientado(o) => {
+        // a coordenada é levada ao espaço local do quadrilátero
+        let [ex, ey] = o.eixos();
+        let dx = p.0 - o.c.0;
+        let dy = p.1 - o.c.1;
+        (dx * ex.0 + dy * ex.1).abs() <= o.hx + EPS &&
+        (dx * ey.0 + dy * ey.1).abs() <= o.hy + EPS
+      },
+
+      Forma::Segmento(s) => distancia_segmento(p, ponto_f(s.p1), ponto_f(s.p2)) <= EPS,
+
+      Forma::Poligono(poli) => {
+        let n = poli.pontos.len();
+        let mut dentro = false;
+        for i in 0..n {
+          let a = ponto_f(poli.pontos[i]);
+          let b = ponto_f(poli.pontos[(i + 1) % n]);
+
+          // a borda pertence ao polígono
+          if distancia_segmento(p, a, b) <= EPS {
+            return true;
+          }
+
+          // regra par-ímpar com um raio para a direita
+          if (a.1 > p.1) != (b.1 > p.1) {
+            let x = a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0);
+            if p.0 < x {
+              dentro = !dentro;
+            }
+          }
+        }
+
+        dentro
+      },
+    }
+  }
+
+
+  /// Retorna os limites reais da forma: esquerda-inferior
+  /// e direita-superior
+  pub fn limites( &self ) -> ((f64, f64), (f64, f64)) {
+    let pontos: Vec<(f64, f64)> = match self {
+      Forma::Bloco(b)        => vec![ponto_f(b.p1), ponto_f(b.p2)],
+      Forma::Quadrilatero(q) => {
+        let b = q.into_block();
+        vec![ponto_f(b.p1), ponto_f(b.p2)]
+      },
+      Forma::Circulo(c)      => {
+        let (x, y) = ponto_f(c.p);
+        vec![(x - c.r, y - c.r), (x + c.r, y + c.r)]
+      },
+      Forma::Orientado(o)    => o.corners().to_vec(),
+      Forma::Segmento(s)     => vec![ponto_f(s.p1), ponto_f(s.p2)],
+      Forma::Poligono(p)     => p.pontos.iter().map(|v| ponto_f(*v)).collect(),
+    };
+
+    let mut min = (f64::MAX, f64::MAX);
+    let mut max = (f64::MIN, f64::MIN);
+    for p in pontos.iter() {
+      min = (min.0.min(p.0), min.1.min(p.1));
+      max = (max.0.max(p.0), max.1.max(p.1));
+    }
+
+    // polígono sem vértices
+    if pontos.is_empty() {
+      return ((0.0, 0.0), (0.0, 0.0));
+    }
+
+    return (min, max);
+  }
+
+
+  /// Retorna o menor bloco de coordenadas inteiras que contém
+  /// a forma
+  pub fn to_block( &self ) -> Bloco {
+    match self {
+      Forma::Bloco(b)        => *b,
+      Forma::Quadrilatero(q) => q.into_block(),
+      Forma::Circulo(c)      => c.into_block(),
+      Forma::Orientado(o)    => o.into_block(),
+      _                      => {
+        let (min, max) = self.limites();
+        Bloco::new(
+          (min.0.floor() as i32, min.1.floor() as i32),
+          (max.0.ceil() as i32, max.1.ceil() as i32)
+        )
+      },
+    }
+  }
+
+
+  /// Retorna a área da forma, segmentos não possuem área
+  pub fn area( &self ) -> f64 {
+    match self {
+      Forma::Bloco(b)        => b.area() as f64,
+      Forma::Quadrilatero(q) => q.area() as f64,
+      Forma::Circulo(c)      => core::f64::consts::PI * c.r * c.r,
+      Forma::Orientado(o)    => 4.0 * o.hx * o.hy,
+      Forma::Segmento(_)     => 0.0,
+      Forma::Poligono(p)     => p.area(),
+    }
+  }
+
+
+  /// Analisa a colisão com outra forma, bordas que se tocam
+  /// também colidem
+  pub fn collide( &self, f: &Forma) -> bool {
+    // pares com métodos próprios mantêm a precisão inteira
+    match (self, f) {
+      (Forma::Bloco(a), Forma::Bloco(b))               => a.collide_block(*b),
+      (Forma::Bloco(a), Forma::Quadrilatero(b))        => a.collide_quad(*b),
+      (Forma::Quadrilatero(a), Forma::Bloco(b))        => a.collide_block(*b),
+      (Forma::Quadrilatero(a), Forma::Quadrilatero(b)) => a.collide_quad(*b),
+      (Forma::Circulo(a), Forma::Circulo(b))           => a.collide_circle(*b),
+      _                                                => self.distance(f) <= EPS,
+    }
+  }
+
+
+  /// Retorna a menor distância até outra forma, zero quando
+  /// as formas colidem
+  pub fn distance( &self, f: &Forma) -> f64 {
+    match (self, f) {
+      (Forma::Circulo(a), Forma::Circulo(b)) => {
+        (Ponto::diff(a.p, b.p) - a.r - b.r).max(0.0)
+      },
+
+      (Forma::Circulo(c), outra) | (outra, Forma::Circulo(c)) => {
+        (outra.distance_f(ponto_f(c.p)) - c.r).max(0.0)
+      },
+
+      (a, b) => {
+        // uma forma dentro da outra
+        let dentro = a.vertices().iter().any(|v| b.contains(*v)) ||
+                     b.vertices().iter().any(|v| a.contains(*v));
+        if dentro {
+          return 0.0;
+        }
+
+        let mut menor = f64::MAX;
+        for (a1, a2) in a.arestas_f() {
+          for (b1, b2) in b.arestas_f() {
+            menor = menor.min(distancia_segmentos(a1, a2, b1, b2));
+          }
+        }
+
+        menor
+      },
+    }
+  }
+
+
+  /// Retorna a menor distância de uma coordenada real à forma,
+  /// zero quando a coordenada está dentro dela
+  pub fn distance_f( &self, p: (f64, f64)) -> f64 {
+    if self.contains(p) {
+      return 0.0;
+    }
+
+    if let Forma::Circulo(c) = self {
+      return (fpitagoras(p.0 - c.p.x as f64, p.1 - c.p.y as f64) - c.r).max(0.0);
+    }
+
+    return self.arestas_f().iter()
+      .map(|(a, b)| distancia_segmento(p, *a, *b))
+      .fold(f64::MAX, f64::min);
+  }
+
+
+  /// Lança um raio a partir de `origem` na `direcao` e retorna
+  /// a distância até a forma, zero quando a origem está dentro
+  /// dela e `None` quando o raio não a atinge
+  pub fn raycast( &self, origem: (f64, f64), direcao: (f64, f64)) -> Option<f64> {
+    let n = fpitagoras(direcao.0, direcao.1);
+    if n == 0.0 || !n.is_finite() {
+      return None;
+    }
+    let d = (direcao.0 / n, direcao.1 / n);
+
+    if self.contains(origem) {
+      return Some(0.0);
+    }
+
+    if let Forma::Circulo(c) = self {
+      // raiz menor de |origem + t·d - centro| = r
+      let m = (origem.0 - c.p.x as f64, origem.1 - c.p.y as f64);
+      let b = m.0 * d.0 + m.1 * d.1;
+      let delta = b * b - (m.0 * m.0 + m.1 * m.1 - c.r * c.r);
+      if delta < 0.0 {
+        return None;
+      }
+
+      let t = -b - delta.sqrt();
+      return Some(t).filter(|t| *t >= 0.0);
+    }
+
+    return self.arestas_f().iter()
+      .filter_map(|(a, b)| raio_segmento(origem, d, *a, *b))
+      .min_by(f64::total_cmp);
+  }
+
+
+  /// Vértices reais da forma, o círculo é representado pelo centro
+  pub(crate) fn vertices( &self ) -> Vec<(f64, f64)> {
+    match self {
+      Forma::Bloco(b) => vec![
+        ponto_f(b.p1),
+        (b.p2.x as f64, b.p1.y as f64),
+        ponto_f(b.p2),
+        (b.p1.x as f64, b.p2.y as f64),
+      ],
+      Forma::Quadrilatero(q) => Forma::Bloco(q.into_block()).vertices(),
+      Forma::Circulo(c)      => vec![ponto_f(c.p)],
+      Forma::Orientado(o)    => o.corners().to_vec(),
+      Forma::Segmento(s)     => vec![ponto_f(s.p1), ponto_f(s.p2)],
+      Forma::Poligono(p)     => p.pontos.iter().map(|v| ponto_f(*v)).collect(),
+    }
+  }
+
+
+  /// Arestas reais da forma, o segmento possui uma única aresta
+  fn arestas_f( &self ) -> Vec<((f64, f64), (f64, f64))> {
+    let v = self.vertices();
+    match self {
+      Forma::Segmento(_) | Forma::Circulo(_) => vec![(v[0], v[v.len() - 1])],
+      _ => (0..v.len()).map(|i| (v[i], v[(i + 1) % v.len()])).collect(),
+    }
+  }
+}
+
+
+/// Soma as margens a um intervalo, um intervalo invertido pelas
+/// margens negativas se reduz ao seu ponto médio, sem sair do
+/// intervalo original
+fn margens( inicio: i32, fim: i32, antes: i32, depois: i32) -> (i32, i32) {
+  let a = inicio as i64 - antes as i64;
+  let b = fim as i64 + depois as i64;
+  let (a, b) = if a > b {
+    let meio = (a + b).div_euclid(2).clamp(inicio as i64, fim as i64);
+    (meio, meio)
+  } else {
+    (a, b)
+  };
+
+  let limita = |v: i64| v.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+  (limita(a), limita(b))
+}
+
+
+/// Converte um ponto em uma coordenada real
+fn ponto_f( p: Ponto) -> (f64, f64) {
+  (p.x as f64, p.y as f64)
+}
+
+
+/// Distância de uma coordenada ao segmento `ab`
+fn distancia_segmento( p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+  let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+  let len2 = dx * dx + dy * dy;
+
+  // segmento degenerado em um ponto
+  if len2 == 0.0 {
+    return fpitagoras(p.0 - a.0, p.1 - a.1);
+  }
+
+  let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0);
+  fpitagoras(p.0 - (a.0 + t * dx), p.1 - (a.1 + t * dy))
+}
+
+
+/// Distância entre os segmentos `ab` e `cd`, zero quando se cruzam
+fn distancia_segmentos( a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> f64 {
+  let cruz = |o: (f64, f64), p: (f64, f64), q: (f64, f64)| {
+    (p.0 - o.0) * (q.1 - o.1) - (p.1 - o.1) * (q.0 - o.0)
+  };
+
+  // cada segmento separa os extremos do outro
+  let d1 = cruz(a, b, c);
+  let d2 = cruz(a, b, d);
+  let d3 = cruz(c, d, a);
+  let d4 = cruz(c, d, b);
+  if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0)) &&
+     ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0)) {
+    return 0.0;
+  }
+
+  // sem cruzamento, a menor distância envolve um dos extremos
+  distancia_segmento(a, c, d)
+    .min(distancia_segmento(b, c, d))
+    .min(distancia_segmento(c, a, b))
+    .min(distancia_segmento(d, a, b))
+}
+
+
+/// Distância ao longo do raio `o + t·d` até o segmento `ab`
+fn raio_segmento( o: (f64, f64), d: (f64, f64), a: (f64, f64), b: (f64, f64)) -> Option<f64> {
+  let cruz = |u: (f64, f64), v: (f64, f64)| u.0 * v.1 - u.1 * v.0;
+  let e = (b.0 - a.0, b.1 - a.1);
+  let w = (a.0 - o.0, a.1 - o.1);
+  let denominador = cruz(d, e);
+
+  // raio paralelo ao segmento, só o atinge se forem colineares
+  if denominador.abs() <= EPS {
+    if cruz(w, d).abs() > EPS {
+      return None;
+    }
+
+    let ta = w.0 * d.0 + w.1 * d.1;
+    let tb = (b.0 - o.0) * d.0 + (b.1 - o.1) * d.1;
+    return [ta, tb].iter().copied().filter(|t| *t >= 0.0).min_by(f64::total_cmp);
+  }
+
+  let t = cruz(w, e) / denominador;
+  let u = cruz(w, d) / denominador;
+  if t >= 0.0 && (-EPS..=1.0 + EPS).contains(&u) {
+    return Some(t);
+  }
+
+  return None;
+}
+
+
+/// Tolerância das comparações entre coordenadas reais
+const EPS: f64 = 1e-9;
+
+
+/// Projeta os cantos sobre um eixo, retornando o intervalo coberto
+fn projeta( cantos: &[(f64, f64); 4], eixo: (f64, f64)) -> (f64, f64) {
+  let mut min = f64::MAX;
+  let mut max = f64::MIN;
+  for p in cantos.iter() {
+    let d = p.0 * eixo.0 + p.1 * eixo.1;
+    min = min.min(d);
+    max = max.max(d);
+  }
+
+  (min, max)
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::random_data::entre;
+
+
+  /// Cantos do bloco, para comparações
+  fn cantos( b: Bloco) -> (Ponto, Ponto) {
+    (b.p1, b.p2)
+  }
+
+
+  /// Verifica se o bloco interno está dentro do externo
+  fn contem( externo: Bloco, interno: Bloco) -> bool {
+    externo.p1.x <= interno.p1.x && externo.p1.y <= interno.p1.y
+      && interno.p2.x <= externo.p2.x && interno.p2.y <= externo.p2.y
+  }
+
+
+  #[test]
+  fn entradas_vazias_nao_tem_bloco() {
+    assert!(Bloco::from_points(Vec::new()).is_none());
+    assert!(Bloco::from_shapes(&[]).is_none());
+    assert!(Bloco::from_shapes(Vec::<Forma>::new().iter()).is_none());
+  }
+
+
+  #[test]
+  fn blocos_envolventes() {
+    let mut estado = 0x510e527fade682d1;
+    for n in 1..60 {
+      let pontos: Vec<Ponto> = (0..n).map(|_| Ponto::new(entre(&mut estado, -1000, 999), entre(&mut estado, -1000, 999))).collect();
+      let b = Bloco::from_points(pontos.iter().copied()).unwrap();
+      assert_eq!(b.p1.x, pontos.iter().map(|p| p.x).min().unwrap());
+      assert_eq!(b.p1.y, pontos.iter().map(|p| p.y).min().unwrap());
+      assert_eq!(b.p2.x, pontos.iter().map(|p| p.x).max().unwrap());
+      assert_eq!(b.p2.y, pontos.iter().map(|p| p.y).max().unwrap());
+
+      let formas: Vec<Forma> = pontos.windows(2).enumerate().map(|(i, par)| match i % 4 {
+        0 => Forma::Bloco(Bloco::from(par[0], par[1])),
+        1 => Forma::Circulo(Circulo::from(par[0], (par[1].x.abs() % 50) as f64 + 0.5)),
+        2 => Forma::Segmento(Segmento::from(par[0], par[1])),
+        _ => Forma::Orientado(QuadOrientado::new(ponto_f(par[0]), 3.5, 1.25, par[1].x as f32)),
+      }).collect();
+      if let Some(b) = Bloco::from_shapes(&formas) {
+        for f in formas.iter() {
+          assert!(contem(b, f.to_block()), "{:?} fora de {:?}", f, b);
+          let (min, max) = f.limites();
+          assert!(b.p1.x as f64 <= min.0 && b.p1.y as f64 <= min.1 && max.0 <= b.p2.x as f64 && max.1 <= b.p2.y as f64);
+        }
+        assert_eq!(cantos(b), cantos(formas.iter().map(Forma::to_block).reduce(Bloco::merge).unwrap()));
+      }
+    }
+  }
+
+
+  #[test]
+  fn blocos_de_circulos() {
+    let b = Circulo::new((0, 0), 2.5).into_block();
+    assert_eq!((b.p1, b.p2), (Ponto::new(-3, -3), Ponto::new(3, 3)));
+    let b = Circulo::new((4, -7), 2.0).into_block();
+    assert_eq!((b.p1, b.p2), (Ponto::new(2, -9), Ponto::new(6, -5)));
+    let b = Circulo::new((1, 1), 0.0).into_block();
+    assert_eq!((b.p1, b.p2), (Ponto::new(1, 1), Ponto::new(1, 1)));
+  }
+
+
+  #[test]
+  fn blocos_de_circulos_nos_limites_de_i32() {
+    let (min, max) = (i32::MIN, i32::MAX);
+
+    // o lado que passaria do limite fica nele
+    let b = Circulo::new((max, min), 10.5).into_block();
+    assert_eq!((b.p1, b.p2), (Ponto::new(max - 11, min), Ponto::new(max, min + 11)));
+    let b = Circulo::new((min, max), 1.0).into_block();
+    assert_eq!((b.p1, b.p2), (Ponto::new(min, max - 1), Ponto::new(min + 1, max)));
+
+    // raios maiores que o intervalo ocupam o intervalo inteiro
+    let inteiro = (Ponto::new(min, min), Ponto::new(max, max));
+    for &(c, r) in [((0, 0), 1e10), ((max, max), 5e9), ((min, 0), f64::MAX), ((0, 0), f64::INFINITY)].iter() {
+      let b = Circulo::new(c, r).into_block();
+      assert_eq!((b.p1, b.p2), inteiro, "{:?} {}", c, r);
+    }
+
+    let formas = [
+      Forma::Circulo(Circulo::new((max, 0), 100.0)),
+      Forma::Circulo(Circulo::new((min, 0), 100.0)),
+    ];
+    let b = Bloco::from_shapes(&formas).unwrap();
+    assert_eq!((b.p1, b.p2), (Ponto::new(min, -100), Ponto::new(max, 100)));
+  }
+
+
+  #[test]
+  fn margens_dos_blocos() {
+    let b = Bloco::new((0, 0), (10, 4));
+    let e = b.expand(1, 2, 3, 4);
+    assert_eq!((e.p1, e.p2), (Ponto::new(-1, -2), Ponto::new(13, 8)));
+    assert_eq!(cantos(b.inflate(2)), cantos(Bloco::new((-2, -2), (12, 6))));
+
+    // margens negativas encolhem até um ponto dentro do bloco
+    assert_eq!(cantos(b.inflate(-1)), cantos(Bloco::new((1, 1), (9, 3))));
+    assert_eq!(cantos(b.inflate(-3)), cantos(Bloco::new((3, 2), (7, 2))));
+    assert_eq!(cantos(b.inflate(-100)), cantos(Bloco::new((5, 2), (5, 2))));
+    assert_eq!(cantos(b.expand(-20, 0, 0, 0)), cantos(Bloco::new((10, 0), (10, 4))));
+    assert_eq!(cantos(b.expand(0, 0, -20, 0)), cantos(Bloco::new((0, 0), (0, 4))));
+    assert_eq!(cantos(b.expand(-8, 0, -4, 0)), cantos(Bloco::new((7, 0), (7, 4))));
+
+    // e as margens não passam dos limites de `i32`
+    let (min, max) = (i32::MIN, i32::MAX);
+    let inteiro = Bloco::new((min, min), (max, max));
+    assert_eq!(cantos(inteiro.inflate(max)), cantos(inteiro));
+    assert_eq!(cantos(inteiro.inflate(min)), cantos(Bloco::new((-1, -1), (-1, -1))));
+    assert_eq!(cantos(Bloco::new((max - 1, 0), (max, 1)).expand(0, 0, max, 0)), cantos(Bloco::new((max - 1, 0), (max, 1))));
+    assert_eq!(cantos(Bloco::new((0, 0), (0, 0)).inflate(min)), cantos(Bloco::new((0, 0), (0, 0))));
+  }
+
+
+  /// Analisa a colisão pelos dois lados
+  fn colidem( a: QuadOrientado, b: QuadOrientado) -> bool {
+    let (ab, ba) = (a.collide_orient(b), b.collide_orient(a));
+    assert_eq!(ab, ba, "{:?} e {:?}", a, b);
+    return ab;
+  }
+
+
+  #[test]
+  fn orientados_sem_rotacao_e_a_90_graus() {
+    let a = QuadOrientado::new((0.0, 0.0), 4.0, 1.0, 0.0);
+
+    // arestas que se tocam colidem, com qualquer folga não
+    assert!(colidem(a, QuadOrientado::new((6.0, 0.0), 2.0, 1.0, 0.0)));
+    assert!(colidem(a, QuadOrientado::new((6.0, 2.0), 2.0, 1.0, 0.0)));
+    assert!(!colidem(a, QuadOrientado::new((6.001, 0.0), 2.0, 1.0, 0.0)));
+    assert!(!colidem(a, QuadOrientado::new((0.0, 2.001), 2.0, 1.0, 0.0)));
+    assert!(colidem(a, QuadOrientado::new((0.0, 0.0), 0.5, 0.5, 0.0)));
+
+    // a 90 graus as dimensões trocam de eixo
+    let b = QuadOrientado::new((0.0, 0.0), 4.0, 1.0, core::f32::consts::FRAC_PI_2);
+    assert!(colidem(b, QuadOrientado::new((0.0, 5.9), 1.0, 2.0, 0.0)));
+    assert!(!colidem(b, QuadOrientado::new((0.0, 6.1), 1.0, 2.0, 0.0)));
+    assert!(colidem(b, QuadOrientado::new((2.9, 0.0), 2.0, 1.0, 0.0)));
+    assert!(!colidem(b, QuadOrientado::new((3.1, 0.0), 2.0, 1.0, 0.0)));
+    assert!(colidem(a, b));
+
+    // blocos e quadriláteros são orientados sem rotação
+    assert!(a.collide_block(Bloco::new((4, 1), (6, 3))));
+    assert!(!a.collide_block(Bloco::new((5, -1), (6, 1))));
+    assert!(Bloco::new((-4, -1), (4, 1)).collide_orient(a));
+    assert!(a.collide_quad(Quadrilatero::new((-6, -3), 2, 2)));
+    assert!(!Quadrilatero::new((-7, -3), 2, 2).collide_orient(a));
+    assert!(b.collide_block(Bloco::new((-3, 3), (-1, 5))));
+    assert!(!b.collide_block(Bloco::new((2, 0), (3, 1))));
+  }
+
+
+  #[test]
+  fn separacao_apenas_em_um_eixo_girado() {
+    let quarto = core::f32::consts::FRAC_PI_4;
+    let raiz = 2f64.sqrt();
+
+    // losangos |x| + |y| <= √2, com blocos envolventes que se
+    // sobrepõem e separados só pela diagonal
+    let a = QuadOrientado::new((0.0, 0.0), 1.0, 1.0, quarto);
+    for &(x, y, colide) in [(1.5, 1.5, false), (1.4, 1.4, true), (2.0, 0.8, true), (2.0, 0.9, false)].iter() {
+      let b = QuadOrientado::new((x, y), 1.0, 1.0, quarto);
+      assert!(a.into_block().collide_block(b.into_block()));
+      assert_eq!(colidem(a, b), colide, "({}, {})", x, y);
+    }
+
+    // o eixo separador é um dos eixos do outro quadrilátero: um
+    // quadrado sem rotação e um losango que o separa pela
+    // diagonal, x + y > 2 + √2, embora se sobreponham em x e y
+    let c = QuadOrientado::new((0.0, 0.0), 1.0, 1.0, 0.0);
+    let perto = QuadOrientado::new((2.2, 1.2), 1.0, 1.0, quarto);
+    let longe = QuadOrientado::new((2.2, 1.4), 1.0, 1.0, quarto);
+    assert!(2.2 + 1.2 < 2.0 + raiz && 2.2 + 1.4 > 2.0 + raiz);
+    assert!(colidem(c, perto));
+    assert!(!colidem(c, longe));
+    assert!(c.into_block().collide_block(longe.into_block()));
+
+    // o mesmo com blocos inteiros
+    let d = QuadOrientado::new((0.0, 0.0), raiz, raiz, quarto);
+    assert!(d.collide_block(Bloco::new((0, 1), (2, 3))));
+    assert!(!d.collide_block(Bloco::new((2, 1), (3, 3))));
+    assert!(!Bloco::new((-3, -3), (-1, -2)).collide_orient(d));
+    assert!(Bloco::new((-3, -3), (-1, -1)).collide_orient(d));
+  }
+
+
+  #[test]
+  fn orientados_contra_circulos() {
+    let quarto = core::f32::consts::FRAC_PI_4;
+
+    // o losango |x| + |y| <= 2 tem um vértice em (2, 0) e uma
+    // aresta a √2 / 2 de (1, 1)
+    let a = QuadOrientado::new((0.0, 0.0), 2f64.sqrt(), 2f64.sqrt(), quarto);
+    assert!(a.collide_circle(Circulo::new((0, 0), 0.5)));
+    assert!(a.collide_circle(Circulo::new((3, 0), 1.0 + 1e-6)));
+    assert!(!a.collide_circle(Circulo::new((3, 0), 0.999)));
+    assert!(a.collide_circle(Circulo::new((2, 2), 2f64.sqrt() + 1e-6)));
+    assert!(!Circulo::new((2, 2), 1.41).collide_orient(a));
+
+    // sem rotação e a 90 graus, tocando as arestas
+    let b = QuadOrientado::new((0.0, 0.0), 4.0, 1.0, 0.0);
+    assert!(b.collide_circle(Circulo::new((0, 3), 2.0)));
+    assert!(!b.collide_circle(Circulo::new((0, 3), 1.99)));
+    assert!(b.collide_circle(Circulo::new((6, 0), 2.0)));
+    let c = QuadOrientado::new((0.0, 0.0), 4.0, 1.0, core::f32::consts::FRAC_PI_2);
+    assert!(c.collide_circle(Circulo::new((3, 0), 2.0)));
+    assert!(!c.collide_circle(Circulo::new((0, 6), 1.99)));
+    assert!(Circulo::new((0, 6), 2.01).collide_orient(c));
+  }
+
+
+  /// Bloco sorteado em uma grade pequena, às vezes sem área
+  fn bloco( estado: &mut u64) -> Bloco {
+    let mut c = || entre(estado, -6, 6);
+    Bloco::new((c(), c()), (c(), c()))
+  }
+
+
+  /// Verifica se a célula unitária de canto (x, y) está no bloco
+  fn cobre( b: Bloco, x: i32, y: i32) -> bool {
+    b.p1.x <= x && x < b.p2.x && b.p1.y <= y && y < b.p2.y
+  }
+
+
+  /// Confere que as partes não se sobrepõem e que cada célula
+  /// da grade coberta pela região está em exatamente uma delas
+  fn confere_partes( partes: &[Bloco], regiao: impl Fn(i32, i32) -> bool) {
+    assert!(partes.iter().all(|p| p.area() > 0), "{:?}", partes);
+    for (i, a) in partes.iter().enumerate() {
+      for b in partes[i + 1..].iter() {
+        assert!(a.intersect(*b).is_none(), "{:?} e {:?}", a, b);
+      }
+    }
+
+    let mut celulas = 0;
+    for x in -7..7 {
+      for y in -7..7 {
+        let vezes = partes.iter().filter(|p| cobre(**p, x, y)).count();
+        assert_eq!(vezes, regiao(x, y) as usize, "célula ({}, {})", x, y);
+        celulas += vezes as i64;
+      }
+    }
+    assert_eq!(partes.iter().map(|p| p.area()).sum::<i64>(), celulas);
+  }
+
+
+  #[test]
+  fn intersecao_de_blocos() {
+    let a = Bloco::new((0, 0), (4, 4));
+    assert_eq!(a.intersect(Bloco::new((2, 1), (6, 3))).map(cantos), Some(cantos(Bloco::new((2, 1), (4, 3)))));
+    assert_eq!(a.intersect(Bloco::new((1, 1), (2, 2))).map(cantos), Some(cantos(Bloco::new((1, 1), (2, 2)))));
+
+    // blocos que só se tocam, ou sem área, não se intersectam
+    assert!(a.intersect(Bloco::new((4, 0), (6, 4))).is_none());
+    assert!(a.intersect(Bloco::new((4, 4), (6, 6))).is_none());
+    assert!(a.intersect(Bloco::new((1, 1), (3, 1))).is_none());
+
+    let mut estado = 0x5eed_0029;
+    for _ in 0..500 {
+      let (a, b) = (bloco(&mut estado), bloco(&mut estado));
+      assert_eq!(a.intersect(b).map(cantos), b.intersect(a).map(cantos));
+      let partes: Vec<Bloco> = a.intersect(b).into_iter().collect();
+      confere_partes(&partes, |x, y| cobre(a, x, y) && cobre(b, x, y));
+    }
+  }
+
+
+  #[test]
+  fn diferenca_de_blocos() {
+    let a = Bloco::new((0, 0), (6, 6));
+    assert_eq!(a.difference(Bloco::new((2, 2), (4, 4))).len(), 4);
+    assert_eq!(a.difference(Bloco::new((-1, -1), (7, 7))).len(), 0);
+    assert_eq!(a.difference(Bloco::new((6, 0), (8, 6))).iter().map(|b| cantos(*b)).collect::<Vec<_>>(), vec![cantos(a)]);
+
+    // um bloco sem área não tem o que sobrar
+    assert!(Bloco::new((1, 1), (1, 5)).difference(a).is_empty());
+    assert!(Bloco::new((1, 1), (5, 1)).difference(Bloco::new((9, 9), (10, 10))).is_empty());
+
+    let mut estado = 0x5eed_1029;
+    for _ in 0..500 {
+      let (a, b) = (bloco(&mut estado), bloco(&mut estado));
+      confere_partes(&a.difference(b), |x, y| cobre(a, x, y) && !cobre(b, x, y));
+    }
+  }
+
+
+  #[test]
+  fn uniao_de_blocos() {
+    let mut estado = 0x5eed_2029;
+    for rodada in 0..300 {
+      let n = rodada % 8;
+      let mut blocos: Vec<Bloco> = (0..n).map(|_| bloco(&mut estado)).collect();
+
+      // blocos aninhados e repetidos
+      if let Some(&b) = blocos.first() {
+        blocos.push(b);
+        if b.area() > 0 {
+          blocos.push(b.inflate(-1));
+        }
+      }
+
+      let uniao = Bloco::union_all(&blocos);
+      confere_partes(&uniao, |x, y| blocos.iter().any(|b| cobre(*b, x, y)));
+      assert_eq!(uniao.iter().map(|b| b.area()).sum::<i64>(), crate::measure_data::area_coberta(&blocos));
+
+      // o mesmo para quadriláteros
+      let quads: Vec<Quadrilatero> = blocos.iter().map(|b| b.into_quad()).collect();
+      let areas: i64 = Quadrilatero::union_all(&quads).iter().map(|q| q.area()).sum();
+      assert_eq!(areas, uniao.iter().map(|b| b.area()).sum::<i64>());
+    }
+
+    let (a, b) = (Bloco::new((0, 0), (4, 4)), Bloco::new((2, 2), (6, 6)));
+    assert_eq!(a.union(b).iter().map(|b| b.area()).sum::<i64>(), 28);
+    assert!(Bloco::union_all(&[]).is_empty());
+  }
+
+
+  #[test]
+  fn areas_nos_limites_de_i32() {
+    let (min, max) = (i32::MIN, i32::MAX);
+    let largo = Bloco::new((min, 0), (max, 1));
+    assert_eq!(largo.area(), u32::MAX as i64);
+    assert_eq!(largo.center(), (-0.5, 0.5));
+    assert_eq!(crate::measure_data::area_coberta(&[largo]), u32::MAX as i64);
+    assert_eq!(Bloco::new((min, min), (max, max)).area(), i64::MAX);
+    assert_eq!(Bloco::new((min, min), (max, min)).area(), 0);
+    assert_eq!(largo.difference(Bloco::new((0, 0), (1, 1))).len(), 2);
+  }
 }
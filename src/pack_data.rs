@@ -0,0 +1,475 @@
+/// Empacotamento de quadriláteros dentro de um recipiente,
+/// usado para dispor sprites e painéis sem sobreposição.
+///
+/// Três heurísticas estão disponíveis: a linha do horizonte
+/// (skyline), os retângulos máximos (maxrects) e os cortes de
+/// guilhotina. Todas posicionam os quadriláteros do maior para
+/// o menor e podem, opcionalmente, girá-los em 90 graus.
+///
+/// O recipiente sempre tem sua esquerda-inferior na origem.
+/// Quadriláteros sem área não são posicionados e ficam entre os
+/// restantes.
+pub use super::object_data::{
+    Ponto as Ponto,
+    Quadrilatero as Quadrilatero,
+};
+use alloc::{vec, vec::Vec};
+
+
+// Estruturas
+/// Heurística usada para escolher a posição de cada quadrilátero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristica {
+  /// Posiciona sobre a linha do horizonte o mais abaixo possível
+  Skyline,
+
+  /// Mantém todos os retângulos livres máximos e escolhe
+  /// o que deixa a menor sobra no lado mais curto
+  MaxRects,
+
+  /// Divide o espaço livre em cortes de ponta a ponta e
+  /// escolhe o retângulo livre de área mais próxima
+  Guilhotina
+}
+
+
+/// Recipiente onde os quadriláteros são posicionados
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recipiente {
+  /// Dimensões fixas, o que não couber fica de fora
+  Fixo(u16, u16),
+
+  /// Dimensões iniciais que dobram até que tudo caiba,
+  /// o recipiente final é ajustado ao espaço usado
+  Crescente(u16, u16)
+}
+
+
+/// Quadrilátero posicionado pelo empacotamento
+#[derive(Debug, Clone, Copy)]
+pub struct Colocado {
+  /// Índice do quadrilátero na lista de entrada
+  pub indice: usize,
+
+  /// Quadrilátero em sua posição final
+  pub q: Quadrilatero,
+
+  /// Se o quadrilátero foi girado em 90 graus
+  pub girado: bool
+}
+
+
+/// Resultado de um empacotamento
+#[derive(Debug, Clone)]
+pub struct Empacotamento {
+  /// Recipiente final
+  pub recipiente: Quadrilatero,
+
+  /// Quadriláteros posicionados, na ordem da entrada
+  pub colocados: Vec<Colocado>,
+
+  /// Índices dos quadriláteros que não couberam
+  pub restantes: Vec<usize>,
+
+  /// Área do recipiente não ocupada
+  pub desperdicio: i64
+}
+
+
+/// Estado de uma heurística durante o empacotamento
+enum Estado {
+  /// Segmentos do horizonte: x, altura e largura
+  Skyline(Vec<(i32, i32, i32)>),
+
+  /// Retângulos livres máximos, possivelmente sobrepostos
+  MaxRects(Vec<Quadrilatero>),
+
+  /// Retângulos livres disjuntos
+  Guilhotina(Vec<Quadrilatero>)
+}
+
+
+// Funções
+/// Empacota os quadriláteros no recipiente, apenas as dimensões
+/// dos quadriláteros de entrada são consideradas
+pub fn empacotar(
+  quads: &[Quadrilatero],
+  recipiente: Recipiente,
+  heuristica: Heuristica,
+  girar: bool
+) -> Empacotamento {
+  match recipiente {
+    Recipiente::Fixo(tx, ty) => {
+      empacota_em(quads, tx as i32, ty as i32, heuristica, girar)
+    },
+
+    Recipiente::Crescente(tx, ty) => {
+      let (mut tx, mut ty) = ((tx as i32).max(1), (ty as i32).max(1));
+      loop {
+        let mut e = empacota_em(quads, tx, ty, heuristica, girar);
+
+        // o limite evita que dimensões impossíveis estourem, e os
+        // quadriláteros sem área nunca cabem
+        let cabe = e.restantes.iter().all(|&i| sem_area(quads[i]));
+        if cabe || tx.max(ty) > i32::MAX / 4 {
+          ajusta(&mut e);
+          return e;
+        }
+
+        // dobra o lado menor
+        if tx <= ty {
+          tx *= 2;
+        } else {
+          ty *= 2;
+        }
+      }
+    },
+  }
+}
+
+
+/// Empacota os quadriláteros em um recipiente de dimensões fixas
+fn empacota_em(
+  quads: &[Quadrilatero],
+  tx: i32,
+  ty: i32,
+  heuristica: Heuristica,
+  girar: bool
+) -> Empacotamento {
+  let recipiente = quad(0, 0, tx, ty);
+
+  let mut estado = match heuristica {
+    Heuristica::Skyline    => Estado::Skyline(vec![(0, 0, tx)]),
+    Heuristica::MaxRects   => Estado::MaxRects(vec![recipiente]),
+    Heuristica::Guilhotina => Estado::Guilhotina(vec![recipiente]),
+  };
+
+  // maiores primeiro, o lado maior decide e a área desempata
+  let mut ordem: Vec<usize> = (0..quads.len()).collect();
+  ordem.sort_by_key(|&i| {
+    let q = quads[i];
+    (core::cmp::Reverse(q.tx.max(q.ty)), core::cmp::Reverse(q.area()))
+  });
+
+  let mut colocados = Vec::with_capacity(quads.len());
+  let mut restantes = Vec::new();
+  for i in ordem {
+    let (w, h) = (quads[i].tx, quads[i].ty);
+    if sem_area(quads[i]) {
+      restantes.push(i);
+      continue;
+    }
+
+    match estado.coloca(w, h, girar, tx, ty) {
+      Some((q, girado)) => colocados.push(Colocado { indice: i, q, girado }),
+      None              => restantes.push(i),
+    }
+  }
+
+  colocados.sort_by_key(|c| c.indice);
+  restantes.sort_unstable();
+
+  let usada: i64 = colocados.iter().map(|c| c.q.area()).sum();
+  Empacotamento {
+    recipiente,
+    colocados,
+    restantes,
+    desperdicio: recipiente.area() - usada
+  }
+}
+
+
+/// Reduz o recipiente ao espaço efetivamente usado
+fn ajusta( e: &mut Empacotamento) {
+  let tx = e.colocados.iter().map(|c| c.q.p.x + c.q.tx).max().unwrap_or(0);
+  let ty = e.colocados.iter().map(|c| c.q.p.y + c.q.ty).max().unwrap_or(0);
+  let usada: i64 = e.colocados.iter().map(|c| c.q.area()).sum();
+
+  e.recipiente.tx = tx;
+  e.recipiente.ty = ty;
+  e.desperdicio = e.recipiente.area() - usada;
+}
+
+
+/// Analisa se o quadrilátero não tem área para ser posicionado
+fn sem_area( q: Quadrilatero) -> bool {
+  q.tx <= 0 || q.ty <= 0
+}
+
+
+/// Analisa se o quadrilátero `a` contém inteiramente o `b`
+fn contem( a: Quadrilatero, b: Quadrilatero) -> bool {
+  a.p.x <= b.p.x && b.p.x + b.tx <= a.p.x + a.tx &&
+  a.p.y <= b.p.y && b.p.y + b.ty <= a.p.y + a.ty
+}
+
+
+/// Gera um quadrilátero através de coordenadas e dimensões inteiras
+fn quad( x: i32, y: i32, tx: i32, ty: i32) -> Quadrilatero {
+  Quadrilatero {
+    p: Ponto::new(x, y),
+    tx,
+    ty
+  }
+}
+
+
+// Métodos relacionados ao estado
+impl Estado {
+
+
+  /// Posiciona um quadrilátero de dimensões `w` × `h`, testando
+  /// também a versão girada, e retorna sua posição final
+  fn coloca( &mut self, w: i32, h: i32, girar: bool, tx: i32, ty: i32) -> Option<(Quadrilatero, bool)> {
+    let mut opcoes = vec![(w, h, false)];
+    if girar && w != h {
+      opcoes.push((h, w, true));
+    }
+
+    // menor pontuação vence, a primeira opção desempata
+    let mut melhor: Option<((i64, i64), usize, Quadrilatero, bool)> = None;
+    for (w, h, girado) in opcoes {
+      if let Some((pontos, indice, q)) = self.busca(w, h, tx, ty) {
+        if melhor.is_none_or(|m| pontos < m.0) {
+          melhor = Some((pontos, indice, q, girado));
+        }
+      }
+    }
+
+    let (_, indice, q, girado) = melhor?;
+    self.ocupa(indice, q);
+    return Some((q, girado));
+  }
+
+
+  /// Busca a melhor posição para as dimensões, retornando
+  /// a pontuação, o índice da região usada e a posição
+  fn busca( &self, w: i32, h: i32, tx: i32, ty: i32) -> Option<((i64, i64), usize, Quadrilatero)> {
+    let mut melhor: Option<((i64, i64), usize, Quadrilatero)> = None;
+
+    match self {
+      Estado::Skyline(horizonte) => {
+        for (i, &(x, _, _)) in horizonte.iter().enumerate() {
+          if x + w > tx {
+            break;
+          }
+
+          // o quadrilátero repousa sobre o segmento mais alto que cobre
+          let y = horizonte[i..].iter()
+            .take_while(|s| s.0 < x + w)
+            .map(|s| s.1)
+            .max()
+            .unwrap_or(0);
+
+          if y + h > ty {
+            continue;
+          }
+
+          let pontos = ((y + h) as i64, x as i64);
+          if melhor.is_none_or(|m| pontos < m.0) {
+            melhor = Some((pontos, i, quad(x, y, w, h)));
+          }
+        }
+      },
+
+      Estado::MaxRects(livres) => {
+        for (i, f) in livres.iter().enumerate() {
+          if w > f.tx || h > f.ty {
+            continue;
+          }
+
+          // menor sobra no lado curto, depois no lado longo
+          let sx = (f.tx - w) as i64;
+          let sy = (f.ty - h) as i64;
+          let pontos = (sx.min(sy), sx.max(sy));
+          if melhor.is_none_or(|m| pontos < m.0) {
+            melhor = Some((pontos, i, quad(f.p.x, f.p.y, w, h)));
+          }
+        }
+      },
+
+      Estado::Guilhotina(livres) => {
+        for (i, f) in livres.iter().enumerate() {
+          if w > f.tx || h > f.ty {
+            continue;
+          }
+
+          // área mais próxima, depois a menor sobra no lado curto
+          let sobra = f.area() - (w as i64 * h as i64);
+          let curta = ((f.tx - w).min(f.ty - h)) as i64;
+          let pontos = (sobra, curta);
+          if melhor.is_none_or(|m| pontos < m.0) {
+            melhor = Some((pontos, i, quad(f.p.x, f.p.y, w, h)));
+          }
+        }
+      },
+    }
+
+    return melhor;
+  }
+
+
+  /// Marca a posição como ocupada, atualizando as regiões livres
+  fn ocupa( &mut self, indice: usize, q: Quadrilatero) {
+    match self {
+      Estado::Skyline(horizonte) => {
+        let (x, y) = (q.p.x, q.p.y + q.ty);
+        let fim = x + q.tx;
+
+        // os segmentos sob o quadrilátero são cortados ou removidos
+        let mut novo = Vec::with_capacity(horizonte.len() + 2);
+        novo.extend(horizonte[..indice].iter().copied());
+        novo.push((x, y, q.tx));
+        for &(sx, sy, sw) in horizonte[indice..].iter() {
+          if sx + sw <= fim {
+            continue;
+          }
+
+          if sx < fim {
+            novo.push((fim, sy, sx + sw - fim));
+          } else {
+            novo.push((sx, sy, sw));
+          }
+        }
+
+        // segmentos vizinhos de mesma altura são unidos
+        horizonte.clear();
+        for s in novo {
+          match horizonte.last_mut() {
+            Some(u) if u.1 == s.1 => u.2 += s.2,
+            _                     => horizonte.push(s),
+          }
+        }
+      },
+
+      Estado::MaxRects(livres) => {
+        let mut novos = Vec::with_capacity(livres.len() + 4);
+        for f in livres.drain(..) {
+          if f.intersect(q).is_none() {
+            novos.push(f);
+            continue;
+          }
+
+          // até quatro retângulos máximos sobram ao redor
+          let (fx2, fy2) = (f.p.x + f.tx, f.p.y + f.ty);
+          let (qx2, qy2) = (q.p.x + q.tx, q.p.y + q.ty);
+          if q.p.x > f.p.x { novos.push(quad(f.p.x, f.p.y, q.p.x - f.p.x, f.ty)); }
+          if qx2 < fx2     { novos.push(quad(qx2, f.p.y, fx2 - qx2, f.ty)); }
+          if q.p.y > f.p.y { novos.push(quad(f.p.x, f.p.y, f.tx, q.p.y - f.p.y)); }
+          if qy2 < fy2     { novos.push(quad(f.p.x, qy2, f.tx, fy2 - qy2)); }
+        }
+
+        // retângulos contidos em outros não são máximos
+        for (i, f) in novos.iter().enumerate() {
+          let contido = novos.iter().enumerate().any(|(j, g)| {
+            i != j && contem(*g, *f) && (!contem(*f, *g) || j < i)
+          });
+
+          if !contido {
+            livres.push(*f);
+          }
+        }
+      },
+
+      Estado::Guilhotina(livres) => {
+        let f = livres.swap_remove(indice);
+        let sx = f.tx - q.tx;
+        let sy = f.ty - q.ty;
+
+        // o corte segue o eixo de menor sobra
+        let (direita, cima) = if sx < sy {
+          (quad(f.p.x + q.tx, f.p.y, sx, q.ty), quad(f.p.x, f.p.y + q.ty, f.tx, sy))
+        } else {
+          (quad(f.p.x + q.tx, f.p.y, sx, f.ty), quad(f.p.x, f.p.y + q.ty, q.tx, sy))
+        };
+
+        for r in [direita, cima].iter() {
+          if r.area() > 0 {
+            livres.push(*r);
+          }
+        }
+      },
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::random_data::entre;
+
+
+  /// Confere as posições de um empacotamento contra a entrada
+  fn confere( quads: &[Quadrilatero], e: &Empacotamento) {
+    let r = e.recipiente;
+    for c in e.colocados.iter() {
+      let o = quads[c.indice];
+      let dimensoes = if c.girado { (o.ty, o.tx) } else { (o.tx, o.ty) };
+      assert_eq!((c.q.tx, c.q.ty), dimensoes);
+      assert!(contem(r, c.q), "{:?} fora de {:?}", c.q, r);
+    }
+
+    for (i, a) in e.colocados.iter().enumerate() {
+      for b in e.colocados[i + 1..].iter() {
+        let (a, b) = (a.q, b.q);
+        let separados = a.p.x + a.tx <= b.p.x || b.p.x + b.tx <= a.p.x
+          || a.p.y + a.ty <= b.p.y || b.p.y + b.ty <= a.p.y;
+        assert!(separados, "{:?} sobrepõe {:?}", a, b);
+      }
+    }
+
+    // cada índice aparece uma única vez
+    let mut indices: Vec<usize> = e.colocados.iter().map(|c| c.indice).chain(e.restantes.iter().copied()).collect();
+    indices.sort_unstable();
+    assert_eq!(indices, (0..quads.len()).collect::<Vec<_>>());
+
+    let usada: i64 = e.colocados.iter().map(|c| c.q.area()).sum();
+    assert_eq!(e.desperdicio, r.area() - usada);
+  }
+
+
+  #[test]
+  fn sem_sobreposicao_e_dentro_do_recipiente() {
+    let mut estado = 0x9e37_79b9_7f4a_7c15;
+    for rodada in 0..300 {
+      let n = entre(&mut estado, 0, 39) as usize;
+      let quads: Vec<Quadrilatero> = (0..n)
+        .map(|_| quad(0, 0, 1 + entre(&mut estado, 0, 29), 1 + entre(&mut estado, 0, 29)))
+        .collect();
+
+      for &h in [Heuristica::Skyline, Heuristica::MaxRects, Heuristica::Guilhotina].iter() {
+        for &girar in [false, true].iter() {
+          let fixo = empacotar(&quads, Recipiente::Fixo(64, 48), h, girar);
+          confere(&quads, &fixo);
+
+          let crescente = empacotar(&quads, Recipiente::Crescente(16, 16), h, girar);
+          confere(&quads, &crescente);
+          assert!(crescente.restantes.is_empty(), "rodada {} {:?}", rodada, h);
+        }
+      }
+    }
+  }
+
+
+  #[test]
+  fn sem_area_fica_de_fora() {
+    let quads = [quad(0, 0, 0, 5), quad(3, 3, 4, 4), quad(0, 0, 5, -1)];
+    for &h in [Heuristica::Skyline, Heuristica::MaxRects, Heuristica::Guilhotina].iter() {
+      let e = empacotar(&quads, Recipiente::Crescente(1, 1), h, true);
+      confere(&quads, &e);
+      assert_eq!(e.restantes, vec![0, 2]);
+      assert_eq!(e.colocados.len(), 1);
+    }
+  }
+
+
+  #[test]
+  fn recipiente_fixo_deixa_de_fora_o_que_nao_cabe() {
+    let quads = [quad(0, 0, 10, 10), quad(0, 0, 11, 2), quad(0, 0, 2, 11)];
+    let e = empacotar(&quads, Recipiente::Fixo(10, 10), Heuristica::MaxRects, false);
+    confere(&quads, &e);
+    assert_eq!(e.restantes, vec![1, 2]);
+    assert_eq!(e.desperdicio, 0);
+  }
+}
@@ -0,0 +1,42 @@
+/// Tipos mais usados da biblioteca, para importar de uma vez
+/// com `use jeotry::prelude::*`.
+pub use crate::vector_data::{
+    Ponto as Ponto,
+    Vetor as Vetor,
+    Modulo as Modulo,
+};
+pub use crate::object_data::{
+    Bloco as Bloco,
+    Quadrilatero as Quadrilatero,
+    Circulo as Circulo,
+    QuadOrientado as QuadOrientado,
+    Segmento as Segmento,
+    Poligono as Poligono,
+    Forma as Forma,
+};
+pub use crate::transform_data::Transformacao;
+pub use crate::hull_data::Envoltoria;
+pub use crate::kdtree_data::ArvoreKd;
+pub use crate::delaunay_data::Triangulacao;
+pub use crate::voronoi_data::Voronoi;
+#[cfg(feature = "std")]
+pub use crate::scene_data::{
+    Cena as Cena,
+    Elemento as Elemento,
+};
+
+#[cfg(feature = "render")]
+pub use crate::svg_data::{
+    Svg as Svg,
+    Estilo as Estilo,
+};
+#[cfg(feature = "render")]
+pub use crate::term_data::Terminal;
+#[cfg(feature = "render")]
+pub use crate::raster_data::Imagem;
+
+#[cfg(feature = "physics")]
+pub use crate::contact_data::{
+    Camada as Camada,
+    Rastreador as Rastreador,
+};
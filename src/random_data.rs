@@ -0,0 +1,16 @@
+/// Gerador xorshift de 64 bits, reproduzível a partir da semente
+/// e sem estado global, usado para embaralhar entradas e para
+/// sortear os casos dos testes. O estado nunca deve ser zero
+pub(crate) fn sorteio( estado: &mut u64) -> u64 {
+  *estado ^= *estado << 13;
+  *estado ^= *estado >> 7;
+  *estado ^= *estado << 17;
+  *estado
+}
+
+
+/// Inteiro sorteado no intervalo fechado entre `min` e `max`
+#[cfg(test)]
+pub(crate) fn entre( estado: &mut u64, min: i32, max: i32) -> i32 {
+  (min as i64 + (sorteio(estado) % (max as i64 - min as i64 + 1) as u64) as i64) as i32
+}
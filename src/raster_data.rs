@@ -0,0 +1,905 @@
+/// Rasterização de objetos em imagens de pixels, sem dependências.
+///
+/// As imagens são escritas nos formatos binários PPM (cores) e
+/// PGM (tons de cinza), que podem ser abertos pela maioria dos
+/// visualizadores e comparados byte a byte em testes.
+///
+/// As coordenadas dos pixels seguem a convenção dos módulos de
+/// data: o pixel (0, 0) fica na esquerda-inferior da imagem. Os
+/// objetos são levados do plano aos pixels por uma transformação,
+/// o que permite escolher a escala e o enquadramento do desenho.
+///
+/// Preenchimentos amostram o centro de cada pixel, de forma que
+/// um bloco de (0, 0) a (4, 2) pinta exatamente 4 × 2 pixels.
+pub use super::object_data::{
+    Ponto as Ponto,
+    Forma as Forma,
+};
+use super::transform_data::Transformacao;
+use std::fmt;
+
+
+/// Cor em vermelho, verde e azul
+pub type Cor = [u8; 3];
+
+/// Variável de ambiente que, quando definida, faz as comparações
+/// com imagens de referência regravarem as referências
+pub static ATUALIZAR_REFERENCIAS: &str = "JEOTRY_ATUALIZAR";
+
+
+// Estruturas
+/// Imagem em memória
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Imagem {
+  /// Largura em pixels
+  pub largura: usize,
+
+  /// Altura em pixels
+  pub altura: usize,
+
+  /// Suaviza as linhas com anti-aliasing
+  pub suave: bool,
+
+  /// Pixels em linhas de cima para baixo
+  pixels: Vec<Cor>
+}
+
+
+/// Resultado de uma comparação que encontrou diferenças
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diferenca {
+  /// Quantidade de pixels acima da tolerância
+  pub pixels: usize,
+
+  /// Maior diferença encontrada em um canal
+  pub maxima: u8,
+
+  /// Primeiro pixel diferente, em coordenadas da imagem
+  pub primeiro: Option<(usize, usize)>,
+
+  /// As dimensões das imagens não coincidem
+  pub dimensoes: bool
+}
+
+
+// Métodos relacionados à imagem
+impl Imagem {
+
+
+  /// Gera uma nova imagem preenchida com uma cor
+  pub fn new( largura: usize, altura: usize, fundo: Cor) -> Self {
+    Self {
+      largura,
+      altura,
+      suave: false,
+      pixels: vec![fundo; largura * altura]
+    }
+  }
+
+
+  /// Retorna a cor de um pixel
+  pub fn get( &self, x: i64, y: i64) -> Option<Cor> {
+    let i = self.indice(x, y)?;
+    Some(self.pixels[i])
+  }
+
+
+  /// Pinta um pixel, pixels fora da imagem são ignorados
+  pub fn set( &mut self, x: i64, y: i64, cor: Cor) {
+    if let Some(i) = self.indice(x, y) {
+      self.pixels[i] = cor;
+    }
+  }
+
+
+  /// Mistura uma cor a um pixel com a opacidade dada, de 0 a 1
+  pub fn mistura( &mut self, x: i64, y: i64, cor: Cor, alfa: f64) {
+    if let Some(i) = self.indice(x, y) {
+      let a = alfa.clamp(0.0, 1.0);
+      let antes = self.pixels[i];
+      for c in 0..3 {
+        let v = antes[c] as f64 * (1.0 - a) + cor[c] as f64 * a;
+        self.pixels[i][c] = v.round() as u8;
+      }
+    }
+  }
+
+
+  /// Desenha uma linha entre dois pixels, pelo algoritmo de
+  /// Bresenham ou pelo de Wu quando a imagem é suave
+  pub fn linha( &mut self, p1: Ponto, p2: Ponto, cor: Cor) {
+    if self.suave {
+      let a = (p1.x as f64, p1.y as f64);
+      let b = (p2.x as f64, p2.y as f64);
+      self.linha_wu(a, b, cor);
+      return;
+    }
+
+    // a linha é percorrida no eixo de maior variação, apenas no
+    // trecho em que esse eixo está dentro da imagem
+    let (a, b) = ((p1.x as i64, p1.y as i64), (p2.x as i64, p2.y as i64));
+    let ingreme = (b.1 - a.1).abs() > (b.0 - a.0).abs();
+    let (a, b, limite) = if ingreme {
+      ((a.1, a.0), (b.1, b.0), self.altura as i64)
+    } else {
+      (a, b, self.largura as i64)
+    };
+
+    let (dx, dy) = ((b.0 - a.0).abs(), (b.1 - a.1).abs());
+    let sx = if a.0 < b.0 { 1 } else { -1 };
+    let sy = if a.1 < b.1 { 1 } else { -1 };
+    let (inicio, fim) = if sx > 0 {
+      (-a.0, limite - 1 - a.0)
+    } else {
+      (a.0 - (limite - 1), a.0)
+    };
+
+    for k in inicio.max(0)..=fim.min(dx) {
+      // o mesmo pixel que o passo k de Bresenham escolheria, em
+      // i128 porque o produto passa de i64 nas coordenadas de i32
+      let m = if dx == 0 {
+        0
+      } else {
+        ((2 * k as i128 * dy as i128 + dx as i128) / (2 * dx as i128)) as i64
+      };
+
+      let (x, y) = (a.0 + sx * k, a.1 + sy * m);
+      if ingreme {
+        self.set(y, x, cor);
+      } else {
+        self.set(x, y, cor);
+      }
+    }
+  }
+
+
+  /// Desenha uma linha suavizada entre coordenadas reais
+  /// pelo algoritmo de Wu
+  fn linha_wu( &mut self, a: (f64, f64), b: (f64, f64), cor: Cor) {
+    let ingreme = (b.1 - a.1).abs() > (b.0 - a.0).abs();
+
+    // a linha é percorrida no eixo de maior variação
+    let (mut a, mut b) = if ingreme {
+      ((a.1, a.0), (b.1, b.0))
+    } else {
+      (a, b)
+    };
+    if a.0 > b.0 {
+      std::mem::swap(&mut a, &mut b);
+    }
+
+    // apenas as posições dentro da imagem no eixo percorrido
+    let limite = if ingreme { self.altura } else { self.largura } as i64;
+    let dx = b.0 - a.0;
+    let inclinacao = if dx == 0.0 { 1.0 } else { (b.1 - a.1) / dx };
+    let mut pinta = |x: i64, y: i64, alfa: f64| {
+      if ingreme {
+        self.mistura(y, x, cor, alfa);
+      } else {
+        self.mistura(x, y, cor, alfa);
+      }
+    };
+
+    let inicio = (a.0.round() as i64).max(0);
+    let fim = (b.0.round() as i64).min(limite - 1);
+    for x in inicio..=fim {
+      let y = a.1 + inclinacao * (x as f64 - a.0);
+      let base = y.floor();
+      let frac = y - base;
+      pinta(x, base as i64, 1.0 - frac);
+      pinta(x, base as i64 + 1, frac);
+    }
+  }
+
+
+  /// Desenha o contorno de um círculo pelo algoritmo do ponto médio
+  pub fn circulo( &mut self, c: Ponto, r: i32, cor: Cor) {
+    if r < 0 {
+      return;
+    }
+
+    let (cx, cy, r) = (c.x as i64, c.y as i64, r as i64);
+    let (w, h) = (self.largura as i64, self.altura as i64);
+    let fim = fim_do_octante(r);
+
+    // só as alturas do octante cujas simetrias caem em linhas
+    // ou colunas da imagem são percorridas
+    let faixas = [(-cy, h - 1 - cy), (cy - h + 1, cy), (-cx, w - 1 - cx), (cx - w + 1, cx)];
+    for &(inicio, ultimo) in faixas.iter() {
+      for y in inicio.max(0)..=ultimo.min(fim) {
+        let x = octante_x(r, y);
+        for (sx, sy) in [(x, y), (y, x)].iter() {
+          self.set(cx + sx, cy + sy, cor);
+          self.set(cx - sx, cy + sy, cor);
+          self.set(cx + sx, cy - sy, cor);
+          self.set(cx - sx, cy - sy, cor);
+        }
+      }
+    }
+  }
+
+
+  /// Preenche um círculo com as linhas horizontais do ponto médio
+  pub fn circulo_cheio( &mut self, c: Ponto, r: i32, cor: Cor) {
+    if r < 0 {
+      return;
+    }
+
+    let (cx, cy, r) = (c.x as i64, c.y as i64, r as i64);
+    let fim = fim_do_octante(r);
+
+    // cada linha da imagem recebe a maior meia largura que as
+    // linhas do octante e de sua reflexão dariam a ela
+    for linha in 0..self.altura as i64 {
+      let d = (linha - cy).abs();
+      let meia = if d <= fim {
+        octante_x(r, d)
+      } else if d <= r {
+        // a maior altura do octante que ainda alcança a distância d
+        let (mut baixo, mut alto) = (0, fim);
+        while baixo < alto {
+          let meio = baixo + (alto - baixo + 1) / 2;
+          if octante_x(r, meio) >= d {
+            baixo = meio;
+          } else {
+            alto = meio - 1;
+          }
+        }
+        baixo
+      } else {
+        continue;
+      };
+
+      self.horizontal(cx - meia, cx + meia, linha, cor);
+    }
+  }
+
+
+  /// Desenha o contorno de um polígono
+  pub fn poligono( &mut self, pontos: &[(f64, f64)], cor: Cor) {
+    let n = pontos.len();
+    for i in 0..n {
+      let a = pontos[i];
+      let b = pontos[(i + 1) % n];
+      if self.suave {
+        self.linha_wu(a, b, cor);
+      } else {
+        self.linha(arredonda(a), arredonda(b), cor);
+      }
+    }
+  }
+
+
+  /// Preenche um polígono por linhas de varredura, com a
+  /// regra par-ímpar
+  pub fn poligono_cheio( &mut self, pontos: &[(f64, f64)], cor: Cor) {
+    let n = pontos.len();
+    if n < 3 {
+      return;
+    }
+
+    let y_min = pontos.iter().map(|p| p.1).fold(f64::MAX, f64::min).floor() as i64;
+    let y_max = pontos.iter().map(|p| p.1).fold(f64::MIN, f64::max).ceil() as i64;
+    let y_min = y_min.max(0);
+    let y_max = y_max.min(self.altura as i64);
+
+    let mut cortes: Vec<f64> = Vec::with_capacity(n);
+    for y in y_min..y_max {
+      // a varredura passa pelo centro dos pixels
+      let yc = y as f64 + 0.5;
+      cortes.clear();
+      for i in 0..n {
+        let a = pontos[i];
+        let b = pontos[(i + 1) % n];
+        if (a.1 > yc) != (b.1 > yc) {
+          cortes.push(a.0 + (yc - a.1) / (b.1 - a.1) * (b.0 - a.0));
+        }
+      }
+
+      cortes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+      for par in cortes.chunks(2) {
+        if let [x1, x2] = par {
+          // pixels cujo centro está entre os cortes
+          let inicio = (x1 - 0.5).ceil() as i64;
+          let fim = (x2 - 0.5).ceil() as i64 - 1;
+          self.horizontal(inicio, fim, y, cor);
+        }
+      }
+    }
+  }
+
+
+  /// Desenha uma forma levada aos pixels pela transformação dada,
+  /// preenchida ou apenas o contorno
+  pub fn forma( &mut self, f: &Forma, t: Transformacao, cor: Cor, cheia: bool) {
+    match t.aplica(f) {
+      Forma::Circulo(c) => {
+        let r = c.r.round() as i32;
+        if cheia {
+          self.circulo_cheio(c.p, r, cor);
+        } else {
+          self.circulo(c.p, r, cor);
+        }
+      },
+
+      Forma::Segmento(s) => self.linha(s.p1, s.p2, cor),
+
+      outra => {
+        let pontos = contorno(&outra);
+        if cheia {
+          self.poligono_cheio(&pontos, cor);
+        } else {
+          self.poligono(&pontos, cor);
+        }
+      },
+    }
+  }
+
+
+  /// Retorna a imagem em tons de cinza, pela luminância
+  pub fn cinza( &self ) -> Vec<u8> {
+    self.pixels.iter()
+      .map(|c| (0.299 * c[0] as f64 + 0.587 * c[1] as f64 + 0.114 * c[2] as f64).round() as u8)
+      .collect()
+  }
+
+
+  /// Codifica a imagem no formato PPM binário (P6)
+  pub fn ppm( &self ) -> Vec<u8> {
+    let mut saida = format!("P6\n{} {}\n255\n", self.largura, self.altura).into_bytes();
+    saida.reserve(self.pixels.len() * 3);
+    for c in self.pixels.iter() {
+      saida.extend_from_slice(c);
+    }
+
+    return saida;
+  }
+
+
+  /// Codifica a imagem no formato PGM binário (P5)
+  pub fn pgm( &self ) -> Vec<u8> {
+    let mut saida = format!("P5\n{} {}\n255\n", self.largura, self.altura).into_bytes();
+    saida.extend(self.cinza());
+    return saida;
+  }
+
+
+  /// Escreve a imagem em um arquivo PPM
+  pub fn salvar_ppm<P: AsRef<std::path::Path>>( &self, caminho: P) -> std::io::Result<()> {
+    std::fs::write(caminho, self.ppm())
+  }
+
+
+  /// Escreve a imagem em um arquivo PGM
+  pub fn salvar_pgm<P: AsRef<std::path::Path>>( &self, caminho: P) -> std::io::Result<()> {
+    std::fs::write(caminho, self.pgm())
+  }
+
+
+  /// Lê uma imagem PPM (P6) ou PGM (P5) binária com 8 bits por canal
+  pub fn ler( bytes: &[u8]) -> Result<Self, String> {
+    let mut pos = 0;
+    let mut campos = Vec::with_capacity(4);
+
+    // cabeçalho: tipo, largura, altura e valor máximo,
+    // separados por espaços e com comentários após `#`
+    while campos.len() < 4 {
+      while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'#') {
+        if bytes[pos] == b'#' {
+          while pos < bytes.len() && bytes[pos] != b'\n' {
+            pos += 1;
+          }
+        } else {
+          pos += 1;
+        }
+      }
+
+      let inicio = pos;
+      while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() {
+        pos += 1;
+      }
+
+      if inicio == pos {
+        return Err("cabeçalho incompleto".to_string());
+      }
+      campos.push(String::from_utf8_lossy(&bytes[inicio..pos]).to_string());
+    }
+
+    // um único espaço separa o cabeçalho dos dados
+    pos += 1;
+
+    let numero = |s: &str| s.parse::<usize>().map_err(|_| format!("número inválido '{}'", s));
+    let largura = numero(&campos[1])?;
+    let altura = numero(&campos[2])?;
+    if numero(&campos[3])? != 255 {
+      return Err(format!("apenas 8 bits por canal são aceitos, recebeu {}", campos[3]));
+    }
+
+    let canais = match campos[0].as_str() {
+      "P6" => 3,
+      "P5" => 1,
+      t    => return Err(format!("formato não suportado '{}'", t)),
+    };
+
+    // o cabeçalho não é confiável, o tamanho pode estourar
+    let tamanho = largura.checked_mul(altura)
+      .and_then(|n| n.checked_mul(canais))
+      .ok_or_else(|| format!("dimensões grandes demais: {} x {}", largura, altura))?;
+
+    let dados = bytes.get(pos..).unwrap_or(&[]);
+    if dados.len() < tamanho {
+      return Err("dados da imagem incompletos".to_string());
+    }
+
+    let pixels = dados[..tamanho]
+      .chunks(canais)
+      .map(|c| if canais == 3 { [c[0], c[1], c[2]] } else { [c[0]; 3] })
+      .collect();
+
+    return Ok(Self {
+      largura,
+      altura,
+      suave: false,
+      pixels
+    });
+  }
+
+
+  /// Compara com outra imagem, canais que diferem até a
+  /// tolerância são considerados iguais
+  pub fn compara( &self, outra: &Imagem, tolerancia: u8) -> Result<(), Diferenca> {
+    if self.largura != outra.largura || self.altura != outra.altura {
+      return Err(Diferenca {
+        pixels: self.pixels.len().max(outra.pixels.len()),
+        maxima: 255,
+        primeiro: None,
+        dimensoes: true
+      });
+    }
+
+    let mut d = Diferenca {
+      pixels: 0,
+      maxima: 0,
+      primeiro: None,
+      dimensoes: false
+    };
+
+    for (i, (a, b)) in self.pixels.iter().zip(outra.pixels.iter()).enumerate() {
+      let delta = (0..3).map(|c| a[c].abs_diff(b[c])).max().unwrap_or(0);
+      if delta > tolerancia {
+        d.pixels += 1;
+        d.maxima = d.maxima.max(delta);
+        if d.primeiro.is_none() {
+          d.primeiro = Some((i % self.largura, self.altura - 1 - i / self.largura));
+        }
+      }
+    }
+
+    if d.pixels > 0 {
+      return Err(d);
+    }
+
+    return Ok(());
+  }
+
+
+  /// Compara com uma imagem de referência gravada em disco.
+  ///
+  /// A referência é criada quando ainda não existe ou quando a
+  /// variável `JEOTRY_ATUALIZAR` está definida. Em caso de
+  /// diferença a imagem atual é gravada ao lado da referência,
+  /// com a extensão `.atual.ppm`, para inspeção.
+  pub fn confere_referencia<P: AsRef<std::path::Path>>( &self, caminho: P, tolerancia: u8) -> Result<(), String> {
+    let caminho = caminho.as_ref();
+    if !caminho.exists() || std::env::var_os(ATUALIZAR_REFERENCIAS).is_some() {
+      return self.salvar_ppm(caminho).map_err(|e| e.to_string());
+    }
+
+    let bytes = std::fs::read(caminho).map_err(|e| e.to_string())?;
+    let referencia = Imagem::ler(&bytes)?;
+
+    if let Err(d) = self.compara(&referencia, tolerancia) {
+      let atual = caminho.with_extension("atual.ppm");
+      let _ = self.salvar_ppm(&atual);
+      return Err(format!("{} ({}): {}", caminho.display(), atual.display(), d));
+    }
+
+    return Ok(());
+  }
+
+
+  /// Pinta uma linha horizontal de pixels, extremos inclusos
+  fn horizontal( &mut self, x1: i64, x2: i64, y: i64, cor: Cor) {
+    let inicio = x1.max(0);
+    let fim = x2.min(self.largura as i64 - 1);
+    for x in inicio..=fim {
+      self.set(x, y, cor);
+    }
+  }
+
+
+  /// Índice de um pixel no vetor, com o eixo y invertido
+  fn indice( &self, x: i64, y: i64) -> Option<usize> {
+    if x < 0 || y < 0 || x >= self.largura as i64 || y >= self.altura as i64 {
+      return None;
+    }
+
+    let linha = self.altura - 1 - y as usize;
+    Some(linha * self.largura + x as usize)
+  }
+}
+
+
+impl fmt::Display for Diferenca {
+  fn fmt( &self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.dimensoes {
+      return write!(f, "as dimensões das imagens são diferentes");
+    }
+
+    write!(f, "{} pixels diferentes, diferença máxima {}", self.pixels, self.maxima)?;
+    if let Some((x, y)) = self.primeiro {
+      write!(f, ", primeiro em ({}, {})", x, y)?;
+    }
+
+    Ok(())
+  }
+}
+
+
+/// Coordenada x do primeiro octante de um círculo de raio `r`
+/// na altura `y`, a mesma que o algoritmo do ponto médio chega
+/// passo a passo: a maior com x·(x - 1) < r² - y²
+fn octante_x( r: i64, y: i64) -> i64 {
+  let n = (r * r - y * y) as u128;
+  if n == 0 {
+    return 0;
+  }
+
+  return raiz(4 * n - 3).div_ceil(2) as i64;
+}
+
+
+/// Última altura do primeiro octante de um círculo de raio `r`,
+/// a maior em que y ainda não passou de x
+fn fim_do_octante( r: i64) -> i64 {
+  let (mut baixo, mut alto) = (0, r);
+  while baixo < alto {
+    let meio = baixo + (alto - baixo + 1) / 2;
+    if meio <= octante_x(r, meio) {
+      baixo = meio;
+    } else {
+      alto = meio - 1;
+    }
+  }
+
+  return baixo;
+}
+
+
+/// Raiz quadrada inteira, arredondada para baixo
+fn raiz( n: u128) -> u128 {
+  // a estimativa em reais erra por pouco e é corrigida
+  let mut s = (n as f64).sqrt() as u128;
+  while s * s > n {
+    s -= 1;
+  }
+  while (s + 1) * (s + 1) <= n {
+    s += 1;
+  }
+
+  return s;
+}
+
+
+/// Contorno de uma forma poligonal em coordenadas reais
+fn contorno( f: &Forma) -> Vec<(f64, f64)> {
+  let p = |v: Ponto| (v.x as f64, v.y as f64);
+  match f {
+    Forma::Bloco(b) => {
+      vec![p(b.p1), (b.p2.x as f64, b.p1.y as f64), p(b.p2), (b.p1.x as f64, b.p2.y as f64)]
+    },
+    Forma::Quadrilatero(q) => contorno(&Forma::Bloco(q.into_block())),
+    Forma::Orientado(o)    => o.corners().to_vec(),
+    Forma::Poligono(poli)  => poli.pontos.iter().map(|v| p(*v)).collect(),
+    Forma::Segmento(s)     => vec![p(s.p1), p(s.p2)],
+    Forma::Circulo(c)      => vec![p(c.p)],
+  }
+}
+
+
+/// Arredonda uma coordenada real para o pixel mais próximo
+fn arredonda( p: (f64, f64)) -> Ponto {
+  Ponto::new(p.0.round() as i32, p.1.round() as i32)
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::object_data::{Bloco, Circulo, Segmento};
+  use crate::random_data::entre;
+  use std::path::PathBuf;
+
+  const PRETO: Cor = [0, 0, 0];
+  const BRANCO: Cor = [255, 255, 255];
+
+
+  /// Compara com a referência do repositório, que só é criada
+  /// quando `JEOTRY_ATUALIZAR` está definida
+  fn confere( imagem: &Imagem, nome: &str) {
+    let caminho: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "referencias", nome].iter().collect();
+    assert!(
+      caminho.exists() || std::env::var_os(ATUALIZAR_REFERENCIAS).is_some(),
+      "referência {} ausente", caminho.display()
+    );
+    imagem.confere_referencia(&caminho, 0).unwrap();
+  }
+
+
+  /// Pixels pintados com a cor dada
+  fn pintados( imagem: &Imagem, cor: Cor) -> Vec<(i64, i64)> {
+    let mut saida = Vec::new();
+    for y in 0..imagem.altura as i64 {
+      for x in 0..imagem.largura as i64 {
+        if imagem.get(x, y) == Some(cor) {
+          saida.push((x, y));
+        }
+      }
+    }
+    return saida;
+  }
+
+
+  #[test]
+  fn linha_de_bresenham() {
+    let mut imagem = Imagem::new(12, 8, PRETO);
+    imagem.linha(Ponto::new(0, 1), Ponto::new(11, 6), BRANCO);
+
+    // um pixel por coluna, no máximo a meio pixel da reta
+    let pixels = pintados(&imagem, BRANCO);
+    assert_eq!(pixels.len(), 12);
+    for (x, y) in pixels {
+      let ideal = 1.0 + 5.0 * x as f64 / 11.0;
+      assert!((y as f64 - ideal).abs() <= 0.5, "({}, {})", x, y);
+    }
+
+    confere(&imagem, "linha.ppm");
+  }
+
+
+  #[test]
+  fn circulo_do_ponto_medio() {
+    let mut imagem = Imagem::new(17, 17, PRETO);
+    imagem.circulo(Ponto::new(8, 8), 6, BRANCO);
+
+    // simétrico nos oito octantes e perto do raio
+    let pixels = pintados(&imagem, BRANCO);
+    for &(x, y) in pixels.iter() {
+      let (dx, dy) = (x - 8, y - 8);
+      for (sx, sy) in [(dx, dy), (-dx, dy), (dx, -dy), (dy, dx)] {
+        assert!(pixels.contains(&(8 + sx, 8 + sy)));
+      }
+      let distancia = ((dx * dx + dy * dy) as f64).sqrt();
+      assert!((distancia - 6.0).abs() < 0.75, "({}, {})", x, y);
+    }
+
+    confere(&imagem, "circulo.ppm");
+  }
+
+
+  #[test]
+  fn poligono_preenchido() {
+    let triangulo = [(1.0, 1.0), (14.0, 3.0), (5.0, 11.0)];
+    let mut imagem = Imagem::new(16, 12, PRETO);
+    imagem.poligono_cheio(&triangulo, BRANCO);
+
+    // pixels pintados são os de centro dentro do triângulo
+    let dentro = |x: f64, y: f64| {
+      (0..3).all(|i| {
+        let (a, b) = (triangulo[i], triangulo[(i + 1) % 3]);
+        (b.0 - a.0) * (y - a.1) - (b.1 - a.1) * (x - a.0) > 0.0
+      })
+    };
+    for y in 0..12 {
+      for x in 0..16 {
+        let esperado = dentro(x as f64 + 0.5, y as f64 + 0.5);
+        assert_eq!(imagem.get(x, y) == Some(BRANCO), esperado, "({}, {})", x, y);
+      }
+    }
+
+    confere(&imagem, "poligono.ppm");
+  }
+
+
+  #[test]
+  fn cena_com_formas_transformadas() {
+    let mut imagem = Imagem::new(24, 20, [250, 250, 250]);
+    let (vermelho, azul, verde, roxo) = ([200, 40, 40], [40, 40, 200], [40, 160, 40], [120, 40, 160]);
+
+    // o dobro do tamanho: de (2, 2) a (8, 6)
+    let dobro = Transformacao::escala(2.0);
+    imagem.forma(&Forma::Bloco(Bloco::new((1, 1), (4, 3))), dobro, vermelho, true);
+
+    // um quadrado girado em 45° vira um losango em (17, 14)
+    let losango = Transformacao::rotacao(core::f32::consts::FRAC_PI_4).seguida(Transformacao::translacao(17.0, 14.0));
+    imagem.forma(&Forma::Bloco(Bloco::new((-2, -2), (2, 2))), losango, azul, true);
+
+    // um segmento horizontal girado em 90° ao redor de (8, 8)
+    let giro = Transformacao::rotacao_em(core::f32::consts::FRAC_PI_2, (8.0, 8.0));
+    imagem.forma(&Forma::Segmento(Segmento::new((2, 8), (14, 8))), giro, verde, true);
+
+    // um círculo de raio 2 passa a ter raio 4 em (18, 6)
+    let circulo = dobro.seguida(Transformacao::translacao(14.0, 2.0));
+    imagem.forma(&Forma::Circulo(Circulo::new((2, 2), 2.0)), circulo, roxo, false);
+
+    let mut bloco = Vec::new();
+    for y in 2..6 {
+      for x in 2..8 {
+        bloco.push((x, y));
+      }
+    }
+    assert_eq!(pintados(&imagem, vermelho), bloco);
+
+    // centros a menos de 2√2 do centro pela distância de Manhattan
+    let dentro = |(x, y): &(i64, i64)| (*x as f64 + 0.5 - 17.0).abs() + (*y as f64 + 0.5 - 14.0).abs() < 2.0 * 2f64.sqrt();
+    let azuis = pintados(&imagem, azul);
+    assert_eq!(azuis.len(), 12);
+    assert!(azuis.iter().all(dentro));
+
+    assert_eq!(pintados(&imagem, verde), (2..=14).map(|y| (8, y)).collect::<Vec<_>>());
+
+    let roxos = pintados(&imagem, roxo);
+    for p in [(22, 6), (14, 6), (18, 10), (18, 2)].iter() {
+      assert!(roxos.contains(p));
+    }
+    for &(x, y) in roxos.iter() {
+      let distancia = (((x - 18) * (x - 18) + (y - 6) * (y - 6)) as f64).sqrt();
+      assert!((distancia - 4.0).abs() < 0.75, "({}, {})", x, y);
+    }
+
+    confere(&imagem, "cena.ppm");
+  }
+
+
+  /// Pixels da linha de Bresenham percorrida passo a passo
+  fn bresenham( p1: (i64, i64), p2: (i64, i64)) -> Vec<(i64, i64)> {
+    let (mut x, mut y) = p1;
+    let dx = (p2.0 - x).abs();
+    let dy = -(p2.1 - y).abs();
+    let sx = if x < p2.0 { 1 } else { -1 };
+    let sy = if y < p2.1 { 1 } else { -1 };
+    let mut erro = dx + dy;
+
+    let mut pixels = Vec::new();
+    loop {
+      pixels.push((x, y));
+      if (x, y) == p2 {
+        return pixels;
+      }
+
+      let e2 = 2 * erro;
+      if e2 >= dy {
+        erro += dy;
+        x += sx;
+      }
+      if e2 <= dx {
+        erro += dx;
+        y += sy;
+      }
+    }
+  }
+
+
+  /// Pontos do primeiro octante percorrido passo a passo
+  fn octante( r: i64) -> Vec<(i64, i64)> {
+    let (mut x, mut y) = (r, 0);
+    let mut decisao = 1 - x;
+    let mut pontos = Vec::new();
+    while y <= x {
+      pontos.push((x, y));
+      y += 1;
+      if decisao < 0 {
+        decisao += 2 * y + 1;
+      } else {
+        x -= 1;
+        decisao += 2 * (y - x) + 1;
+      }
+    }
+
+    return pontos;
+  }
+
+
+  #[test]
+  fn linhas_e_circulos_cortados_pela_imagem() {
+    let mut estado = 0x5eed_0036;
+    for _ in 0..3000 {
+      let mut c = || entre(&mut estado, -30, 45) as i64;
+      let (a, b) = ((c(), c()), (c(), c()));
+      let (centro, r) = ((c(), c()), c().rem_euclid(31));
+
+      let mut imagem = Imagem::new(24, 16, PRETO);
+      imagem.linha(Ponto::new(a.0 as i32, a.1 as i32), Ponto::new(b.0 as i32, b.1 as i32), BRANCO);
+      let mut esperada = Imagem::new(24, 16, PRETO);
+      for (x, y) in bresenham(a, b) {
+        esperada.set(x, y, BRANCO);
+      }
+      assert!(imagem.compara(&esperada, 0).is_ok(), "{:?} {:?}", a, b);
+
+      let p = Ponto::new(centro.0 as i32, centro.1 as i32);
+      let (mut contorno, mut cheio) = (Imagem::new(24, 16, PRETO), Imagem::new(24, 16, PRETO));
+      contorno.circulo(p, r as i32, BRANCO);
+      cheio.circulo_cheio(p, r as i32, BRANCO);
+
+      let (mut contorno_esperado, mut cheio_esperado) = (Imagem::new(24, 16, PRETO), Imagem::new(24, 16, PRETO));
+      for (x, y) in octante(r) {
+        for (sx, sy) in [(x, y), (y, x)] {
+          for (mx, my) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
+            contorno_esperado.set(centro.0 + mx * sx, centro.1 + my * sy, BRANCO);
+          }
+          for my in [1, -1] {
+            cheio_esperado.horizontal(centro.0 - sx, centro.0 + sx, centro.1 + my * sy, BRANCO);
+          }
+        }
+      }
+      assert!(contorno.compara(&contorno_esperado, 0).is_ok(), "{:?} {}", centro, r);
+      assert!(cheio.compara(&cheio_esperado, 0).is_ok(), "{:?} {}", centro, r);
+    }
+
+    // coordenadas extremas só percorrem o trecho visível
+    let mut imagem = Imagem::new(16, 12, PRETO);
+    imagem.linha(Ponto::new(i32::MIN, i32::MIN), Ponto::new(i32::MAX, i32::MAX), BRANCO);
+    assert_eq!(pintados(&imagem, BRANCO), (0..12).map(|k| (k, k)).collect::<Vec<_>>());
+
+    imagem.suave = true;
+    imagem.linha(Ponto::new(3, i32::MIN), Ponto::new(3, i32::MAX), [0, 0, 255]);
+    assert_eq!(pintados(&imagem, [0, 0, 255]), (0..12).map(|y| (3, y)).collect::<Vec<_>>());
+
+    // o topo de um círculo de raio máximo passa pela linha 7
+    let (centro, r) = (Ponto::new(5, 7 - i32::MAX), i32::MAX);
+    let mut contorno = Imagem::new(16, 12, PRETO);
+    contorno.circulo(centro, r, BRANCO);
+    assert_eq!(pintados(&contorno, BRANCO), (0..16).map(|x| (x, 7)).collect::<Vec<_>>());
+
+    let mut cheio = Imagem::new(16, 12, PRETO);
+    cheio.circulo_cheio(centro, r, BRANCO);
+    assert_eq!(pintados(&cheio, BRANCO).len(), 16 * 8);
+    assert!(pintados(&cheio, BRANCO).iter().all(|&(_, y)| y <= 7));
+  }
+
+
+  #[test]
+  fn ppm_e_pgm_sao_relidos() {
+    let mut imagem = Imagem::new(5, 3, [10, 20, 30]);
+    imagem.set(4, 2, [255, 0, 128]);
+
+    let relida = Imagem::ler(&imagem.ppm()).unwrap();
+    assert!(relida.compara(&imagem, 0).is_ok());
+
+    let cinza = Imagem::ler(&imagem.pgm()).unwrap();
+    assert_eq!(cinza.get(4, 2).map(|c| c[0]), Some(imagem.cinza()[4]));
+
+    let mut outra = imagem.clone();
+    outra.set(1, 1, [10, 20, 33]);
+    assert!(outra.compara(&imagem, 3).is_ok());
+    let d = outra.compara(&imagem, 2).unwrap_err();
+    assert_eq!((d.pixels, d.maxima, d.primeiro), (1, 3, Some((1, 1))));
+  }
+
+
+  #[test]
+  fn cabecalhos_invalidos_sao_recusados() {
+    let casos: [&[u8]; 7] = [
+      b"P5 4294967296 4294967296 255\n",
+      b"P6 18446744073709551615 2 255\n",
+      b"P6 6148914691236517206 1 255\n",
+      b"P5 99999999999999999999 1 255\n",
+      b"P5 2 2 65535\n\0\0\0\0",
+      b"P3 1 1 255\n\0\0\0",
+      b"P5 2 2 255\n\0\0\0",
+    ];
+    for bytes in casos.iter() {
+      assert!(Imagem::ler(bytes).is_err(), "{}", String::from_utf8_lossy(bytes));
+    }
+  }
+}
@@ -0,0 +1,1138 @@
+/// Terminal interativo com variáveis nomeadas.
+///
+/// Cada linha é uma expressão, opcionalmente atribuída a uma
+/// variável, como em `p = ponto(1, 2)`, `b = bloco(p, (5, 5))`,
+/// `b.mov(vetor(2, 4))` ou `b collides c`. Métodos que alteram
+/// o objeto no Rust, como `mov`, também alteram a variável, a
+/// cada passo de chamadas encadeadas como `b.mov(v).mov(v)`.
+///
+/// Linhas que começam com `:` são comandos do terminal, como
+/// `:vars` e `:historico`, e `!n` repete a linha `n` do histórico.
+/// Os resultados são exibidos com as implementações de `Debug`,
+/// em forma compacta na listagem das variáveis.
+pub use jeotry::object_data::{
+    Ponto as Ponto,
+    Vetor as Vetor,
+    Bloco as Bloco,
+    Quadrilatero as Quadrilatero,
+    Circulo as Circulo,
+    QuadOrientado as QuadOrientado,
+    Segmento as Segmento,
+    Poligono as Poligono,
+    Forma as Forma,
+};
+use jeotry::vector_data::{
+  Modulo,
+  inner_angle,
+};
+use jeotry::scene_data::{
+  Cena,
+  Elemento,
+};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::io::{self, BufRead, Write};
+use std::fmt;
+
+
+/// Texto de ajuda do terminal
+pub static AJUDA: &str = "\
+construtores:
+  ponto(x, y)  vetor(x, y)  vetor(p1, p2)  modulo(v)  modulo(v1, v2)
+  bloco(p1, p2)  quad(p, tx, ty)  circulo(c, r)  orientado(c, hx, hy, â)
+  segmento(p1, p2)  poligono(p1, p2, p3, ...)
+funções:
+  distancia(a, b)  area(a)  angulo(v)  angulo(v, u)  colide(a, b)
+métodos:
+  .mov(v)  .sum(v)  .diff(p)  .module()  .angle()  .neg()
+  .area()  .contains(p)  .collide(a)  .distance(a)
+operadores:
+  a collides b  a + b  a - b  -a
+comandos:
+  :vars  :historico  :apagar nome  :salvar arquivo  :carregar arquivo
+  :ajuda  :sair  !n  !!
+pontos também podem ser escritos como pares, como em (1, 2)";
+
+/// Palavra usada no operador de colisão
+static COLIDE: &str = "collides";
+
+/// Profundidade máxima das expressões, contando parênteses,
+/// sinais, operadores e métodos encadeados
+const PROFUNDIDADE_MAXIMA: usize = 128;
+
+
+// Estruturas
+/// Valor resultante de uma expressão
+#[derive(Debug, Clone)]
+pub enum Valor {
+  Numero(f64),
+  Logico(bool),
+  Par(f64, f64),
+  Ponto(Ponto),
+  Vetor(Vetor),
+  Modulo(Modulo),
+  Forma(Forma)
+}
+
+
+/// Estado do terminal interativo
+#[derive(Debug, Clone, Default)]
+pub struct Repl {
+  /// Variáveis em ordem alfabética
+  variaveis: BTreeMap<String, Valor>,
+
+  /// Linhas avaliadas, na ordem
+  historico: Vec<String>
+}
+
+
+/// Partes de uma linha
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Numero(f64),
+  Nome(String),
+  Simbolo(char)
+}
+
+
+/// Árvore de uma expressão
+#[derive(Debug, Clone)]
+enum Expr {
+  Numero(f64),
+  Variavel(String),
+  Par(Box<Expr>, Box<Expr>),
+  Chamada(String, Vec<Expr>),
+  Metodo(Box<Expr>, String, Vec<Expr>),
+  Colide(Box<Expr>, Box<Expr>),
+  Soma(Box<Expr>, Box<Expr>),
+  Diferenca(Box<Expr>, Box<Expr>),
+  Negativo(Box<Expr>)
+}
+
+
+/// Leitor descendente recursivo sobre os tokens de uma linha
+struct Leitor {
+  tokens: Vec<Token>,
+  pos: usize,
+  profundidade: usize
+}
+
+
+// Métodos relacionados aos valores
+impl Valor {
+
+
+  /// Nome do tipo do valor
+  pub fn tipo( &self ) -> &'static str {
+    match self {
+      Valor::Numero(_) => "número",
+      Valor::Logico(_) => "lógico",
+      Valor::Par(..)   => "par",
+      Valor::Ponto(_)  => "ponto",
+      Valor::Vetor(_)  => "vetor",
+      Valor::Modulo(_) => "modulo",
+      Valor::Forma(f)  => Elemento::Forma(f.clone()).tipo(),
+    }
+  }
+
+
+  /// Objeto exibido pelo `Debug`, sem a variante do valor
+  fn objeto( &self ) -> &dyn fmt::Debug {
+    match self {
+      Valor::Numero(v)  => v,
+      Valor::Logico(b)  => b,
+      Valor::Par(..)    => self,
+      Valor::Ponto(p)   => p,
+      Valor::Vetor(v)   => v,
+      Valor::Modulo(m)  => m,
+      Valor::Forma(Forma::Bloco(b))        => b,
+      Valor::Forma(Forma::Quadrilatero(q)) => q,
+      Valor::Forma(Forma::Circulo(c))      => c,
+      Valor::Forma(Forma::Orientado(o))    => o,
+      Valor::Forma(Forma::Segmento(s))     => s,
+      Valor::Forma(Forma::Poligono(p))     => p,
+    }
+  }
+
+
+  /// Converte para um inteiro, sem perder a parte fracionária
+  fn inteiro( &self ) -> Result<i32, String> {
+    let v = self.real()?;
+    if v.fract() != 0.0 || v < i32::MIN as f64 || v > i32::MAX as f64 {
+      return Err(format!("esperava um inteiro, recebeu {}", v));
+    }
+
+    return Ok(v as i32);
+  }
+
+
+  /// Converte para um número real
+  fn real( &self ) -> Result<f64, String> {
+    match self {
+      Valor::Numero(v) => Ok(*v),
+      v                => Err(format!("esperava um número, recebeu {}", v.tipo())),
+    }
+  }
+
+
+  /// Converte para um ponto, aceitando pares de inteiros
+  fn ponto( &self ) -> Result<Ponto, String> {
+    match self {
+      Valor::Ponto(p)  => Ok(*p),
+      Valor::Par(x, y) => {
+        Ok(Ponto::new(Valor::Numero(*x).inteiro()?, Valor::Numero(*y).inteiro()?))
+      },
+      v                => Err(format!("esperava um ponto, recebeu {}", v.tipo())),
+    }
+  }
+
+
+  /// Converte para coordenadas reais
+  fn coordenadas( &self ) -> Result<(f64, f64), String> {
+    match self {
+      Valor::Ponto(p)  => Ok((p.x as f64, p.y as f64)),
+      Valor::Par(x, y) => Ok((*x, *y)),
+      v                => Err(format!("esperava um ponto, recebeu {}", v.tipo())),
+    }
+  }
+
+
+  /// Converte para um vetor, aceitando pares de inteiros
+  fn vetor( &self ) -> Result<Vetor, String> {
+    match self {
+      Valor::Vetor(v)  => Ok(*v),
+      Valor::Par(..)   => {
+        let p = self.ponto()?;
+        Ok(Vetor::new(p.x, p.y))
+      },
+      v                => Err(format!("esperava um vetor, recebeu {}", v.tipo())),
+    }
+  }
+
+
+  /// Converte para uma forma, pontos viram segmentos sem comprimento
+  fn forma( &self ) -> Result<Forma, String> {
+    match self {
+      Valor::Forma(f)  => Ok(f.clone()),
+      Valor::Ponto(p)  => Ok(Forma::Segmento(Segmento::from(*p, *p))),
+      v                => Err(format!("esperava uma forma, recebeu {}", v.tipo())),
+    }
+  }
+}
+
+
+impl fmt::Display for Valor {
+  fn fmt( &self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Valor::Numero(v) => write!(f, "{}", v),
+      Valor::Logico(b) => write!(f, "{}", b),
+      Valor::Par(x, y) => write!(f, "({}, {})", x, y),
+      v if f.alternate() => write!(f, "{:?}", v.objeto()),
+      v                => write!(f, "{:#?}", v.objeto()),
+    }
+  }
+}
+
+
+// Métodos relacionados ao terminal
+impl Repl {
+
+
+  /// Gera um terminal sem variáveis
+  pub fn new() -> Self {
+    Self {
+      variaveis: BTreeMap::new(),
+      historico: Vec::new()
+    }
+  }
+
+
+  /// Retorna o valor de uma variável
+  pub fn get( &self, nome: &str) -> Option<&Valor> {
+    self.variaveis.get(nome)
+  }
+
+
+  /// Define as variáveis a partir dos elementos de uma cena
+  pub fn carregar_cena( &mut self, cena: &Cena) {
+    for (nome, e) in cena.iter() {
+      let valor = match e {
+        Elemento::Ponto(p) => Valor::Ponto(*p),
+        Elemento::Vetor(v) => Valor::Vetor(*v),
+        Elemento::Forma(f) => Valor::Forma(f.clone()),
+      };
+      self.variaveis.insert(nome.to_string(), valor);
+    }
+  }
+
+
+  /// Gera uma cena com as variáveis que são pontos, vetores
+  /// ou formas, falha se alguma delas não puder ser escrita
+  pub fn cena( &self ) -> Result<Cena, String> {
+    let mut cena = Cena::new();
+    for (nome, v) in self.variaveis.iter() {
+      let e = match v {
+        Valor::Ponto(p) => Elemento::Ponto(*p),
+        Valor::Vetor(v) => Elemento::Vetor(*v),
+        Valor::Forma(f) => Elemento::Forma(f.clone()),
+        _               => continue,
+      };
+      cena.inserir(nome, e)?;
+    }
+
+    return Ok(cena);
+  }
+
+
+  /// Lê e avalia linhas até o fim da entrada ou `:sair`
+  pub fn executa<R: BufRead, W: Write>( &mut self, entrada: R, mut saida: W) -> io::Result<()> {
+    write!(saida, ">> ")?;
+    saida.flush()?;
+
+    for linha in entrada.lines() {
+      let linha = linha?;
+      if linha.trim() == ":sair" {
+        return Ok(());
+      }
+
+      match self.avaliar(&linha) {
+        Ok(Some(resultado)) => writeln!(saida, "{}", resultado)?,
+        Ok(None)            => {},
+        Err(e)              => writeln!(saida, "erro: {}", e)?,
+      }
+
+      write!(saida, ">> ")?;
+      saida.flush()?;
+    }
+
+    writeln!(saida)?;
+    return Ok(());
+  }
+
+
+  /// Avalia uma linha e retorna o texto a ser exibido
+  pub fn avaliar( &mut self, linha: &str) -> Result<Option<String>, String> {
+    let linha = linha.trim();
+    if linha.is_empty() {
+      return Ok(None);
+    }
+
+    if let Some(comando) = linha.strip_prefix(':') {
+      return self.comando(comando);
+    }
+
+    // repetição de uma linha do histórico
+    if let Some(n) = linha.strip_prefix('!') {
+      let indice = match n {
+        "!" => self.historico.len(),
+        n   => n.parse::<usize>().map_err(|_| format!("posição do histórico inválida '{}'", n))?,
+      };
+      let anterior = match indice.checked_sub(1).and_then(|i| self.historico.get(i)) {
+        Some(l) => l.clone(),
+        None    => return Err(format!("não existe a linha {} no histórico", indice)),
+      };
+      return self.avaliar(&anterior);
+    }
+
+    self.historico.push(linha.to_string());
+
+    let tokens = tokens(linha)?;
+
+    // atribuição
+    if let [Token::Nome(nome), Token::Simbolo('='), ..] = tokens.as_slice() {
+      if nome == COLIDE {
+        return Err(format!("'{}' é uma palavra reservada", COLIDE));
+      }
+
+      let nome = nome.clone();
+      let expr = Leitor::new(tokens[2..].to_vec()).linha()?;
+      let valor = self.avalia(&expr)?;
+      let texto = valor.to_string();
+      self.variaveis.insert(nome, valor);
+      return Ok(Some(texto));
+    }
+
+    let expr = Leitor::new(tokens).linha()?;
+    return Ok(Some(self.avalia(&expr)?.to_string()));
+  }
+
+
+  /// Executa um comando do terminal
+  fn comando( &mut self, comando: &str) -> Result<Option<String>, String> {
+    let mut partes = comando.split_whitespace();
+    let nome = partes.next().unwrap_or("");
+    let argumento = partes.next();
+
+    match (nome, argumento) {
+      ("vars", None) => {
+        let linhas: Vec<String> = self.variaveis.iter()
+          .map(|(nome, v)| format!("{}: {} = {:#}", nome, v.tipo(), v))
+          .collect();
+        Ok(Some(linhas.join("\n")).filter(|s| !s.is_empty()))
+      },
+
+      ("historico", None) => {
+        let linhas: Vec<String> = self.historico.iter().enumerate()
+          .map(|(i, l)| format!("{:>4}  {}", i + 1, l))
+          .collect();
+        Ok(Some(linhas.join("\n")).filter(|s| !s.is_empty()))
+      },
+
+      ("apagar", Some(nome)) => {
+        match self.variaveis.remove(nome) {
+          Some(_) => Ok(None),
+          None    => Err(format!("variável '{}' não definida", nome)),
+        }
+      },
+
+      ("salvar", Some(caminho)) => {
+        let cena = self.cena()?;
+        cena.salvar(caminho).map_err(|e| format!("{}: {}", caminho, e))?;
+        Ok(Some(format!("{} objetos salvos", cena.len())))
+      },
+
+      ("carregar", Some(caminho)) => {
+        let cena = Cena::carregar(caminho).map_err(|e| format!("{}: {}", caminho, e))?;
+        self.carregar_cena(&cena);
+        Ok(Some(format!("{} objetos carregados", cena.len())))
+      },
+
+      ("ajuda", None) => Ok(Some(AJUDA.to_string())),
+
+      _ => Err(format!("comando inválido ':{}', use :ajuda", comando)),
+    }
+  }
+
+
+  /// Avalia uma expressão
+  fn avalia( &mut self, e: &Expr) -> Result<Valor, String> {
+    match e {
+      Expr::Numero(v)   => Ok(Valor::Numero(*v)),
+
+      Expr::Variavel(n) => match self.variaveis.get(n) {
+        Some(v) => Ok(v.clone()),
+        None    => Err(format!("variável '{}' não definida", n)),
+      },
+
+      Expr::Par(x, y)   => {
+        let x = self.avalia(x)?.real()?;
+        let y = self.avalia(y)?.real()?;
+        Ok(Valor::Par(x, y))
+      },
+
+      Expr::Chamada(nome, args) => {
+        let args = self.avalia_todos(args)?;
+        funcao(nome, &args)
+      },
+
+      Expr::Metodo(receptor, nome, args) => {
+        let (resultado, _) = self.avalia_metodo(receptor, nome, args)?;
+        Ok(resultado)
+      },
+
+      Expr::Colide(a, b) => {
+        let a = self.avalia(a)?.forma()?;
+        let b = self.avalia(b)?.forma()?;
+        Ok(Valor::Logico(a.collide(&b)))
+      },
+
+      Expr::Soma(a, b) => {
+        let a = self.avalia(a)?;
+        let b = self.avalia(b)?;
+        match (a, b) {
+          (Valor::Numero(a), Valor::Numero(b)) => Ok(Valor::Numero(a + b)),
+          (Valor::Par(x1, y1), Valor::Par(x2, y2)) => Ok(Valor::Par(x1 + x2, y1 + y2)),
+          (Valor::Vetor(a), b) => Ok(Valor::Vetor(soma_vetores(a, b.vetor()?)?)),
+          (Valor::Ponto(p), b) => Ok(Valor::Ponto(desloca(p, b.vetor()?)?)),
+          (Valor::Forma(f), b) => Ok(Valor::Forma(desloca_forma(f, b.vetor()?)?)),
+          (a, b) => Err(format!("não é possível somar {} e {}", a.tipo(), b.tipo())),
+        }
+      },
+
+      Expr::Diferenca(a, b) => {
+        let a = self.avalia(a)?;
+        let b = self.avalia(b)?;
+        match (a, b) {
+          (Valor::Numero(a), Valor::Numero(b)) => Ok(Valor::Numero(a - b)),
+          (Valor::Par(x1, y1), Valor::Par(x2, y2)) => Ok(Valor::Par(x1 - x2, y1 - y2)),
+          (Valor::Ponto(p), Valor::Ponto(q)) => Ok(Valor::Vetor(vetor_entre(q, p)?)),
+          (Valor::Vetor(a), b) => Ok(Valor::Vetor(soma_vetores(a, negativo(b.vetor()?)?)?)),
+          (Valor::Ponto(p), b) => Ok(Valor::Ponto(desloca(p, negativo(b.vetor()?)?)?)),
+          (Valor::Forma(f), b) => Ok(Valor::Forma(desloca_forma(f, negativo(b.vetor()?)?)?)),
+          (a, b) => Err(format!("não é possível subtrair {} de {}", b.tipo(), a.tipo())),
+        }
+      },
+
+      Expr::Negativo(a) => match self.avalia(a)? {
+        Valor::Numero(v) => Ok(Valor::Numero(-v)),
+        Valor::Par(x, y) => Ok(Valor::Par(-x, -y)),
+        Valor::Vetor(v)  => Ok(Valor::Vetor(negativo(v)?)),
+        v                => Err(format!("não é possível negar {}", v.tipo())),
+      },
+    }
+  }
+
+
+  /// Avalia um método e retorna junto do resultado a variável
+  /// que ele alterou, que segue sendo alterada pelos métodos
+  /// encadeados em seguida
+  fn avalia_metodo( &mut self, receptor: &Expr, nome: &str, args: &[Expr]) -> Result<(Valor, Option<String>), String> {
+    let (valor, variavel) = match receptor {
+      Expr::Variavel(n)     => (self.avalia(receptor)?, Some(n.clone())),
+      Expr::Metodo(r, m, a) => self.avalia_metodo(r, m, a)?,
+      e                     => (self.avalia(e)?, None),
+    };
+    let args = self.avalia_todos(args)?;
+    let (resultado, alterado) = metodo(valor, nome, &args)?;
+
+    // métodos que alteram o objeto também alteram a variável
+    match (variavel, alterado) {
+      (Some(n), Some(v)) => {
+        self.variaveis.insert(n.clone(), v);
+        Ok((resultado, Some(n)))
+      },
+      _ => Ok((resultado, None)),
+    }
+  }
+
+
+  /// Avalia os argumentos de uma chamada
+  fn avalia_todos( &mut self, args: &[Expr]) -> Result<Vec<Valor>, String> {
+    args.iter().map(|a| self.avalia(a)).collect()
+  }
+}
+
+
+/// Confere a quantidade de argumentos de uma chamada
+fn aridade( nome: &str, args: &[Valor], n: usize) -> Result<(), String> {
+  if args.len() != n {
+    return Err(format!("'{}' espera {} argumento(s), recebeu {}", nome, n, args.len()));
+  }
+
+  return Ok(());
+}
+
+
+/// Avalia uma função ou construtor
+fn funcao( nome: &str, args: &[Valor]) -> Result<Valor, String> {
+  let valor = match (nome, args) {
+    ("ponto", [p])    => Valor::Ponto(p.ponto()?),
+    ("ponto", [x, y]) => Valor::Ponto(Ponto::new(x.inteiro()?, y.inteiro()?)),
+
+    ("vetor", [v])    => Valor::Vetor(v.vetor()?),
+    ("vetor", [Valor::Numero(_), _]) => {
+      Valor::Vetor(Vetor::new(args[0].inteiro()?, args[1].inteiro()?))
+    },
+    ("vetor", [p1, p2]) => Valor::Vetor(vetor_entre(p1.ponto()?, p2.ponto()?)?),
+
+    ("modulo", [v])       => Valor::Modulo(Modulo::extract(v.vetor()?)),
+    ("modulo", [v1, v2])  => Valor::Modulo(Modulo::extract(soma_vetores(v1.vetor()?, v2.vetor()?)?)),
+
+    ("bloco", _) => {
+      aridade(nome, args, 2)?;
+      Valor::Forma(Forma::Bloco(Bloco::from(args[0].ponto()?, args[1].ponto()?)))
+    },
+
+    ("quad", _) => {
+      aridade(nome, args, 3)?;
+      let tx = args[1].inteiro()?;
+      let ty = args[2].inteiro()?;
+      if tx < 0 || ty < 0 || tx > u16::MAX as i32 || ty > u16::MAX as i32 {
+        return Err(format!("dimensões inválidas: {} x {}", tx, ty));
+      }
+      Valor::Forma(Forma::Quadrilatero(Quadrilatero::from(args[0].ponto()?, tx as u16, ty as u16)))
+    },
+
+    ("circulo", _) => {
+      aridade(nome, args, 2)?;
+      let r = args[1].real()?;
+      if r < 0.0 {
+        return Err(format!("raio negativo: {}", r));
+      }
+      Valor::Forma(Forma::Circulo(Circulo::from(args[0].ponto()?, r)))
+    },
+
+    ("orientado", _) => {
+      aridade(nome, args, 4)?;
+      let hx = args[1].real()?;
+      let hy = args[2].real()?;
+      if hx < 0.0 || hy < 0.0 {
+        return Err(format!("dimensões negativas: {} x {}", hx, hy));
+      }
+      let â = args[3].real()? as f32;
+      Valor::Forma(Forma::Orientado(QuadOrientado::new(args[0].coordenadas()?, hx, hy, â)))
+    },
+
+    ("segmento", _) => {
+      aridade(nome, args, 2)?;
+      Valor::Forma(Forma::Segmento(Segmento::from(args[0].ponto()?, args[1].ponto()?)))
+    },
+
+    ("poligono", _) => {
+      if args.len() < 3 {
+        return Err(format!("o polígono precisa de ao menos 3 pontos, recebeu {}", args.len()));
+      }
+      let pontos = args.iter().map(Valor::ponto).collect::<Result<Vec<Ponto>, String>>()?;
+      Valor::Forma(Forma::Poligono(Poligono::new(pontos)))
+    },
+
+    ("distancia", [Valor::Ponto(p), Valor::Ponto(q)]) => Valor::Numero(Ponto::diff(*p, *q)),
+    ("distancia", _) => {
+      aridade(nome, args, 2)?;
+      Valor::Numero(args[0].forma()?.distance(&args[1].forma()?))
+    },
+
+    ("area", _) => {
+      aridade(nome, args, 1)?;
+      Valor::Numero(args[0].forma()?.area())
+    },
+
+    ("angulo", [v]) => {
+      let v = v.vetor()?;
+      nao_nulo(v)?;
+      Valor::Numero(v.angle() as f64)
+    },
+    ("angulo", [v, u]) => {
+      let (v, u) = (v.vetor()?, u.vetor()?);
+      nao_nulo(v)?;
+      nao_nulo(u)?;
+      Valor::Numero(inner_angle(v, u) as f64)
+    },
+
+    ("colide", _) => {
+      aridade(nome, args, 2)?;
+      Valor::Logico(args[0].forma()?.collide(&args[1].forma()?))
+    },
+
+    ("ponto", _) | ("vetor", _) | ("modulo", _) | ("angulo", _) => {
+      return Err(format!("'{}' espera 1 ou 2 argumentos, recebeu {}", nome, args.len()));
+    },
+
+    _ => return Err(format!("função desconhecida '{}'", nome)),
+  };
+
+  return Ok(valor);
+}
+
+
+/// Avalia um método, retornando o resultado e o novo valor do
+/// receptor quando o método o altera
+fn metodo( receptor: Valor, nome: &str, args: &[Valor]) -> Result<(Valor, Option<Valor>), String> {
+  let resultado = match (&receptor, nome) {
+    (Valor::Ponto(p), "sum") => {
+      aridade(nome, args, 1)?;
+      Valor::Ponto(desloca(*p, args[0].vetor()?)?)
+    },
+    (Valor::Ponto(p), "mov") => {
+      aridade(nome, args, 1)?;
+      let p = desloca(*p, args[0].vetor()?)?;
+      return Ok((Valor::Ponto(p), Some(Valor::Ponto(p))));
+    },
+    (Valor::Ponto(p), "diff") => {
+      aridade(nome, args, 1)?;
+      Valor::Numero(Ponto::diff(*p, args[0].ponto()?))
+    },
+
+    (Valor::Vetor(v), "module") => {
+      aridade(nome, args, 0)?;
+      Valor::Numero(v.module())
+    },
+    (Valor::Vetor(v), "angle") => {
+      aridade(nome, args, 0)?;
+      nao_nulo(*v)?;
+      Valor::Numero(v.angle() as f64)
+    },
+    (Valor::Vetor(v), "neg") => {
+      aridade(nome, args, 0)?;
+      Valor::Vetor(negativo(*v)?)
+    },
+    (Valor::Vetor(v), "sum") | (Valor::Vetor(v), "mov") => {
+      aridade(nome, args, 1)?;
+      let v = soma_vetores(*v, args[0].vetor()?)?;
+      return Ok((Valor::Vetor(v), Some(Valor::Vetor(v))));
+    },
+
+    (Valor::Forma(f), "mov") => {
+      aridade(nome, args, 1)?;
+      let f = desloca_forma(f.clone(), args[0].vetor()?)?;
+      return Ok((Valor::Forma(f.clone()), Some(Valor::Forma(f))));
+    },
+    (Valor::Forma(f), "sum") => {
+      aridade(nome, args, 1)?;
+      Valor::Forma(desloca_forma(f.clone(), args[0].vetor()?)?)
+    },
+
+    (_, "area") => {
+      aridade(nome, args, 0)?;
+      Valor::Numero(receptor.forma()?.area())
+    },
+    (_, "contains") => {
+      aridade(nome, args, 1)?;
+      Valor::Logico(receptor.forma()?.contains(args[0].coordenadas()?))
+    },
+    (_, "collide") => {
+      aridade(nome, args, 1)?;
+      Valor::Logico(receptor.forma()?.collide(&args[0].forma()?))
+    },
+    (_, "distance") => {
+      aridade(nome, args, 1)?;
+      Valor::Numero(receptor.forma()?.distance(&args[0].forma()?))
+    },
+
+    (v, _) => return Err(format!("{} não possui o método '{}'", v.tipo(), nome)),
+  };
+
+  return Ok((resultado, None));
+}
+
+
+/// Garante que o vetor possui ângulo
+fn nao_nulo( v: Vetor) -> Result<(), String> {
+  if v.x == 0 && v.y == 0 {
+    return Err("vetores nulos não possuem ângulo".to_string());
+  }
+
+  return Ok(());
+}
+
+
+/// Soma coordenadas inteiras, resultados fora de i32 são erros
+/// de avaliação em vez de estouros
+fn soma_inteira( a: i32, b: i64) -> Result<i32, String> {
+  match i32::try_from(a as i64 + b) {
+    Ok(v)  => Ok(v),
+    Err(_) => Err(format!("estouro: {} + {} não cabe em um inteiro de 32 bits", a, b)),
+  }
+}
+
+
+/// Soma um vetor a um ponto
+fn desloca( p: Ponto, v: Vetor) -> Result<Ponto, String> {
+  Ok(Ponto::new(soma_inteira(p.x, v.x as i64)?, soma_inteira(p.y, v.y as i64)?))
+}
+
+
+/// Soma dois vetores
+fn soma_vetores( a: Vetor, b: Vetor) -> Result<Vetor, String> {
+  Ok(Vetor::new(soma_inteira(a.x, b.x as i64)?, soma_inteira(a.y, b.y as i64)?))
+}
+
+
+/// Vetor que leva de um ponto a outro
+fn vetor_entre( p1: Ponto, p2: Ponto) -> Result<Vetor, String> {
+  Ok(Vetor::new(soma_inteira(p2.x, -(p1.x as i64))?, soma_inteira(p2.y, -(p1.y as i64))?))
+}
+
+
+/// Nega um vetor, -2147483648 não possui negativo em i32
+fn negativo( v: Vetor) -> Result<Vetor, String> {
+  vetor_entre(Ponto::new(v.x, v.y), Ponto::new(0, 0))
+}
+
+
+/// Move uma forma, conferindo antes todos os pontos inteiros
+fn desloca_forma( f: Forma, v: Vetor) -> Result<Forma, String> {
+  match &f {
+    Forma::Bloco(b)        => [b.p1, b.p2].iter().try_for_each(|p| desloca(*p, v).map(|_| ()))?,
+    Forma::Quadrilatero(q) => desloca(q.p, v).map(|_| ())?,
+    Forma::Circulo(c)      => desloca(c.p, v).map(|_| ())?,
+    Forma::Segmento(s)     => [s.p1, s.p2].iter().try_for_each(|p| desloca(*p, v).map(|_| ()))?,
+    Forma::Poligono(p)     => p.pontos.iter().try_for_each(|p| desloca(*p, v).map(|_| ()))?,
+    Forma::Orientado(_)    => (),
+  }
+
+  return Ok(f.sum(v));
+}
+
+
+/// Separa uma linha em tokens
+fn tokens( linha: &str) -> Result<Vec<Token>, String> {
+  let chars: Vec<char> = linha.chars().collect();
+  let mut tokens = Vec::new();
+  let mut i = 0;
+
+  while i < chars.len() {
+    let c = chars[i];
+    if c.is_whitespace() {
+      i += 1;
+    } else if c.is_ascii_digit() {
+      let inicio = i;
+      while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+        i += 1;
+      }
+      let texto: String = chars[inicio..i].iter().collect();
+      let v = texto.parse::<f64>().map_err(|_| format!("número inválido '{}'", texto))?;
+      tokens.push(Token::Numero(v));
+    } else if c.is_alphabetic() || c == '_' {
+      let inicio = i;
+      while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+        i += 1;
+      }
+      tokens.push(Token::Nome(chars[inicio..i].iter().collect()));
+    } else if "(),.=+-".contains(c) {
+      tokens.push(Token::Simbolo(c));
+      i += 1;
+    } else {
+      return Err(format!("caractere inesperado '{}'", c));
+    }
+  }
+
+  return Ok(tokens);
+}
+
+
+// Métodos relacionados ao leitor
+impl Leitor {
+
+
+  /// Gera um leitor no início dos tokens
+  fn new( tokens: Vec<Token>) -> Self {
+    Self {
+      tokens,
+      pos: 0,
+      profundidade: 0
+    }
+  }
+
+
+  /// Lê uma linha inteira como uma expressão
+  fn linha( &mut self ) -> Result<Expr, String> {
+    let e = self.expressao()?;
+    match self.tokens.get(self.pos) {
+      None    => Ok(e),
+      Some(t) => Err(format!("inesperado {}", descreve(t))),
+    }
+  }
+
+
+  /// Consome o símbolo se for o próximo
+  fn aceita( &mut self, c: char) -> bool {
+    if self.tokens.get(self.pos) == Some(&Token::Simbolo(c)) {
+      self.pos += 1;
+      return true;
+    }
+
+    return false;
+  }
+
+
+  /// Consome o símbolo ou retorna um erro
+  fn espera( &mut self, c: char) -> Result<(), String> {
+    if self.aceita(c) {
+      return Ok(());
+    }
+
+    match self.tokens.get(self.pos) {
+      Some(t) => Err(format!("esperava '{}', encontrou {}", c, descreve(t))),
+      None    => Err(format!("esperava '{}' no fim da linha", c)),
+    }
+  }
+
+
+  /// Desce um nível na expressão, falha além do limite
+  fn desce( &mut self ) -> Result<(), String> {
+    self.profundidade += 1;
+    if self.profundidade > PROFUNDIDADE_MAXIMA {
+      return Err(format!("expressão com mais de {} níveis", PROFUNDIDADE_MAXIMA));
+    }
+
+    return Ok(());
+  }
+
+
+  /// expressao := soma ('collides' soma)?
+  fn expressao( &mut self ) -> Result<Expr, String> {
+    let a = self.soma()?;
+    if let Some(Token::Nome(n)) = self.tokens.get(self.pos) {
+      if n == COLIDE {
+        self.pos += 1;
+        let b = self.soma()?;
+        return Ok(Expr::Colide(Box::new(a), Box::new(b)));
+      }
+    }
+
+    return Ok(a);
+  }
+
+
+  /// soma := unario (('+' | '-') unario)*
+  fn soma( &mut self ) -> Result<Expr, String> {
+    // cada operador aprofunda a árvore à esquerda
+    let nivel = self.profundidade;
+    let mut a = self.unario()?;
+    loop {
+      if self.aceita('+') {
+        self.desce()?;
+        a = Expr::Soma(Box::new(a), Box::new(self.unario()?));
+      } else if self.aceita('-') {
+        self.desce()?;
+        a = Expr::Diferenca(Box::new(a), Box::new(self.unario()?));
+      } else {
+        self.profundidade = nivel;
+        return Ok(a);
+      }
+    }
+  }
+
+
+  /// unario := '-' unario | posfixo
+  fn unario( &mut self ) -> Result<Expr, String> {
+    self.desce()?;
+    let e = if self.aceita('-') {
+      match self.unario()? {
+        Expr::Numero(v) => Expr::Numero(-v),
+        e               => Expr::Negativo(Box::new(e)),
+      }
+    } else {
+      self.posfixo()?
+    };
+    self.profundidade -= 1;
+
+    return Ok(e);
+  }
+
+
+  /// posfixo := primario ('.' nome '(' argumentos ')')*
+  fn posfixo( &mut self ) -> Result<Expr, String> {
+    let nivel = self.profundidade;
+    let mut e = self.primario()?;
+    while self.aceita('.') {
+      self.desce()?;
+      let nome = match self.tokens.get(self.pos) {
+        Some(Token::Nome(n)) => n.clone(),
+        _                    => return Err("esperava o nome de um método após '.'".to_string()),
+      };
+      self.pos += 1;
+      self.espera('(')?;
+      let args = self.argumentos()?;
+      e = Expr::Metodo(Box::new(e), nome, args);
+    }
+    self.profundidade = nivel;
+
+    return Ok(e);
+  }
+
+
+  /// primario := numero | nome '(' argumentos ')' | nome | '(' expressao (',' expressao)? ')'
+  fn primario( &mut self ) -> Result<Expr, String> {
+    let t = match self.tokens.get(self.pos) {
+      Some(t) => t.clone(),
+      None    => return Err("expressão incompleta".to_string()),
+    };
+    self.pos += 1;
+
+    match t {
+      Token::Numero(v) => Ok(Expr::Numero(v)),
+
+      Token::Nome(n) if n == COLIDE => Err(format!("'{}' precisa de um objeto à esquerda", COLIDE)),
+
+      Token::Nome(n) => {
+        if self.aceita('(') {
+          return Ok(Expr::Chamada(n, self.argumentos()?));
+        }
+        Ok(Expr::Variavel(n))
+      },
+
+      Token::Simbolo('(') => {
+        let a = self.expressao()?;
+        if self.aceita(',') {
+          let b = self.expressao()?;
+          self.espera(')')?;
+          return Ok(Expr::Par(Box::new(a), Box::new(b)));
+        }
+        self.espera(')')?;
+        Ok(a)
+      },
+
+      t => Err(format!("inesperado {}", descreve(&t))),
+    }
+  }
+
+
+  /// Lê argumentos separados por vírgula até ')', já após '('
+  fn argumentos( &mut self ) -> Result<Vec<Expr>, String> {
+    let mut args = Vec::new();
+    if self.aceita(')') {
+      return Ok(args);
+    }
+
+    loop {
+      args.push(self.expressao()?);
+      if self.aceita(')') {
+        return Ok(args);
+      }
+      self.espera(',')?;
+    }
+  }
+}
+
+
+/// Descreve um token para as mensagens de erro
+fn descreve( t: &Token) -> String {
+  match t {
+    Token::Numero(v)  => format!("o número {}", v),
+    Token::Nome(n)    => format!("'{}'", n),
+    Token::Simbolo(c) => format!("'{}'", c),
+  }
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+
+
+  /// Lê uma linha sem avaliar
+  fn le( linha: &str) -> Result<Expr, String> {
+    Leitor::new(tokens(linha)?).linha()
+  }
+
+
+  /// Avalia uma linha e retorna o texto exibido
+  fn avalia( repl: &mut Repl, linha: &str) -> Result<String, String> {
+    repl.avaliar(linha).map(|r| r.unwrap_or_default())
+  }
+
+
+  fn ponto( repl: &Repl, nome: &str) -> Ponto {
+    match repl.get(nome) {
+      Some(Valor::Ponto(p)) => *p,
+      v                     => panic!("{} não é um ponto: {:?}", nome, v),
+    }
+  }
+
+
+  #[test]
+  fn precedencia_e_associatividade() {
+    let mut repl = Repl::new();
+    assert_eq!(avalia(&mut repl, "1 - 2 - 3").unwrap(), "-4");
+    assert_eq!(avalia(&mut repl, "1 - (2 - 3)").unwrap(), "2");
+    assert_eq!(avalia(&mut repl, "-(1 + 2) + 4").unwrap(), "1");
+    assert_eq!(avalia(&mut repl, "--2").unwrap(), "2");
+    assert_eq!(avalia(&mut repl, "(1, 2) + (3, -4)").unwrap(), "(4, -2)");
+    assert_eq!(avalia(&mut repl, "vetor(3, 4).module()").unwrap(), "5");
+    assert_eq!(avalia(&mut repl, "bloco((0, 0), (2, 2)) collides ponto(1, 1) + vetor(5, 5)").unwrap(), "false");
+    assert_eq!(avalia(&mut repl, "bloco((0, 0), (2, 2)) collides ponto(1, 1)").unwrap(), "true");
+
+    assert!(matches!(le("a + b collides c"), Ok(Expr::Colide(a, _)) if matches!(*a, Expr::Soma(..))));
+    assert!(matches!(le("-a.neg()"), Ok(Expr::Negativo(a)) if matches!(*a, Expr::Metodo(..))));
+    assert!(matches!(le("-3"), Ok(Expr::Numero(v)) if v == -3.0));
+  }
+
+
+  #[test]
+  fn erros_de_sintaxe() {
+    let casos = [
+      ("(1, 2", "esperava ')' no fim da linha"),
+      ("ponto(1 2)", "esperava ',', encontrou o número 2"),
+      ("1 +", "expressão incompleta"),
+      ("1 2", "inesperado o número 2"),
+      ("collides b", "'collides' precisa de um objeto à esquerda"),
+      ("a.(1)", "esperava o nome de um método após '.'"),
+      ("a.mov", "esperava '(' no fim da linha"),
+      (")", "inesperado ')'"),
+      ("a = ", "expressão incompleta"),
+      ("1 $ 2", "caractere inesperado '$'"),
+      ("1.2.3", "número inválido '1.2.3'"),
+    ];
+    for (linha, erro) in casos.iter() {
+      assert_eq!(Repl::new().avaliar(linha), Err(erro.to_string()), "{}", linha);
+    }
+  }
+
+
+  #[test]
+  fn profundidade_limitada() {
+    let mut repl = Repl::new();
+    let n = PROFUNDIDADE_MAXIMA / 2;
+    let raso = format!("{}1{}", "(".repeat(n), ")".repeat(n));
+    assert_eq!(avalia(&mut repl, &raso).unwrap(), "1");
+
+    // linhas profundas falham sem esgotar a pilha
+    let erro = Err(format!("expressão com mais de {} níveis", PROFUNDIDADE_MAXIMA));
+    let profundas = [
+      format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000)),
+      format!("{}1", "-".repeat(100_000)),
+      format!("1{}", " + 1".repeat(100_000)),
+      format!("ponto(0, 0){}", ".sum((1, 1))".repeat(100_000)),
+      format!("{}1{}", "ponto(".repeat(100_000), ", 1)".repeat(100_000)),
+      "(".repeat(100_000),
+    ];
+    for linha in profundas.iter() {
+      assert_eq!(repl.avaliar(linha), erro);
+    }
+  }
+
+
+  #[test]
+  fn metodos_encadeados_alteram_a_variavel_a_cada_passo() {
+    let mut repl = Repl::new();
+    avalia(&mut repl, "p = ponto(0, 0)").unwrap();
+    avalia(&mut repl, "v = vetor(1, 2)").unwrap();
+
+    avalia(&mut repl, "p.mov(v).mov(v).mov(v)").unwrap();
+    assert_eq!(ponto(&repl, "p"), Ponto::new(3, 6));
+
+    // métodos que não alteram interrompem a cadeia
+    avalia(&mut repl, "p.sum(v).mov(v)").unwrap();
+    assert_eq!(ponto(&repl, "p"), Ponto::new(3, 6));
+
+    // e a atribuição recebe o resultado do último passo
+    avalia(&mut repl, "q = p.mov(v).mov(v)").unwrap();
+    assert_eq!(ponto(&repl, "p"), Ponto::new(5, 10));
+    assert_eq!(ponto(&repl, "q"), Ponto::new(5, 10));
+
+    avalia(&mut repl, "b = bloco((0, 0), (1, 1))").unwrap();
+    avalia(&mut repl, "b.mov(v).mov(v)").unwrap();
+    assert_eq!(avalia(&mut repl, "b collides ponto(2, 4)").unwrap(), "true");
+    assert_eq!(avalia(&mut repl, "b collides ponto(0, 0)").unwrap(), "false");
+
+    // um passo que falha mantém os anteriores
+    assert!(avalia(&mut repl, "p.mov(v).angle()").is_err());
+    assert_eq!(ponto(&repl, "p"), Ponto::new(6, 12));
+  }
+
+
+  #[test]
+  fn estouros_sao_erros_de_avaliacao() {
+    let mut repl = Repl::new();
+    avalia(&mut repl, "p = ponto(2147483647, -2147483648)").unwrap();
+    avalia(&mut repl, "b = bloco((0, 0), (2147483647, 1))").unwrap();
+    avalia(&mut repl, "v = vetor(1, 0)").unwrap();
+
+    let linhas = [
+      "ponto(2147483647, 0) - ponto(-2147483648, 0)",
+      "vetor(ponto(2147483647, 0), ponto(-2147483648, 0))",
+      "p + v",
+      "p - vetor(0, 1)",
+      "p.sum(v)",
+      "p.mov(v)",
+      "-vetor(-2147483648, 0)",
+      "vetor(0, -2147483648).neg()",
+      "vetor(2147483647, 0) + v",
+      "vetor(-2147483648, 0) - v",
+      "vetor(2147483647, 0).mov(v)",
+      "modulo(vetor(2147483647, 0), v)",
+      "b + v",
+      "b.mov(v)",
+      "circulo((-2147483648, 0), 1) - v",
+      "segmento((0, 0), (2147483647, 0)).sum(v)",
+      "poligono((0, 0), (1, 0), (2147483647, 1)) + v",
+    ];
+    for linha in linhas.iter() {
+      let erro = avalia(&mut repl, linha).unwrap_err();
+      assert!(erro.starts_with("estouro: "), "{}: {}", linha, erro);
+    }
+
+    // as variáveis não mudam quando o método falha
+    assert_eq!(ponto(&repl, "p"), Ponto::new(2147483647, -2147483648));
+    assert_eq!(avalia(&mut repl, "b collides ponto(2147483646, 0)").unwrap(), "true");
+
+    // resultados no limite e medidas entre pontos distantes valem
+    assert_eq!(avalia(&mut repl, "p - v + v"), avalia(&mut repl, "p"));
+    assert_eq!(avalia(&mut repl, "ponto(2147483647, 0).diff(ponto(-2147483648, 0))").unwrap(), "4294967295");
+    assert_eq!(avalia(&mut repl, "distancia(ponto(0, 0), ponto(0, -2147483648))").unwrap(), "2147483648");
+    assert_eq!(avalia(&mut repl, "vetor(60000, 80000).module()").unwrap(), "100000");
+    assert_eq!(avalia(&mut repl, "orientado((2147483647, 0), 1, 1, 0) + v collides ponto(2147483646, 0)").unwrap(), "false");
+  }
+}
@@ -0,0 +1,604 @@
+/// Servidor JSON-RPC 2.0 sobre a entrada e a saída padrão.
+///
+/// Cada linha da entrada é uma requisição, ou uma lista delas,
+/// e cada resposta é escrita em uma única linha. Notificações,
+/// requisições sem `id`, não recebem resposta. O estado é uma
+/// cena mantida entre as chamadas.
+///
+/// Objetos são escritos como `{"tipo": "bloco", "valores":
+/// [1, 1, 3, 3]}`, com os mesmos valores das linhas de uma cena,
+/// e podem ser trocados pelo nome de um objeto da cena. Pontos
+/// dos parâmetros são listas `[x, y]`.
+///
+/// ```text
+/// --> {"jsonrpc": "2.0", "id": 1, "method": "create", "params": {"nome": "b1", "forma": {"tipo": "bloco", "valores": [1, 1, 3, 3]}}}
+/// <-- {"jsonrpc":"2.0","id":1,"result":{"nome":"b1","tipo":"bloco","valores":[1,1,3,3]}}
+/// --> {"jsonrpc": "2.0", "id": 2, "method": "collide", "params": {"a": "b1", "b": {"tipo": "circulo", "valores": [4, 4, 2]}}}
+/// <-- {"jsonrpc":"2.0","id":2,"result":true}
+/// ```
+///
+/// Métodos: `create`, `remove`, `get`, `list`, `clear`,
+/// `collide`, `distance`, `area`, `transform`, `query.point`,
+/// `query.region`, `query.nearest`, `query.collisions` e
+/// `shutdown`.
+pub use jeotry::object_data::{
+    Segmento as Segmento,
+    Forma as Forma,
+};
+use jeotry::scene_data::{
+    Cena,
+    Elemento,
+    le_elemento,
+};
+use jeotry::transform_data::Transformacao;
+use jeotry::batch_data::{
+    self,
+    Registro,
+};
+use super::json_data::Json;
+use std::io::{self, BufRead, Write};
+
+
+/// Texto inválido como JSON
+pub static ERRO_LEITURA: i64 = -32700;
+
+/// Requisição sem a estrutura do JSON-RPC
+pub static REQUISICAO_INVALIDA: i64 = -32600;
+
+/// Método desconhecido
+pub static METODO_DESCONHECIDO: i64 = -32601;
+
+/// Parâmetros ausentes ou inválidos
+pub static PARAMETROS_INVALIDOS: i64 = -32602;
+
+/// Nome que não existe na cena
+pub static NAO_ENCONTRADO: i64 = -32001;
+
+
+// Estruturas
+/// Erro de uma chamada, escrito no campo `error` da resposta
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErroRpc {
+  /// Código do erro, com os valores do JSON-RPC
+  pub codigo: i64,
+
+  /// Descrição do erro
+  pub mensagem: String
+}
+
+
+/// Estado do servidor
+#[derive(Debug, Clone, Default)]
+pub struct Servidor {
+  /// Objetos nomeados mantidos entre as chamadas
+  pub cena: Cena,
+
+  /// Indica que `shutdown` foi chamado
+  encerrado: bool
+}
+
+
+// Métodos relacionados aos erros
+impl ErroRpc {
+
+
+  /// Gera um novo erro
+  pub fn new( codigo: i64, mensagem: String) -> Self {
+    Self {
+      codigo,
+      mensagem
+    }
+  }
+
+
+  /// Gera um erro de parâmetros inválidos
+  fn parametro( mensagem: String) -> Self {
+    ErroRpc::new(PARAMETROS_INVALIDOS, mensagem)
+  }
+
+
+  /// Objeto do campo `error`
+  fn json( &self ) -> Json {
+    Json::objeto(vec![
+      ("code", Json::Numero(self.codigo as f64)),
+      ("message", Json::from(self.mensagem.as_str())),
+    ])
+  }
+}
+
+
+// Métodos relacionados ao servidor
+impl Servidor {
+
+
+  /// Gera um servidor com a cena vazia
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+
+  /// Gera um servidor a partir de uma cena
+  pub fn from_cena( cena: Cena) -> Self {
+    Self {
+      cena,
+      encerrado: false
+    }
+  }
+
+
+  /// Lê e responde linhas até o fim da entrada ou `shutdown`
+  pub fn executa<R: BufRead, W: Write>( &mut self, entrada: R, mut saida: W) -> io::Result<()> {
+    for linha in entrada.lines() {
+      let linha = linha?;
+      if let Some(resposta) = self.responde(&linha) {
+        writeln!(saida, "{}", resposta)?;
+        saida.flush()?;
+      }
+
+      if self.encerrado {
+        break;
+      }
+    }
+
+    return Ok(());
+  }
+
+
+  /// Responde a uma linha, `None` para notificações e linhas vazias
+  pub fn responde( &mut self, linha: &str) -> Option<String> {
+    if linha.trim().is_empty() {
+      return None;
+    }
+
+    let requisicao = match linha.parse::<Json>() {
+      Ok(r)  => r,
+      Err(e) => {
+        let erro = ErroRpc::new(ERRO_LEITURA, format!("JSON inválido, {}", e));
+        return Some(resposta(Json::Nulo, Err(erro)).to_string());
+      },
+    };
+
+    // lote de requisições
+    if let Json::Lista(requisicoes) = requisicao {
+      if requisicoes.is_empty() {
+        let erro = ErroRpc::new(REQUISICAO_INVALIDA, "lote vazio".to_string());
+        return Some(resposta(Json::Nulo, Err(erro)).to_string());
+      }
+
+      let respostas: Vec<Json> = requisicoes.iter().filter_map(|r| self.requisicao(r)).collect();
+      if respostas.is_empty() {
+        return None;
+      }
+      return Some(Json::Lista(respostas).to_string());
+    }
+
+    return self.requisicao(&requisicao).map(|r| r.to_string());
+  }
+
+
+  /// Responde a uma requisição, `None` para notificações
+  fn requisicao( &mut self, r: &Json) -> Option<Json> {
+    let id = r.get("id").cloned();
+
+    if r.get("jsonrpc").and_then(Json::texto) != Some("2.0") {
+      let erro = ErroRpc::new(REQUISICAO_INVALIDA, "'jsonrpc' deve ser \"2.0\"".to_string());
+      return Some(resposta(id.unwrap_or(Json::Nulo), Err(erro)));
+    }
+
+    let metodo = match r.get("method").and_then(Json::texto) {
+      Some(m) => m,
+      None    => {
+        let erro = ErroRpc::new(REQUISICAO_INVALIDA, "'method' deve ser um texto".to_string());
+        return Some(resposta(id.unwrap_or(Json::Nulo), Err(erro)));
+      },
+    };
+
+    let vazio = Json::Objeto(Vec::new());
+    let resultado = match r.get("params") {
+      None | Some(Json::Nulo)    => self.chama(metodo, &vazio),
+      Some(p @ Json::Objeto(_))  => self.chama(metodo, p),
+      Some(p)                    => {
+        Err(ErroRpc::parametro(format!("'params' deve ser um objeto, recebeu {}", p.tipo())))
+      },
+    };
+
+    return id.map(|id| resposta(id, resultado));
+  }
+
+
+  /// Executa um método
+  fn chama( &mut self, metodo: &str, params: &Json) -> Result<Json, ErroRpc> {
+    let resultado = match metodo {
+      "create" => {
+        let nome = texto(params, "nome")?;
+        let e = elemento(obrigatorio(params, "forma")?, "forma")?;
+        self.guarda(nome, e)?
+      },
+
+      "remove" => {
+        let nome = texto(params, "nome")?;
+        match self.cena.remover(nome) {
+          Some(e) => elemento_json(Some(nome), &e),
+          None    => return Err(nao_encontrado(nome)),
+        }
+      },
+
+      "get" => {
+        let nome = texto(params, "nome")?;
+        match self.cena.get(nome) {
+          Some(e) => elemento_json(Some(nome), e),
+          None    => return Err(nao_encontrado(nome)),
+        }
+      },
+
+      "list" => {
+        let tipo = match params.get("tipo") {
+          None    => None,
+          Some(_) => Some(texto(params, "tipo")?),
+        };
+
+        Json::Lista(self.cena.iter()
+          .filter(|(_, e)| tipo.is_none_or(|t| e.tipo() == t))
+          .map(|(nome, e)| elemento_json(Some(nome), e))
+          .collect())
+      },
+
+      "clear" => {
+        let n = self.cena.len();
+        self.cena = Cena::new();
+        Json::Numero(n as f64)
+      },
+
+      "collide" => {
+        let a = self.forma(params, "a")?;
+        let b = self.forma(params, "b")?;
+        Json::Logico(a.collide(&b))
+      },
+
+      "distance" => {
+        let a = self.forma(params, "a")?;
+        let b = self.forma(params, "b")?;
+        Json::Numero(a.distance(&b))
+      },
+
+      "area" => Json::Numero(self.forma(params, "a")?.area()),
+
+      "transform" => {
+        let e = self.elemento(params, "a")?;
+        let t = transformacao(obrigatorio(params, "transformacao")?)?;
+        let e = match e {
+          Elemento::Ponto(p) => Elemento::Ponto(t.aplica_ponto(p)),
+          Elemento::Vetor(v) => Elemento::Vetor(t.aplica_vetor(v)),
+          Elemento::Forma(f) => Elemento::Forma(t.aplica(&f)),
+        };
+
+        // o resultado só é guardado com um destino
+        match params.get("destino") {
+          None    => elemento_json(None, &e),
+          Some(_) => {
+            let destino = texto(params, "destino")?;
+            self.guarda(destino, e)?
+          },
+        }
+      },
+
+      "query.point" => {
+        let p = ponto(obrigatorio(params, "ponto")?, "ponto")?;
+        Json::Lista(self.cena.formas()
+          .filter(|(_, f)| f.contains(p))
+          .map(|(nome, _)| Json::from(nome))
+          .collect())
+      },
+
+      "query.region" => {
+        let regiao = self.forma(params, "regiao")?;
+        Json::Lista(self.cena.formas()
+          .filter(|(_, f)| f.collide(&regiao))
+          .map(|(nome, _)| Json::from(nome))
+          .collect())
+      },
+
+      "query.nearest" => {
+        let p = ponto(obrigatorio(params, "ponto")?, "ponto")?;
+        let perto = self.cena.formas()
+          .map(|(nome, f)| (nome, f.distance_f(p)))
+          .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        match perto {
+          Some((nome, d)) => Json::objeto(vec![
+            ("nome", Json::from(nome)),
+            ("distancia", Json::Numero(d)),
+          ]),
+          None            => Json::Nulo,
+        }
+      },
+
+      "query.collisions" => {
+        let registros = self.cena.formas().enumerate().map(|(i, (nome, f))| Ok(Registro {
+          linha: i + 1,
+          id: nome.to_string(),
+          forma: f.clone()
+        }));
+
+        let mut pares = Vec::new();
+        batch_data::colisoes(registros, false, |a, b| {
+          pares.push(Json::Lista(vec![Json::from(a.id.as_str()), Json::from(b.id.as_str())]));
+          Ok(())
+        }).map_err(|e| ErroRpc::new(PARAMETROS_INVALIDOS, e.to_string()))?;
+        Json::Lista(pares)
+      },
+
+      "shutdown" => {
+        self.encerrado = true;
+        Json::Nulo
+      },
+
+      m => return Err(ErroRpc::new(METODO_DESCONHECIDO, format!("método desconhecido '{}'", m))),
+    };
+
+    return Ok(resultado);
+  }
+
+
+  /// Guarda um elemento na cena, com o nome validado como nas
+  /// linhas de uma cena, e retorna o objeto da resposta
+  fn guarda( &mut self, nome: &str, e: Elemento) -> Result<Json, ErroRpc> {
+    let json = elemento_json(Some(nome), &e);
+    self.cena.inserir(nome, e).map_err(ErroRpc::parametro)?;
+    return Ok(json);
+  }
+
+
+  /// Lê um elemento escrito no parâmetro ou pelo nome na cena
+  fn elemento( &self, params: &Json, chave: &str) -> Result<Elemento, ErroRpc> {
+    match obrigatorio(params, chave)? {
+      Json::Texto(nome) => match self.cena.get(nome) {
+        Some(e) => Ok(e.clone()),
+        None    => Err(nao_encontrado(nome)),
+      },
+      j                 => elemento(j, chave),
+    }
+  }
+
+
+  /// Lê uma forma, pontos são tratados como segmentos sem comprimento
+  fn forma( &self, params: &Json, chave: &str) -> Result<Forma, ErroRpc> {
+    match self.elemento(params, chave)? {
+      Elemento::Forma(f) => Ok(f),
+      Elemento::Ponto(p) => Ok(Forma::Segmento(Segmento::from(p, p))),
+      Elemento::Vetor(_) => Err(ErroRpc::parametro(format!("'{}' é um vetor, não uma forma", chave))),
+    }
+  }
+}
+
+
+// Funções
+/// Monta a resposta de uma requisição
+fn resposta( id: Json, resultado: Result<Json, ErroRpc>) -> Json {
+  let (chave, valor) = match resultado {
+    Ok(r)  => ("result", r),
+    Err(e) => ("error", e.json()),
+  };
+
+  return Json::objeto(vec![
+    ("jsonrpc", Json::from("2.0")),
+    ("id", id),
+    (chave, valor),
+  ]);
+}
+
+
+/// Erro de um nome que não existe na cena
+fn nao_encontrado( nome: &str) -> ErroRpc {
+  ErroRpc::new(NAO_ENCONTRADO, format!("'{}' não existe na cena", nome))
+}
+
+
+/// Retorna um parâmetro obrigatório
+fn obrigatorio<'a>( params: &'a Json, chave: &str) -> Result<&'a Json, ErroRpc> {
+  params.get(chave).ok_or_else(|| ErroRpc::parametro(format!("parâmetro '{}' ausente", chave)))
+}
+
+
+/// Retorna um parâmetro de texto obrigatório
+fn texto<'a>( params: &'a Json, chave: &str) -> Result<&'a str, ErroRpc> {
+  let v = obrigatorio(params, chave)?;
+  v.texto().ok_or_else(|| ErroRpc::parametro(format!("'{}' deve ser um texto, recebeu {}", chave, v.tipo())))
+}
+
+
+/// Lê uma lista de números
+fn numeros( j: &Json, chave: &str) -> Result<Vec<f64>, ErroRpc> {
+  let erro = || ErroRpc::parametro(format!("'{}' deve ser uma lista de números", chave));
+  let lista = j.lista().ok_or_else(erro)?;
+  return lista.iter().map(|v| v.numero().ok_or_else(erro)).collect();
+}
+
+
+/// Lê um ponto escrito como `[x, y]`
+fn ponto( j: &Json, chave: &str) -> Result<(f64, f64), ErroRpc> {
+  match numeros(j, chave)?.as_slice() {
+    [x, y] => Ok((*x, *y)),
+    _      => Err(ErroRpc::parametro(format!("'{}' deve ser um ponto [x, y]", chave))),
+  }
+}
+
+
+/// Lê um elemento escrito como `{"tipo": ..., "valores": [...]}`
+fn elemento( j: &Json, chave: &str) -> Result<Elemento, ErroRpc> {
+  let tipo = texto(j, "tipo").map_err(|e| ErroRpc::parametro(format!("'{}': {}", chave, e.mensagem)))?;
+  let valores = numeros(obrigatorio(j, "valores")?, "valores")?;
+  let valores: Vec<String> = valores.iter().map(|v| v.to_string()).collect();
+  let valores: Vec<&str> = valores.iter().map(String::as_str).collect();
+
+  return le_elemento(tipo, &valores).map_err(|e| ErroRpc::parametro(format!("'{}': {}", chave, e)));
+}
+
+
+/// Escreve um elemento, com o nome quando estiver na cena
+fn elemento_json( nome: Option<&str>, e: &Elemento) -> Json {
+  let valores = e.valores()
+    .split_whitespace()
+    .map(|v| Json::Numero(v.parse().unwrap_or(f64::NAN)))
+    .collect();
+
+  let mut pares = vec![
+    ("tipo", Json::from(e.tipo())),
+    ("valores", Json::Lista(valores)),
+  ];
+  if let Some(nome) = nome {
+    pares.insert(0, ("nome", Json::from(nome)));
+  }
+
+  return Json::objeto(pares);
+}
+
+
+/// Lê uma transformação, ou uma lista delas aplicadas em ordem.
+///
+/// Cada uma é escrita como `{"tipo": ..., "valores": [...]}`,
+/// com os tipos `translacao` [x, y], `rotacao` [â] ou [â, cx, cy],
+/// `escala` [s] ou [sx, sy], `cisalhamento` [kx, ky] e `matriz`
+/// [a, b, c, d, tx, ty].
+fn transformacao( j: &Json) -> Result<Transformacao, ErroRpc> {
+  if let Some(lista) = j.lista() {
+    return lista.iter().try_fold(Transformacao::identidade(), |t, j| Ok(t.seguida(transformacao(j)?)));
+  }
+
+  let tipo = texto(j, "tipo")?;
+  let valores = numeros(obrigatorio(j, "valores")?, "valores")?;
+  let t = match (tipo, valores.as_slice()) {
+    ("translacao", [x, y])                 => Transformacao::translacao(*x, *y),
+    ("rotacao", [â])                       => Transformacao::rotacao(*â as f32),
+    ("rotacao", [â, cx, cy])               => Transformacao::rotacao_em(*â as f32, (*cx, *cy)),
+    ("escala", [s])                        => Transformacao::escala(*s),
+    ("escala", [sx, sy])                   => Transformacao::escala_xy(*sx, *sy),
+    ("cisalhamento", [kx, ky])             => Transformacao::cisalhamento(*kx, *ky),
+    ("matriz", [a, b, c, d, tx, ty])       => Transformacao::new(*a, *b, *c, *d, *tx, *ty),
+    _ => {
+      let mensagem = format!("transformação '{}' com {} valores não existe", tipo, valores.len());
+      return Err(ErroRpc::parametro(mensagem));
+    },
+  };
+
+  return Ok(t);
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+
+
+  /// Responde a uma linha e lê a resposta
+  fn chama( servidor: &mut Servidor, linha: &str) -> Json {
+    let resposta = servidor.responde(linha).unwrap_or_else(|| panic!("{} sem resposta", linha));
+    return resposta.parse().unwrap();
+  }
+
+
+  /// Código de erro da resposta
+  fn codigo( resposta: &Json) -> Option<f64> {
+    resposta.get("error")?.get("code")?.numero()
+  }
+
+
+  fn requisicao( metodo: &str, params: &str) -> String {
+    format!(r#"{{"jsonrpc": "2.0", "id": 7, "method": "{}", "params": {}}}"#, metodo, params)
+  }
+
+
+  #[test]
+  fn codigos_de_erro() {
+    let mut servidor = Servidor::new();
+    let casos = [
+      ("{\"jsonrpc\": \"2.0\",", ERRO_LEITURA),
+      ("[]", REQUISICAO_INVALIDA),
+      (r#"{"id": 1, "method": "list"}"#, REQUISICAO_INVALIDA),
+      (r#"{"jsonrpc": "1.0", "id": 1, "method": "list"}"#, REQUISICAO_INVALIDA),
+      (r#"{"jsonrpc": "2.0", "id": 1, "method": 3}"#, REQUISICAO_INVALIDA),
+      (r#"{"jsonrpc": "2.0", "id": 1, "method": "voar"}"#, METODO_DESCONHECIDO),
+      (r#"{"jsonrpc": "2.0", "id": 1, "method": "list", "params": [1]}"#, PARAMETROS_INVALIDOS),
+      (&requisicao("get", "{}"), PARAMETROS_INVALIDOS),
+      (&requisicao("get", r#"{"nome": 1}"#), PARAMETROS_INVALIDOS),
+      (&requisicao("get", r#"{"nome": "x"}"#), NAO_ENCONTRADO),
+      (&requisicao("remove", r#"{"nome": "x"}"#), NAO_ENCONTRADO),
+      (&requisicao("area", r#"{"a": "x"}"#), NAO_ENCONTRADO),
+      (&requisicao("area", r#"{"a": {"tipo": "vetor", "valores": [1, 2]}}"#), PARAMETROS_INVALIDOS),
+      (&requisicao("area", r#"{"a": {"tipo": "bloco", "valores": [1, 2]}}"#), PARAMETROS_INVALIDOS),
+      (&requisicao("area", r#"{"a": {"tipo": "bloco", "valores": [1, "2", 3, 4]}}"#), PARAMETROS_INVALIDOS),
+      (&requisicao("query.point", r#"{"ponto": [1, 2, 3]}"#), PARAMETROS_INVALIDOS),
+      (&requisicao("transform", r#"{"a": {"tipo": "ponto", "valores": [1, 2]}, "transformacao": {"tipo": "girar", "valores": []}}"#), PARAMETROS_INVALIDOS),
+    ];
+    for (linha, esperado) in casos.iter() {
+      let resposta = chama(&mut servidor, linha);
+      assert_eq!(codigo(&resposta), Some(*esperado as f64), "{} -> {}", linha, resposta);
+      assert!(resposta.get("result").is_none());
+    }
+
+    // erros de leitura e requisições sem id respondem com id nulo
+    let resposta = chama(&mut servidor, "{");
+    assert_eq!(resposta.get("id"), Some(&Json::Nulo));
+    let resposta = chama(&mut servidor, r#"{"jsonrpc": "2.0", "id": "abc", "method": "voar"}"#);
+    assert_eq!(resposta.get("id"), Some(&Json::from("abc")));
+
+    // identificadores acima de 2^53 voltam com os mesmos dígitos
+    let linha = r#"{"jsonrpc": "2.0", "id": 9007199254740993, "method": "list"}"#;
+    assert_eq!(servidor.responde(linha).unwrap(), r#"{"jsonrpc":"2.0","id":9007199254740993,"result":[]}"#);
+    let linha = r#"[{"jsonrpc": "2.0", "id": 18446744073709551615, "method": "voar"}]"#;
+    assert!(servidor.responde(linha).unwrap().starts_with(r#"[{"jsonrpc":"2.0","id":18446744073709551615,"error":"#));
+  }
+
+
+  #[test]
+  fn criar_e_transformar_validam_o_nome_igualmente() {
+    let mut servidor = Servidor::new();
+    let forma = r#"{"tipo": "bloco", "valores": [1, 1, 3, 3]}"#;
+    let transformacao = r#"{"tipo": "translacao", "valores": [1, 0]}"#;
+
+    for nome in ["", "com espaço", "com#cerquilha", "tab\\t"].iter() {
+      let criar = chama(&mut servidor, &requisicao("create", &format!(r#"{{"nome": "{}", "forma": {}}}"#, nome, forma)));
+      let transformar = chama(&mut servidor, &requisicao("transform", &format!(
+        r#"{{"a": {}, "transformacao": {}, "destino": "{}"}}"#, forma, transformacao, nome
+      )));
+      assert_eq!(codigo(&criar), Some(PARAMETROS_INVALIDOS as f64), "{}", nome);
+      assert_eq!(criar.get("error"), transformar.get("error"), "{}", nome);
+    }
+    assert_eq!(servidor.cena.len(), 0);
+
+    let criar = chama(&mut servidor, &requisicao("create", &format!(r#"{{"nome": "b", "forma": {}}}"#, forma)));
+    assert_eq!(criar.get("result").unwrap().to_string(), r#"{"nome":"b","tipo":"bloco","valores":[1,1,3,3]}"#);
+    let transformar = chama(&mut servidor, &requisicao("transform", &format!(
+      r#"{{"a": "b", "transformacao": {}, "destino": "c"}}"#, transformacao
+    )));
+    assert_eq!(transformar.get("result").unwrap().to_string(), r#"{"nome":"c","tipo":"bloco","valores":[2,1,4,3]}"#);
+    assert_eq!(servidor.cena.len(), 2);
+  }
+
+
+  #[test]
+  fn notificacoes_e_lotes() {
+    let mut servidor = Servidor::new();
+    let criar = r#"{"jsonrpc": "2.0", "method": "create", "params": {"nome": "p", "forma": {"tipo": "ponto", "valores": [1, 2]}}}"#;
+    assert_eq!(servidor.responde(criar), None);
+    assert_eq!(servidor.responde("   "), None);
+    assert_eq!(servidor.cena.len(), 1);
+
+    // notificações com erro também não respondem
+    assert_eq!(servidor.responde(r#"{"jsonrpc": "2.0", "method": "voar"}"#), None);
+
+    let lote = format!("[{}, {}, {}]",
+      requisicao("get", r#"{"nome": "p"}"#),
+      r#"{"jsonrpc": "2.0", "method": "list"}"#,
+      r#"{"jsonrpc": "2.0", "id": 8, "method": "get", "params": {"nome": "q"}}"#,
+    );
+    let respostas = chama(&mut servidor, &lote);
+    let respostas = respostas.lista().unwrap();
+    assert_eq!(respostas.len(), 2);
+    assert_eq!(respostas[0].get("result").unwrap().to_string(), r#"{"nome":"p","tipo":"ponto","valores":[1,2]}"#);
+    assert_eq!(codigo(&respostas[1]), Some(NAO_ENCONTRADO as f64));
+
+    let apenas_notificacoes = format!("[{}]", r#"{"jsonrpc": "2.0", "method": "list"}"#);
+    assert_eq!(servidor.responde(&apenas_notificacoes), None);
+  }
+}
@@ -0,0 +1,558 @@
+/// Cenas: coleções de objetos e vetores nomeados, que podem
+/// ser lidas e escritas em um formato de texto simples.
+///
+/// Cada linha do arquivo descreve um elemento, com o tipo, o
+/// nome e os valores separados por espaços. Linhas vazias e
+/// tudo após um `#` são ignorados.
+///
+/// ```text
+/// # tipo      nome  valores
+/// ponto       p1    2 4
+/// vetor       v1    2 4
+/// bloco       b1    1 1 3 3          # x1 y1 x2 y2
+/// quad        q1    1 2 4 1          # x y tx ty
+/// circulo     c1    3 3 1.5          # x y r
+/// orientado   o1    3 3 1 0.5 0.785  # cx cy hx hy ângulo
+/// segmento    s1    0 0 4 4          # x1 y1 x2 y2
+/// poligono    t1    0 0 4 0 0 3      # x y de cada vértice
+/// ```
+///
+/// A escrita preserva a ordem dos elementos e usa a menor
+/// representação que relê exatamente os mesmos valores reais.
+pub use super::object_data::{
+    Ponto as Ponto,
+    Vetor as Vetor,
+    Bloco as Bloco,
+    Quadrilatero as Quadrilatero,
+    Circulo as Circulo,
+    QuadOrientado as QuadOrientado,
+    Segmento as Segmento,
+    Poligono as Poligono,
+    Forma as Forma,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+
+// Estruturas
+/// Elemento nomeado de uma cena
+#[derive(Debug, Clone)]
+pub enum Elemento {
+  Ponto(Ponto),
+  Vetor(Vetor),
+  Forma(Forma)
+}
+
+
+/// Coleção ordenada de elementos nomeados
+#[derive(Debug, Clone, Default)]
+pub struct Cena {
+  /// Elementos na ordem de inserção
+  elementos: Vec<(String, Elemento)>,
+
+  /// Posição de cada nome em `elementos`
+  indices: HashMap<String, usize>
+}
+
+
+/// Erro ao ler ou carregar uma cena
+#[derive(Debug)]
+pub enum ErroCena {
+  /// Falha ao acessar o arquivo
+  Io(std::io::Error),
+
+  /// Linha mal formada, a contagem começa em 1
+  Sintaxe {
+    linha: usize,
+    mensagem: String
+  }
+}
+
+
+// Métodos relacionados ao elemento
+impl Elemento {
+
+
+  /// Retorna a palavra que identifica o tipo no arquivo
+  pub fn tipo( &self ) -> &'static str {
+    match self {
+      Elemento::Ponto(_)                     => "ponto",
+      Elemento::Vetor(_)                     => "vetor",
+      Elemento::Forma(Forma::Bloco(_))        => "bloco",
+      Elemento::Forma(Forma::Quadrilatero(_)) => "quad",
+      Elemento::Forma(Forma::Circulo(_))      => "circulo",
+      Elemento::Forma(Forma::Orientado(_))    => "orientado",
+      Elemento::Forma(Forma::Segmento(_))     => "segmento",
+      Elemento::Forma(Forma::Poligono(_))     => "poligono",
+    }
+  }
+
+
+  /// Retorna os valores do elemento como escritos no arquivo
+  pub fn valores( &self ) -> String {
+    match self {
+      Elemento::Ponto(p) => format!("{} {}", p.x, p.y),
+      Elemento::Vetor(v) => format!("{} {}", v.x, v.y),
+      Elemento::Forma(f) => match f {
+        Forma::Bloco(b)        => format!("{} {} {} {}", b.p1.x, b.p1.y, b.p2.x, b.p2.y),
+        Forma::Quadrilatero(q) => format!("{} {} {} {}", q.p.x, q.p.y, q.tx, q.ty),
+        Forma::Circulo(c)      => format!("{} {} {}", c.p.x, c.p.y, c.r),
+        Forma::Orientado(o)    => format!("{} {} {} {} {}", o.c.0, o.c.1, o.hx, o.hy, o.â),
+        Forma::Segmento(s)     => format!("{} {} {} {}", s.p1.x, s.p1.y, s.p2.x, s.p2.y),
+        Forma::Poligono(p)     => {
+          p.pontos.iter()
+            .map(|v| format!("{} {}", v.x, v.y))
+            .collect::<Vec<String>>()
+            .join(" ")
+        },
+      },
+    }
+  }
+}
+
+
+// Métodos relacionados à cena
+impl Cena {
+
+
+  /// Gera uma nova cena vazia
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+
+  /// Insere um elemento, substituindo e retornando o anterior
+  /// de mesmo nome, que mantém sua posição.
+  ///
+  /// Nomes inválidos para o arquivo, veja [`valida_nome`], e
+  /// elementos que não podem ser relidos, como polígonos sem
+  /// vértices ou raios infinitos, são recusados e a cena não muda.
+  pub fn inserir( &mut self, nome: &str, e: Elemento) -> Result<Option<Elemento>, String> {
+    valida_nome(nome)?;
+
+    // a escrita do elemento precisa ser relida pela carga
+    let texto = e.valores();
+    let valores: Vec<&str> = texto.split_whitespace().collect();
+    le_elemento(e.tipo(), &valores).map_err(|erro| format!("'{}' não pode ser escrito: {}", nome, erro))?;
+
+    if let Some(&i) = self.indices.get(nome) {
+      return Ok(Some(std::mem::replace(&mut self.elementos[i].1, e)));
+    }
+
+    self.indices.insert(nome.to_string(), self.elementos.len());
+    self.elementos.push((nome.to_string(), e));
+    return Ok(None);
+  }
+
+
+  /// Remove um elemento pelo nome
+  pub fn remover( &mut self, nome: &str) -> Option<Elemento> {
+    let i = self.indices.remove(nome)?;
+    let (_, e) = self.elementos.remove(i);
+
+    // os elementos seguintes recuam uma posição
+    for (n, _) in self.elementos[i..].iter() {
+      if let Some(j) = self.indices.get_mut(n) {
+        *j -= 1;
+      }
+    }
+
+    return Some(e);
+  }
+
+
+  /// Retorna um elemento pelo nome
+  pub fn get( &self, nome: &str) -> Option<&Elemento> {
+    let i = *self.indices.get(nome)?;
+    Some(&self.elementos[i].1)
+  }
+
+
+  /// Retorna um ponto pelo nome
+  pub fn ponto( &self, nome: &str) -> Option<Ponto> {
+    match self.get(nome)? {
+      Elemento::Ponto(p) => Some(*p),
+      _                  => None,
+    }
+  }
+
+
+  /// Retorna um vetor pelo nome
+  pub fn vetor( &self, nome: &str) -> Option<Vetor> {
+    match self.get(nome)? {
+      Elemento::Vetor(v) => Some(*v),
+      _                  => None,
+    }
+  }
+
+
+  /// Retorna uma forma pelo nome
+  pub fn forma( &self, nome: &str) -> Option<&Forma> {
+    match self.get(nome)? {
+      Elemento::Forma(f) => Some(f),
+      _                  => None,
+    }
+  }
+
+
+  /// Percorre os elementos na ordem da cena
+  pub fn iter( &self ) -> impl Iterator<Item = (&str, &Elemento)> {
+    self.elementos.iter().map(|(n, e)| (n.as_str(), e))
+  }
+
+
+  /// Percorre apenas as formas na ordem da cena
+  pub fn formas( &self ) -> impl Iterator<Item = (&str, &Forma)> {
+    self.iter().filter_map(|(n, e)| match e {
+      Elemento::Forma(f) => Some((n, f)),
+      _                  => None,
+    })
+  }
+
+
+  /// Percorre os elementos de um tipo, como escrito no arquivo
+  pub fn do_tipo<'a>( &'a self, tipo: &'a str) -> impl Iterator<Item = (&'a str, &'a Elemento)> {
+    self.iter().filter(move |(_, e)| e.tipo() == tipo)
+  }
+
+
+  /// Quantidade de elementos
+  pub fn len( &self ) -> usize {
+    self.elementos.len()
+  }
+
+
+  /// Analisa se a cena não possui elementos
+  pub fn is_empty( &self ) -> bool {
+    self.elementos.is_empty()
+  }
+
+
+  /// Lê uma cena de um arquivo
+  pub fn carregar<P: AsRef<std::path::Path>>( caminho: P) -> Result<Self, ErroCena> {
+    let texto = std::fs::read_to_string(caminho).map_err(ErroCena::Io)?;
+    texto.parse()
+  }
+
+
+  /// Escreve a cena em um arquivo
+  pub fn salvar<P: AsRef<std::path::Path>>( &self, caminho: P) -> std::io::Result<()> {
+    std::fs::write(caminho, self.to_string())
+  }
+}
+
+
+impl fmt::Display for Cena {
+  fn fmt( &self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (nome, e) in self.elementos.iter() {
+      writeln!(f, "{} {} {}", e.tipo(), nome, e.valores())?;
+    }
+
+    Ok(())
+  }
+}
+
+
+impl FromStr for Cena {
+  type Err = ErroCena;
+
+  fn from_str( texto: &str) -> Result<Self, Self::Err> {
+    let mut cena = Cena::new();
+
+    for (i, linha) in texto.lines().enumerate() {
+      let erro = |mensagem: String| ErroCena::Sintaxe { linha: i + 1, mensagem };
+
+      // comentários vão até o fim da linha
+      let conteudo = match linha.find('#') {
+        Some(c) => &linha[..c],
+        None    => linha,
+      };
+
+      let mut partes = conteudo.split_whitespace();
+      let tipo = match partes.next() {
+        Some(t) => t,
+        None    => continue,
+      };
+
+      let nome = match partes.next() {
+        Some(n) => n,
+        None    => return Err(erro(format!("'{}' sem nome", tipo))),
+      };
+
+      let valores: Vec<&str> = partes.collect();
+      let e = le_elemento(tipo, &valores).map_err(erro)?;
+
+      if cena.get(nome).is_some() {
+        return Err(erro(format!("nome repetido '{}'", nome)));
+      }
+
+      cena.inserir(nome, e).map_err(erro)?;
+    }
+
+    return Ok(cena);
+  }
+}
+
+
+impl fmt::Display for ErroCena {
+  fn fmt( &self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ErroCena::Io(e)                       => write!(f, "{}", e),
+      ErroCena::Sintaxe { linha, mensagem } => write!(f, "linha {}: {}", linha, mensagem),
+    }
+  }
+}
+
+impl std::error::Error for ErroCena {}
+
+
+/// Lê os valores de um elemento conforme o seu tipo, com a
+/// mesma sintaxe das linhas do arquivo
+pub fn le_elemento( tipo: &str, valores: &[&str]) -> Result<Elemento, String> {
+  let e = match tipo {
+    "ponto" => {
+      let [x, y] = inteiros::<2>(valores)?;
+      Elemento::Ponto(Ponto::new(x, y))
+    },
+
+    "vetor" => {
+      let [x, y] = inteiros::<2>(valores)?;
+      Elemento::Vetor(Vetor::new(x, y))
+    },
+
+    "bloco" => {
+      let [x1, y1, x2, y2] = inteiros::<4>(valores)?;
+      Elemento::Forma(Forma::Bloco(Bloco::new((x1, y1), (x2, y2))))
+    },
+
+    "quad" => {
+      let [x, y, tx, ty] = inteiros::<4>(valores)?;
+      if tx < 0 || ty < 0 {
+        return Err(format!("dimensões negativas: {} x {}", tx, ty));
+      }
+
+      Elemento::Forma(Forma::Quadrilatero(Quadrilatero {
+        p: Ponto::new(x, y),
+        tx,
+        ty
+      }))
+    },
+
+    "circulo" => {
+      quantidade(valores, 3)?;
+      let [x, y] = inteiros::<2>(&valores[..2])?;
+      let r = real(valores[2])?;
+      if r < 0.0 {
+        return Err(format!("raio negativo: {}", r));
+      }
+
+      Elemento::Forma(Forma::Circulo(Circulo::new((x, y), r)))
+    },
+
+    "orientado" => {
+      quantidade(valores, 5)?;
+      let cx = real(valores[0])?;
+      let cy = real(valores[1])?;
+      let hx = real(valores[2])?;
+      let hy = real(valores[3])?;
+      let â = real(valores[4])?;
+      if hx < 0.0 || hy < 0.0 {
+        return Err(format!("dimensões negativas: {} x {}", hx, hy));
+      }
+
+      // o ângulo é lido direto em f32 para reler exatamente o valor escrito
+      let â = valores[4].parse::<f32>().unwrap_or(â as f32);
+      Elemento::Forma(Forma::Orientado(QuadOrientado::new((cx, cy), hx, hy, â)))
+    },
+
+    "segmento" => {
+      let [x1, y1, x2, y2] = inteiros::<4>(valores)?;
+      Elemento::Forma(Forma::Segmento(Segmento::new((x1, y1), (x2, y2))))
+    },
+
+    "poligono" => {
+      if valores.is_empty() || valores.len() % 2 != 0 {
+        return Err(format!("o polígono precisa de pares x y, recebeu {} valores", valores.len()));
+      }
+
+      let mut pontos = Vec::with_capacity(valores.len() / 2);
+      for par in valores.chunks(2) {
+        let [x, y] = inteiros::<2>(par)?;
+        pontos.push(Ponto::new(x, y));
+      }
+
+      Elemento::Forma(Forma::Poligono(Poligono::new(pontos)))
+    },
+
+    t => return Err(format!("tipo desconhecido '{}'", t)),
+  };
+
+  return Ok(e);
+}
+
+
+/// Confere se o nome pode ser escrito e relido em uma cena: não
+/// vazio e sem espaços, quebras de linha ou `#`
+pub fn valida_nome( nome: &str) -> Result<(), String> {
+  if nome.is_empty() || nome.contains(char::is_whitespace) || nome.contains('#') {
+    return Err(format!("nome inválido '{}'", nome.escape_debug()));
+  }
+
+  Ok(())
+}
+
+
+/// Confere a quantidade de valores da linha
+fn quantidade( valores: &[&str], n: usize) -> Result<(), String> {
+  if valores.len() != n {
+    return Err(format!("esperava {} valores, recebeu {}", n, valores.len()));
+  }
+
+  Ok(())
+}
+
+
+/// Lê exatamente `N` valores inteiros
+fn inteiros<const N: usize>( valores: &[&str]) -> Result<[i32; N], String> {
+  quantidade(valores, N)?;
+
+  let mut saida = [0; N];
+  for (s, v) in saida.iter_mut().zip(valores.iter()) {
+    *s = v.parse().map_err(|_| format!("inteiro inválido '{}'", v))?;
+  }
+
+  Ok(saida)
+}
+
+
+/// Lê um valor real finito
+fn real( valor: &str) -> Result<f64, String> {
+  match valor.parse::<f64>() {
+    Ok(v) if v.is_finite() => Ok(v),
+    _                      => Err(format!("número inválido '{}'", valor)),
+  }
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+
+  const EXEMPLO: &str = "\
+# cena de exemplo
+
+ponto       p1    2 -4
+vetor       v1    2 4        # comentário no fim
+bloco       b1    1 1 3 3
+quad        q1    1 2 4 1
+circulo     c1    3 3 0.1
+orientado   o1    3.25 -3 1e-7 0.5 0.785
+segmento    s1    0 0 4 4
+poligono    t1    0 0 4 0 0 3
+";
+
+
+  /// Linha do erro de sintaxe ao ler o texto
+  fn linha_do_erro( texto: &str) -> usize {
+    match texto.parse::<Cena>() {
+      Err(ErroCena::Sintaxe { linha, .. }) => linha,
+      outro                                => panic!("esperava erro de sintaxe, recebeu {:?}", outro),
+    }
+  }
+
+
+  /// Confere que duas cenas têm os mesmos nomes, tipos e valores
+  fn iguais( a: &Cena, b: &Cena) {
+    let texto = |c: &Cena| c.iter().map(|(n, e)| (n.to_string(), e.tipo(), e.valores())).collect::<Vec<_>>();
+    assert_eq!(texto(a), texto(b));
+  }
+
+
+  #[test]
+  fn carrega_salva_e_recarrega_sem_perdas() {
+    let cena: Cena = EXEMPLO.parse().unwrap();
+    assert_eq!(cena.len(), 8);
+    assert_eq!(cena.ponto("p1"), Some(Ponto::new(2, -4)));
+
+    let relida: Cena = cena.to_string().parse().unwrap();
+    iguais(&cena, &relida);
+    assert_eq!(relida.to_string(), cena.to_string());
+  }
+
+
+  #[test]
+  fn reais_voltam_com_os_mesmos_bits() {
+    let mut cena = Cena::new();
+    let raios = [0.1, 1.0 / 3.0, 1e-300, 5e-324, 123456789.0 + 0.1, f64::MAX];
+    for (i, &r) in raios.iter().enumerate() {
+      let c = Circulo::new((i as i32, -(i as i32)), r);
+      cena.inserir(&format!("c{}", i), Elemento::Forma(Forma::Circulo(c))).unwrap();
+    }
+    let o = QuadOrientado::new((0.1 + 0.2, -7.5e-9), 2.0_f64.sqrt(), 1e10, 0.1_f32 + 0.2_f32);
+    cena.inserir("o", Elemento::Forma(Forma::Orientado(o))).unwrap();
+
+    let relida: Cena = cena.to_string().parse().unwrap();
+    for (i, &r) in raios.iter().enumerate() {
+      match relida.forma(&format!("c{}", i)) {
+        Some(Forma::Circulo(c)) => assert_eq!(c.r.to_bits(), r.to_bits()),
+        outro                   => panic!("{:?}", outro),
+      }
+    }
+    match relida.forma("o") {
+      Some(Forma::Orientado(lido)) => {
+        assert_eq!(lido.c.0.to_bits(), o.c.0.to_bits());
+        assert_eq!(lido.c.1.to_bits(), o.c.1.to_bits());
+        assert_eq!(lido.hx.to_bits(), o.hx.to_bits());
+        assert_eq!(lido.â.to_bits(), o.â.to_bits());
+      },
+      outro => panic!("{:?}", outro),
+    }
+  }
+
+
+  #[test]
+  fn arquivo_salvo_e_relido() {
+    let cena: Cena = EXEMPLO.parse().unwrap();
+    let caminho = std::env::temp_dir().join(format!("jeotry-cena-{}.txt", std::process::id()));
+    cena.salvar(&caminho).unwrap();
+    let relida = Cena::carregar(&caminho);
+    std::fs::remove_file(&caminho).unwrap();
+    iguais(&cena, &relida.unwrap());
+  }
+
+
+  #[test]
+  fn erros_indicam_a_linha() {
+    assert_eq!(linha_do_erro("ponto p 1 2\ntriangulo t 0 0"), 2);
+    assert_eq!(linha_do_erro("# só comentário\n\nponto\n"), 3);
+    assert_eq!(linha_do_erro("ponto p 1 2\nvetor v 1\n"), 2);
+    assert_eq!(linha_do_erro("ponto p 1 2\n\n\nponto p 3 4"), 4);
+    assert_eq!(linha_do_erro("quad q 0 0 -1 2"), 1);
+    assert_eq!(linha_do_erro("ponto a 1 2\ncirculo c 0 0 inf\n"), 2);
+    assert_eq!(linha_do_erro("ponto a 1 2\r\nponto b x 2\r\n"), 2);
+    assert_eq!(linha_do_erro("poligono t 0 0 1"), 1);
+  }
+
+
+  #[test]
+  fn nomes_e_elementos_que_nao_relem_sao_recusados() {
+    let mut cena = Cena::new();
+    let p = Elemento::Ponto(Ponto::new(1, 2));
+    for nome in ["", "a b", "a#b", "#", "a\nb", "a\tb", "a\u{a0}b"] {
+      assert!(cena.inserir(nome, p.clone()).is_err(), "{:?}", nome);
+    }
+
+    let vazio = Elemento::Forma(Forma::Poligono(Poligono::new(Vec::new())));
+    assert!(cena.inserir("t", vazio).is_err());
+    let infinito = Elemento::Forma(Forma::Circulo(Circulo::new((0, 0), f64::INFINITY)));
+    assert!(cena.inserir("c", infinito).is_err());
+    assert!(cena.is_empty());
+
+    // nomes válidos incluem acentos e pontuação
+    assert!(cena.inserir("ponto_á-1.b", p.clone()).unwrap().is_none());
+    assert!(cena.inserir("ponto_á-1.b", p).unwrap().is_some());
+    let relida: Cena = cena.to_string().parse().unwrap();
+    iguais(&cena, &relida);
+  }
+}
@@ -0,0 +1,271 @@
+/// Representações intermediárias usadas na desserialização,
+/// disponíveis apenas com a feature `serde`.
+///
+/// Os dados recebidos de fora passam por estas estruturas antes
+/// de virarem objetos, o que garante que valores inválidos, como
+/// raios negativos, cantos invertidos e coordenadas não finitas,
+/// sejam recusados em vez de corrigidos em silêncio.
+use core::convert::TryFrom;
+use alloc::format;
+use alloc::string::{String, ToString};
+use serde::Deserialize;
+
+use super::vector_data::{
+    Ponto,
+    Modulo,
+};
+use super::object_data::{
+    Bloco,
+    Quadrilatero,
+    Circulo,
+    QuadOrientado,
+};
+use super::transform_data::Transformacao;
+
+
+// Estruturas
+/// Módulo como recebido, antes da validação
+#[derive(Deserialize)]
+pub(crate) struct ModuloBruto {
+  i: f64,
+  #[serde(rename = "angulo")]
+  â: f32
+}
+
+
+/// Bloco como recebido, antes da validação dos cantos
+#[derive(Deserialize)]
+pub(crate) struct BlocoBruto {
+  p1: Ponto,
+  p2: Ponto
+}
+
+
+/// Quadrilátero como recebido, antes da validação
+#[derive(Deserialize)]
+pub(crate) struct QuadBruto {
+  p: Ponto,
+  tx: i32,
+  ty: i32
+}
+
+
+/// Círculo como recebido, antes da validação
+#[derive(Deserialize)]
+pub(crate) struct CirculoBruto {
+  p: Ponto,
+  r: f64
+}
+
+
+/// Quadrilátero orientado como recebido, antes da validação
+#[derive(Deserialize)]
+pub(crate) struct OrientadoBruto {
+  c: (f64, f64),
+  hx: f64,
+  hy: f64,
+  #[serde(rename = "angulo")]
+  â: f32
+}
+
+
+/// Transformação como recebida, antes da validação
+#[derive(Deserialize)]
+pub(crate) struct TransformacaoBruta {
+  m: [[f64; 3]; 3]
+}
+
+
+// Conversões
+impl TryFrom<ModuloBruto> for Modulo {
+  type Error = String;
+
+  fn try_from( m: ModuloBruto) -> Result<Self, Self::Error> {
+    if !m.i.is_finite() || m.i < 0.0 {
+      return Err(format!("intensidade inválida: {}", m.i));
+    }
+
+    if !m.â.is_finite() {
+      return Err(format!("ângulo inválido: {}", m.â));
+    }
+
+    return Ok(Modulo {
+      i: m.i,
+      â: m.â
+    });
+  }
+}
+
+
+impl TryFrom<BlocoBruto> for Bloco {
+  type Error = String;
+
+  fn try_from( b: BlocoBruto) -> Result<Self, Self::Error> {
+    if b.p1.x > b.p2.x || b.p1.y > b.p2.y {
+      return Err(format!(
+        "cantos invertidos: ({}, {}) deve estar à esquerda e abaixo de ({}, {})",
+        b.p1.x, b.p1.y, b.p2.x, b.p2.y
+      ));
+    }
+
+    return Ok(Bloco::from(b.p1, b.p2));
+  }
+}
+
+
+impl TryFrom<QuadBruto> for Quadrilatero {
+  type Error = String;
+
+  fn try_from( q: QuadBruto) -> Result<Self, Self::Error> {
+    if q.tx < 0 || q.ty < 0 {
+      return Err(format!("dimensões negativas: {} x {}", q.tx, q.ty));
+    }
+
+    return Ok(Quadrilatero {
+      p: q.p,
+      tx: q.tx,
+      ty: q.ty
+    });
+  }
+}
+
+
+impl TryFrom<CirculoBruto> for Circulo {
+  type Error = String;
+
+  fn try_from( c: CirculoBruto) -> Result<Self, Self::Error> {
+    if !c.r.is_finite() || c.r < 0.0 {
+      return Err(format!("raio inválido: {}", c.r));
+    }
+
+    return Ok(Circulo::from(c.p, c.r));
+  }
+}
+
+
+impl TryFrom<OrientadoBruto> for QuadOrientado {
+  type Error = String;
+
+  fn try_from( o: OrientadoBruto) -> Result<Self, Self::Error> {
+    let valores = [o.c.0, o.c.1, o.hx, o.hy, o.â as f64];
+    if valores.iter().any(|v| !v.is_finite()) {
+      return Err("coordenadas não finitas".to_string());
+    }
+
+    if o.hx < 0.0 || o.hy < 0.0 {
+      return Err(format!("dimensões negativas: {} x {}", o.hx, o.hy));
+    }
+
+    return Ok(QuadOrientado::new(o.c, o.hx, o.hy, o.â));
+  }
+}
+
+
+impl TryFrom<TransformacaoBruta> for Transformacao {
+  type Error = String;
+
+  fn try_from( t: TransformacaoBruta) -> Result<Self, Self::Error> {
+    if t.m.iter().flatten().any(|v| !v.is_finite()) {
+      return Err("matriz com valores não finitos".to_string());
+    }
+
+    if t.m[2] != [0.0, 0.0, 1.0] {
+      return Err(format!("a última linha deve ser [0, 0, 1]: {:?}", t.m[2]));
+    }
+
+    return Ok(Transformacao {
+      m: t.m
+    });
+  }
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use super::super::vector_data::Vetor;
+  use super::super::object_data::{Segmento, Poligono, Forma};
+  use serde::Serialize;
+  use serde::de::DeserializeOwned;
+
+
+  /// Serializa, desserializa e serializa de novo, o texto deve
+  /// ser o mesmo nas duas vezes
+  fn ida_e_volta<T: Serialize + DeserializeOwned>( valor: &T) -> String {
+    let texto = serde_json::to_string(valor).unwrap();
+    let volta: T = serde_json::from_str(&texto).unwrap();
+    assert_eq!(serde_json::to_string(&volta).unwrap(), texto);
+    return texto;
+  }
+
+
+  /// Mensagem de erro da desserialização
+  fn recusa<T: DeserializeOwned + core::fmt::Debug>( texto: &str) -> String {
+    serde_json::from_str::<T>(texto).unwrap_err().to_string()
+  }
+
+
+  #[test]
+  fn formas_vao_e_voltam() {
+    assert_eq!(ida_e_volta(&Ponto::new(-3, 4)), r#"{"x":-3,"y":4}"#);
+    assert_eq!(ida_e_volta(&Bloco::new((5, 6), (1, 2))), r#"{"p1":{"x":1,"y":2},"p2":{"x":5,"y":6}}"#);
+    assert_eq!(ida_e_volta(&Circulo::new((1, 1), 2.5)), r#"{"p":{"x":1,"y":1},"r":2.5}"#);
+    ida_e_volta(&Vetor::new(i32::MIN, i32::MAX));
+    ida_e_volta(&Modulo { i: 3.5, â: -1.25 });
+    ida_e_volta(&Quadrilatero::new((-1, 2), 3, 4));
+    ida_e_volta(&QuadOrientado::new((0.5, -0.25), 2.0, 1.0, 0.75));
+    ida_e_volta(&Transformacao::rotacao(0.5).seguida(Transformacao::translacao(3.0, -1.0)));
+
+    let formas = [
+      Forma::Bloco(Bloco::new((i32::MIN, 0), (i32::MAX, 1))),
+      Forma::Quadrilatero(Quadrilatero::new((0, 0), 7, 0)),
+      Forma::Circulo(Circulo::new((0, 0), 0.0)),
+      Forma::Orientado(QuadOrientado::new((1.0, 1.0), 0.5, 3.0, -2.0)),
+      Forma::Segmento(Segmento::new((1, 2), (3, 4))),
+      Forma::Poligono(Poligono::from(&[(0, 0), (4, 0), (0, 3)])),
+    ];
+    for f in formas.iter() {
+      ida_e_volta(f);
+    }
+    ida_e_volta(&formas.to_vec());
+  }
+
+
+  #[test]
+  fn valores_invalidos_sao_recusados() {
+    let erro = recusa::<Circulo>(r#"{"p":{"x":0,"y":0},"r":-1.0}"#);
+    assert!(erro.contains("raio inválido"), "{}", erro);
+    let erro = recusa::<Bloco>(r#"{"p1":{"x":5,"y":0},"p2":{"x":1,"y":2}}"#);
+    assert!(erro.contains("cantos invertidos"), "{}", erro);
+    let erro = recusa::<Bloco>(r#"{"p1":{"x":0,"y":3},"p2":{"x":1,"y":2}}"#);
+    assert!(erro.contains("cantos invertidos"), "{}", erro);
+    let erro = recusa::<Quadrilatero>(r#"{"p":{"x":0,"y":0},"tx":-1,"ty":2}"#);
+    assert!(erro.contains("dimensões negativas"), "{}", erro);
+    let erro = recusa::<QuadOrientado>(r#"{"c":[0.0,0.0],"hx":1.0,"hy":-1.0,"angulo":0.0}"#);
+    assert!(erro.contains("dimensões negativas"), "{}", erro);
+    let erro = recusa::<Modulo>(r#"{"i":-2.0,"angulo":0.0}"#);
+    assert!(erro.contains("intensidade inválida"), "{}", erro);
+    let erro = recusa::<Transformacao>(r#"{"m":[[1,0,0],[0,1,0],[0,1,1]]}"#);
+    assert!(erro.contains("última linha"), "{}", erro);
+    let erro = recusa::<Forma>(r#"{"Circulo":{"p":{"x":0,"y":0},"r":-0.5}}"#);
+    assert!(erro.contains("raio inválido"), "{}", erro);
+
+    // o JSON não representa valores não finitos, que chegam de
+    // outros formatos direto às representações intermediárias
+    let p = Ponto::new(0, 0);
+    for v in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY].iter().copied() {
+      assert!(Circulo::try_from(CirculoBruto { p, r: v }).is_err());
+      assert!(Modulo::try_from(ModuloBruto { i: v, â: 0.0 }).is_err());
+      assert!(Modulo::try_from(ModuloBruto { i: 1.0, â: v as f32 }).is_err());
+      assert!(QuadOrientado::try_from(OrientadoBruto { c: (v, 0.0), hx: 1.0, hy: 1.0, â: 0.0 }).is_err());
+      assert!(QuadOrientado::try_from(OrientadoBruto { c: (0.0, 0.0), hx: v, hy: 1.0, â: 0.0 }).is_err());
+      assert!(QuadOrientado::try_from(OrientadoBruto { c: (0.0, 0.0), hx: 1.0, hy: 1.0, â: v as f32 }).is_err());
+      let mut m = Transformacao::identidade().m;
+      m[0][2] = v;
+      assert!(Transformacao::try_from(TransformacaoBruta { m }).is_err());
+    }
+
+    // cantos iguais são um bloco sem área, não invertido
+    assert!(Bloco::try_from(BlocoBruto { p1: p, p2: p }).is_ok());
+  }
+}
@@ -0,0 +1,648 @@
+/// Serviço local de consultas geométricas, por TCP em localhost
+/// ou por um socket Unix.
+///
+/// O mundo é uma cena compartilhada entre todas as conexões,
+/// cada uma atendida em sua própria thread. Consultas leem o
+/// mundo ao mesmo tempo, alterações esperam as leituras.
+///
+/// O protocolo é de texto, com um comando por linha e uma
+/// resposta por linha. Respostas começam com `ok`, seguido dos
+/// resultados separados por espaços, ou com `erro`, seguido da
+/// mensagem. Objetos são escritos como nas linhas de uma cena.
+/// Os pares de `collisions` são escritos como linhas de CSV, com
+/// os nomes entre aspas quando contêm vírgulas ou aspas.
+///
+/// Linhas maiores que `LIMITE_LINHA` encerram a conexão e as
+/// conexões além do limite do serviço são recusadas com um erro.
+///
+/// ```text
+/// --> put b1 bloco 1 1 3 3
+/// <-- ok
+/// --> region circulo 4 4 2
+/// <-- ok b1
+/// --> ray 0 2 1 0
+/// <-- ok b1 1
+/// ```
+pub use jeotry::object_data::{
+    Segmento as Segmento,
+    Forma as Forma,
+};
+use jeotry::scene_data::{
+    Cena,
+    Elemento,
+    le_elemento,
+};
+use jeotry::batch_data::{
+    self,
+    Registro,
+};
+use super::cli::campo_csv;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread;
+
+
+/// Endereço padrão do serviço TCP
+pub static ENDERECO_PADRAO: &str = "127.0.0.1:7878";
+
+/// Texto de ajuda do protocolo
+pub static AJUDA: &str = "\
+put <nome> <tipo> <valores>  get <nome>  del <nome>  list  count  clear  \
+point <x> <y>  region <tipo> <valores>  ray <x> <y> <dx> <dy>  \
+collide <a> <b>  collisions  help  quit";
+
+/// Tamanho máximo de uma linha de comando, em bytes
+pub static LIMITE_LINHA: usize = 64 * 1024;
+
+/// Quantidade padrão de conexões atendidas ao mesmo tempo
+pub static LIMITE_CONEXOES: usize = 64;
+
+
+// Estruturas
+/// Endereço em que o serviço escuta
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endereco {
+  /// Endereço TCP, que deve ser local
+  Tcp(String),
+
+  /// Caminho de um socket Unix
+  Unix(std::path::PathBuf)
+}
+
+
+/// Serviço com o mundo compartilhado, cópias compartilham o
+/// mesmo mundo e a mesma contagem de conexões
+#[derive(Debug, Clone)]
+pub struct Servico {
+  /// Conexões atendidas ao mesmo tempo, as demais são recusadas
+  pub limite_conexoes: usize,
+
+  mundo: Arc<RwLock<Cena>>,
+
+  /// Conexões sendo atendidas
+  conexoes: Arc<AtomicUsize>
+}
+
+
+/// Ocupa uma vaga de conexão e a libera ao sair de escopo, mesmo
+/// que a thread da conexão falhe
+struct Vaga(Arc<AtomicUsize>);
+
+
+/// Cliente de linha do serviço
+pub struct Cliente {
+  leitor: BufReader<Box<dyn Read + Send>>,
+  escritor: Box<dyn Write + Send>
+}
+
+
+// Métodos relacionados ao serviço
+impl Servico {
+
+
+  /// Gera um serviço com um mundo inicial
+  pub fn new( cena: Cena) -> Self {
+    Self {
+      limite_conexoes: LIMITE_CONEXOES,
+      mundo: Arc::new(RwLock::new(cena)),
+      conexoes: Arc::new(AtomicUsize::new(0))
+    }
+  }
+
+
+  /// Trava o mundo para leitura, uma thread que falhou não
+  /// impede as demais de continuar
+  pub fn le( &self ) -> RwLockReadGuard<'_, Cena> {
+    self.mundo.read().unwrap_or_else(|e| e.into_inner())
+  }
+
+
+  /// Trava o mundo para escrita
+  pub fn escreve( &self ) -> RwLockWriteGuard<'_, Cena> {
+    self.mundo.write().unwrap_or_else(|e| e.into_inner())
+  }
+
+
+  /// Escuta no endereço e atende cada conexão em uma thread,
+  /// `pronto` recebe o endereço efetivo antes das conexões.
+  /// Conexões que falham ao ser aceitas são registradas na saída
+  /// de erros e o serviço continua
+  pub fn escuta<F: FnOnce(&str)>( &self, endereco: &Endereco, pronto: F) -> io::Result<()> {
+    match endereco {
+      Endereco::Tcp(e) => {
+        let local = e.to_socket_addrs()?.find(|a| a.ip().is_loopback());
+        let local = local.ok_or_else(|| {
+          io::Error::new(io::ErrorKind::InvalidInput, format!("{} não é um endereço local", e))
+        })?;
+
+        let ouvinte = TcpListener::bind(local)?;
+        pronto(&ouvinte.local_addr()?.to_string());
+        for conexao in ouvinte.incoming() {
+          // respostas curtas saem sem esperar o pacote encher
+          let conexao = conexao.and_then(|c| c.set_nodelay(true).map(|_| c));
+          match conexao.and_then(|c| Ok((c.try_clone()?, c))) {
+            Ok((leitor, conexao)) => self.despacha(leitor, conexao),
+            Err(e)                => recusa(e),
+          }
+        }
+      },
+
+      #[cfg(unix)]
+      Endereco::Unix(caminho) => {
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::UnixListener;
+
+        // um socket antigo no caminho é removido, outros arquivos não
+        if let Ok(m) = std::fs::symlink_metadata(caminho) {
+          if m.file_type().is_socket() {
+            std::fs::remove_file(caminho)?;
+          }
+        }
+
+        let ouvinte = UnixListener::bind(caminho)?;
+        pronto(&caminho.display().to_string());
+        for conexao in ouvinte.incoming() {
+          match conexao.and_then(|c| Ok((c.try_clone()?, c))) {
+            Ok((leitor, conexao)) => self.despacha(leitor, conexao),
+            Err(e)                => recusa(e),
+          }
+        }
+      },
+
+      #[cfg(not(unix))]
+      Endereco::Unix(_) => {
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "sockets Unix não são suportados"));
+      },
+    }
+
+    return Ok(());
+  }
+
+
+  /// Ocupa uma vaga de conexão, se houver
+  fn vaga( &self ) -> Option<Vaga> {
+    let ocupadas = self.conexoes.fetch_add(1, Ordering::SeqCst);
+    let vaga = Vaga(self.conexoes.clone());
+    if ocupadas >= self.limite_conexoes {
+      return None;
+    }
+
+    return Some(vaga);
+  }
+
+
+  /// Atende uma conexão em uma nova thread, ou a recusa com um
+  /// erro quando todas as vagas estão ocupadas
+  fn despacha<R, W>( &self, leitor: R, mut escritor: W)
+  where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+  {
+    let vaga = match self.vaga() {
+      Some(v) => v,
+      None    => {
+        let _ = writeln!(escritor, "erro conexões demais, tente mais tarde");
+        return;
+      },
+    };
+
+    let servico = self.clone();
+    thread::spawn(move || {
+      let _vaga = vaga;
+      // uma conexão encerrada pelo cliente não afeta o serviço
+      let _ = servico.atende(BufReader::new(leitor), escritor);
+    });
+  }
+
+
+  /// Responde as linhas de uma conexão até `quit` ou o fim. Uma
+  /// linha maior que `LIMITE_LINHA` é respondida com um erro e
+  /// encerra a conexão, sem ser lida até o fim
+  pub fn atende<R: BufRead, W: Write>( &self, mut entrada: R, mut saida: W) -> io::Result<()> {
+    let mut bytes = Vec::new();
+    loop {
+      bytes.clear();
+      let limite = LIMITE_LINHA as u64 + 1;
+      if (&mut entrada).take(limite).read_until(b'\n', &mut bytes)? == 0 {
+        break;
+      }
+
+      if bytes.last() == Some(&b'\n') {
+        bytes.pop();
+      } else if bytes.len() > LIMITE_LINHA {
+        writeln!(saida, "erro linha maior que {} bytes", LIMITE_LINHA)?;
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "linha longa demais"));
+      }
+
+      let linha = match std::str::from_utf8(&bytes) {
+        Ok(l)  => l.strip_suffix('\r').unwrap_or(l),
+        Err(_) => {
+          writeln!(saida, "erro linha não é UTF-8")?;
+          saida.flush()?;
+          continue;
+        },
+      };
+
+      if linha.trim() == "quit" {
+        writeln!(saida, "ok")?;
+        break;
+      }
+
+      writeln!(saida, "{}", self.responde(linha))?;
+      saida.flush()?;
+    }
+
+    return Ok(());
+  }
+
+
+  /// Responde a um comando, sem a quebra de linha
+  pub fn responde( &self, linha: &str) -> String {
+    match self.executa(linha) {
+      Ok(r) if r.is_empty() => "ok".to_string(),
+      Ok(r)                 => format!("ok {}", r),
+      Err(e)                => format!("erro {}", e),
+    }
+  }
+
+
+  /// Executa um comando e retorna os resultados
+  fn executa( &self, linha: &str) -> Result<String, String> {
+    let partes: Vec<&str> = linha.split_whitespace().collect();
+    let (comando, args) = match partes.split_first() {
+      Some(c) => c,
+      None    => return Err("linha vazia".to_string()),
+    };
+
+    let resultado = match (*comando, args) {
+      ("put", [nome, tipo, valores @ ..]) => {
+        let e = le_elemento(tipo, valores)?;
+        self.escreve().inserir(nome, e)?;
+        String::new()
+      },
+
+      ("get", [nome]) => match self.le().get(nome) {
+        Some(e) => format!("{} {}", e.tipo(), e.valores()),
+        None    => return Err(format!("'{}' não existe", nome)),
+      },
+
+      ("del", [nome]) => match self.escreve().remover(nome) {
+        Some(_) => String::new(),
+        None    => return Err(format!("'{}' não existe", nome)),
+      },
+
+      ("list", []) => self.le().iter().map(|(n, _)| n).collect::<Vec<&str>>().join(" "),
+
+      ("count", []) => self.le().len().to_string(),
+
+      ("clear", []) => {
+        *self.escreve() = Cena::new();
+        String::new()
+      },
+
+      ("point", [x, y]) => {
+        let p = (real(x)?, real(y)?);
+        let mundo = self.le();
+        let nomes: Vec<&str> = mundo.formas().filter(|(_, f)| f.contains(p)).map(|(n, _)| n).collect();
+        nomes.join(" ")
+      },
+
+      ("region", [tipo, valores @ ..]) => {
+        let regiao = match le_elemento(tipo, valores)? {
+          Elemento::Forma(f) => f,
+          Elemento::Ponto(p) => Forma::Segmento(Segmento::from(p, p)),
+          Elemento::Vetor(_) => return Err("a região deve ser uma forma".to_string()),
+        };
+        let mundo = self.le();
+        let nomes: Vec<&str> = mundo.formas().filter(|(_, f)| f.collide(&regiao)).map(|(n, _)| n).collect();
+        nomes.join(" ")
+      },
+
+      ("ray", [x, y, dx, dy]) => {
+        let origem = (real(x)?, real(y)?);
+        let direcao = (real(dx)?, real(dy)?);
+        if direcao == (0.0, 0.0) {
+          return Err("direção nula".to_string());
+        }
+
+        // o objeto mais próximo atingido pelo raio
+        let mundo = self.le();
+        let acerto = mundo.formas()
+          .filter_map(|(n, f)| f.raycast(origem, direcao).map(|t| (n, t)))
+          .min_by(|a, b| a.1.total_cmp(&b.1));
+        match acerto {
+          Some((nome, t)) => format!("{} {}", nome, t),
+          None            => String::new(),
+        }
+      },
+
+      ("collide", [a, b]) => {
+        let mundo = self.le();
+        let forma = |nome: &str| match mundo.get(nome) {
+          Some(Elemento::Forma(f)) => Ok(f.clone()),
+          Some(Elemento::Ponto(p)) => Ok(Forma::Segmento(Segmento::from(*p, *p))),
+          Some(Elemento::Vetor(_)) => Err(format!("'{}' é um vetor", nome)),
+          None                     => Err(format!("'{}' não existe", nome)),
+        };
+        forma(a)?.collide(&forma(b)?).to_string()
+      },
+
+      ("collisions", []) => {
+        let mundo = self.le();
+        let registros = mundo.formas().enumerate().map(|(i, (nome, f))| Ok(Registro {
+          linha: i + 1,
+          id: nome.to_string(),
+          forma: f.clone()
+        }));
+
+        let mut pares = Vec::new();
+        batch_data::colisoes(registros, false, |a, b| {
+          pares.push(format!("{},{}", campo_csv(&a.id), campo_csv(&b.id)));
+          Ok(())
+        }).map_err(|e| e.to_string())?;
+        pares.join(" ")
+      },
+
+      ("help", []) => AJUDA.to_string(),
+
+      (c, _) => return Err(format!("comando ou argumentos inválidos '{}', use help", c)),
+    };
+
+    return Ok(resultado);
+  }
+}
+
+
+impl Default for Servico {
+  fn default() -> Self {
+    Servico::new(Cena::new())
+  }
+}
+
+
+impl Drop for Vaga {
+  fn drop( &mut self ) {
+    self.0.fetch_sub(1, Ordering::SeqCst);
+  }
+}
+
+
+// Métodos relacionados ao cliente
+impl Cliente {
+
+
+  /// Conecta a um serviço
+  pub fn conecta( endereco: &Endereco) -> io::Result<Self> {
+    let (leitor, escritor): (Box<dyn Read + Send>, Box<dyn Write + Send>) = match endereco {
+      Endereco::Tcp(e) => {
+        let conexao = TcpStream::connect(e.as_str())?;
+        conexao.set_nodelay(true)?;
+        (Box::new(conexao.try_clone()?), Box::new(conexao))
+      },
+
+      #[cfg(unix)]
+      Endereco::Unix(caminho) => {
+        let conexao = std::os::unix::net::UnixStream::connect(caminho)?;
+        (Box::new(conexao.try_clone()?), Box::new(conexao))
+      },
+
+      #[cfg(not(unix))]
+      Endereco::Unix(_) => {
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "sockets Unix não são suportados"));
+      },
+    };
+
+    return Ok(Self {
+      leitor: BufReader::new(leitor),
+      escritor
+    });
+  }
+
+
+  /// Envia um comando e retorna a resposta, sem a quebra de linha
+  pub fn pergunta( &mut self, comando: &str) -> io::Result<String> {
+    writeln!(self.escritor, "{}", comando.trim())?;
+    self.escritor.flush()?;
+
+    let mut resposta = String::new();
+    if self.leitor.read_line(&mut resposta)? == 0 {
+      return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "conexão encerrada pelo serviço"));
+    }
+
+    return Ok(resposta.trim_end().to_string());
+  }
+}
+
+
+/// Registra uma conexão que não pôde ser aceita, sem encerrar o
+/// serviço. A pausa evita repetir o erro sem parar quando faltam
+/// recursos, como descritores de arquivo
+fn recusa( e: io::Error) {
+  eprintln!("jeotry: conexão não aceita: {}", e);
+  thread::sleep(std::time::Duration::from_millis(10));
+}
+
+
+/// Lê um número real de um argumento
+fn real( s: &str) -> Result<f64, String> {
+  match s.parse::<f64>() {
+    Ok(v) if v.is_finite() => Ok(v),
+    _                      => Err(format!("número inválido '{}'", s)),
+  }
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use std::sync::mpsc;
+
+
+  /// Inicia o serviço em uma thread e retorna o endereço efetivo
+  fn inicia( servico: &Servico, endereco: Endereco) -> String {
+    let (envia, recebe) = mpsc::channel();
+    let servico = servico.clone();
+    thread::spawn(move || {
+      let avisa = envia.clone();
+      if let Err(e) = servico.escuta(&endereco, move |e| avisa.send(Ok(e.to_string())).unwrap()) {
+        let _ = envia.send(Err(e.to_string()));
+      }
+    });
+
+    return recebe.recv().unwrap().unwrap();
+  }
+
+
+  /// Mundo com uma grade de blocos e um círculo no centro
+  fn mundo() -> Servico {
+    let servico = Servico::default();
+    for i in 0..4 {
+      for j in 0..4 {
+        let linha = format!("put b{}{} bloco {} {} {} {}", i, j, i * 10, j * 10, i * 10 + 4, j * 10 + 4);
+        assert_eq!(servico.responde(&linha), "ok");
+      }
+    }
+    assert_eq!(servico.responde("put c circulo 17 17 6"), "ok");
+    return servico;
+  }
+
+
+  /// Consultas com as respostas esperadas no mundo inicial
+  fn consultas() -> Vec<(&'static str, &'static str)> {
+    vec![
+      ("region bloco 9 9 21 21", "ok b11 b12 b21 b22 c"),
+      ("region circulo 0 0 1", "ok b00"),
+      ("region segmento 5 5 6 6", "ok"),
+      ("point 2 32", "ok b03"),
+      ("point 17 17", "ok c"),
+      ("point 7 7", "ok"),
+      ("ray -5 2 1 0", "ok b00 5"),
+      ("ray 12 -5 0 1", "ok b10 5"),
+      ("ray 100 100 1 0", "ok"),
+      ("collide b11 c", "ok true"),
+      ("collide b00 c", "ok false"),
+      ("collisions", "ok b11,c b12,c c,b21 c,b22"),
+    ]
+  }
+
+
+  /// Vários clientes fazem as consultas ao mesmo tempo, enquanto
+  /// outro altera objetos fora do caminho delas
+  fn clientes_concorrentes( endereco: Endereco) {
+    let mut threads = Vec::new();
+    for k in 0..8 {
+      let endereco = endereco.clone();
+      threads.push(thread::spawn(move || {
+        let mut cliente = Cliente::conecta(&endereco).unwrap();
+        for rodada in 0..25 {
+          for (consulta, esperado) in consultas().iter() {
+            let resposta = cliente.pergunta(consulta).unwrap();
+            assert_eq!(&resposta, esperado, "cliente {} rodada {}: {}", k, rodada, consulta);
+          }
+        }
+        assert_eq!(cliente.pergunta("quit").unwrap(), "ok");
+      }));
+    }
+
+    let escritor = {
+      let endereco = endereco.clone();
+      thread::spawn(move || {
+        let mut cliente = Cliente::conecta(&endereco).unwrap();
+        for i in 0..200 {
+          assert_eq!(cliente.pergunta(&format!("put longe{} ponto {} 1000", i % 3, 1000 + i)).unwrap(), "ok");
+          assert_eq!(cliente.pergunta(&format!("del longe{}", i % 3)).unwrap(), "ok");
+        }
+      })
+    };
+
+    for t in threads {
+      t.join().unwrap();
+    }
+    escritor.join().unwrap();
+  }
+
+
+  #[test]
+  fn consultas_concorrentes_por_tcp() {
+    let servico = mundo();
+    let endereco = inicia(&servico, Endereco::Tcp("127.0.0.1:0".to_string()));
+    assert!(endereco.starts_with("127.0.0.1:") && !endereco.ends_with(":0"));
+
+    clientes_concorrentes(Endereco::Tcp(endereco));
+    assert_eq!(servico.le().len(), 17);
+  }
+
+
+  #[cfg(unix)]
+  #[test]
+  fn consultas_concorrentes_por_socket_unix() {
+    let caminho = std::env::temp_dir().join(format!("jeotry-servico-{}.sock", std::process::id()));
+    let servico = mundo();
+    inicia(&servico, Endereco::Unix(caminho.clone()));
+
+    clientes_concorrentes(Endereco::Unix(caminho.clone()));
+    assert_eq!(servico.le().len(), 17);
+    let _ = std::fs::remove_file(&caminho);
+  }
+
+
+  #[test]
+  fn conexoes_alem_do_limite_sao_recusadas() {
+    let mut servico = mundo();
+    servico.limite_conexoes = 2;
+    let endereco = Endereco::Tcp(inicia(&servico, Endereco::Tcp("127.0.0.1:0".to_string())));
+
+    let mut a = Cliente::conecta(&endereco).unwrap();
+    let mut b = Cliente::conecta(&endereco).unwrap();
+    assert_eq!(a.pergunta("count").unwrap(), "ok 17");
+    assert_eq!(b.pergunta("count").unwrap(), "ok 17");
+
+    // a terceira conexão recebe o erro e é encerrada
+    let mut recusada = Cliente::conecta(&endereco).unwrap();
+    let mut resposta = String::new();
+    recusada.leitor.read_line(&mut resposta).unwrap();
+    assert_eq!(resposta, "erro conexões demais, tente mais tarde\n");
+    assert_eq!(recusada.leitor.read_line(&mut resposta).unwrap(), 0);
+
+    // a vaga volta quando uma conexão termina
+    assert_eq!(a.pergunta("quit").unwrap(), "ok");
+    drop(a);
+    let mut c = (0..200)
+      .find_map(|_| {
+        let mut c = Cliente::conecta(&endereco).unwrap();
+        match c.pergunta("count") {
+          Ok(r) if r == "ok 17" => Some(c),
+          _                     => {
+            thread::sleep(std::time::Duration::from_millis(10));
+            None
+          },
+        }
+      })
+      .expect("a vaga não foi liberada");
+    assert_eq!(c.pergunta("quit").unwrap(), "ok");
+    assert_eq!(b.pergunta("quit").unwrap(), "ok");
+  }
+
+
+  #[test]
+  fn linhas_longas_encerram_a_conexao() {
+    let servico = mundo();
+    let atende = |entrada: &[u8]| {
+      let mut saida = Vec::new();
+      let resultado = servico.atende(entrada, &mut saida);
+      (resultado.is_ok(), String::from_utf8(saida).unwrap())
+    };
+
+    // uma linha no limite ainda é respondida
+    let mut entrada = b"count\r\n".to_vec();
+    entrada.extend(vec![b'x'; LIMITE_LINHA]);
+    entrada.extend(b"\n\xff\ncount");
+    let erro = format!("erro comando ou argumentos inválidos '{}', use help", "x".repeat(LIMITE_LINHA));
+    assert_eq!(atende(&entrada), (true, format!("ok 17\n{}\nerro linha não é UTF-8\nok 17\n", erro)));
+
+    // uma linha maior encerra a conexão sem ser lida até o fim
+    let mut entrada = b"count\n".to_vec();
+    entrada.extend(vec![b'x'; LIMITE_LINHA + 1]);
+    entrada.extend(b"\ncount\n");
+    assert_eq!(atende(&entrada), (false, format!("ok 17\nerro linha maior que {} bytes\n", LIMITE_LINHA)));
+  }
+
+
+  #[test]
+  fn nomes_com_virgulas_sao_citados_nas_colisoes() {
+    let servico = Servico::default();
+    assert_eq!(servico.responde("put a,b bloco 0 0 2 2"), "ok");
+    assert_eq!(servico.responde("put q\" bloco 1 1 3 3"), "ok");
+    assert_eq!(servico.responde("put c bloco 3 0 5 2"), "ok");
+    assert_eq!(servico.responde("collisions"), "ok \"a,b\",\"q\"\"\" \"q\"\"\",c");
+  }
+
+
+  #[test]
+  fn enderecos_que_nao_sao_locais_sao_recusados() {
+    let erro = Servico::default().escuta(&Endereco::Tcp("8.8.8.8:0".to_string()), |_| panic!("não deveria escutar"));
+    assert_eq!(erro.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+  }
+}
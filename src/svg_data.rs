@@ -0,0 +1,498 @@
+/// Exportação de objetos e cenas para imagens SVG.
+///
+/// Os objetos são acumulados em coordenadas do plano e só
+/// são convertidos na escrita do documento, quando os limites
+/// do desenho já são conhecidos. O eixo y é invertido para que
+/// a imagem siga a mesma convenção dos módulos de data, com a
+/// origem embaixo e à esquerda.
+///
+/// As espessuras de linha e os tamanhos de texto são dados em
+/// pixels e não dependem da escala do desenho.
+pub use super::object_data::{
+    Ponto as Ponto,
+    Vetor as Vetor,
+    Bloco as Bloco,
+    Quadrilatero as Quadrilatero,
+    Circulo as Circulo,
+    QuadOrientado as QuadOrientado,
+    Segmento as Segmento,
+    Poligono as Poligono,
+    Forma as Forma,
+};
+use super::scene_data::{
+    Cena,
+    Elemento,
+};
+use std::fmt;
+
+
+// Estruturas
+/// Estilo de desenho, as cores aceitam qualquer valor do SVG
+/// como `"red"`, `"#ff0000"` ou `"none"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estilo {
+  /// Cor das linhas
+  pub traco: String,
+
+  /// Cor do interior
+  pub preenchimento: String,
+
+  /// Espessura das linhas em pixels
+  pub espessura: f64,
+
+  /// Opacidade de 0 a 1
+  pub opacidade: f64,
+
+  /// Tamanho do texto dos rótulos em pixels
+  pub fonte: f64
+}
+
+
+/// Primitiva acumulada em coordenadas do plano
+#[derive(Debug, Clone)]
+enum Primitiva {
+  Retangulo((f64, f64), (f64, f64)),
+  Circulo((f64, f64), f64),
+  Ponto((f64, f64)),
+  Poligono(Vec<(f64, f64)>),
+  Linha((f64, f64), (f64, f64)),
+  Seta((f64, f64), (f64, f64)),
+  Texto((f64, f64), String)
+}
+
+
+/// Documento SVG em construção
+#[derive(Debug, Clone)]
+pub struct Svg {
+  /// Pixels por unidade do plano
+  pub escala: f64,
+
+  /// Espaço ao redor do desenho, em unidades do plano
+  pub margem: f64,
+
+  /// Cor de fundo, `None` deixa o fundo transparente
+  pub fundo: Option<String>,
+
+  /// Primitivas na ordem de desenho
+  primitivas: Vec<(Primitiva, Estilo)>
+}
+
+
+// Métodos relacionados ao estilo
+impl Estilo {
+
+
+  /// Gera um novo estilo através das cores de linha e de interior
+  pub fn new( traco: &str, preenchimento: &str) -> Self {
+    Self {
+      traco: traco.to_string(),
+      preenchimento: preenchimento.to_string(),
+      ..Estilo::default()
+    }
+  }
+
+
+  /// Retorna o estilo com outra espessura de linha
+  pub fn espessura( mut self, espessura: f64) -> Self {
+    self.espessura = espessura;
+    return self;
+  }
+
+
+  /// Retorna o estilo com outra opacidade
+  pub fn opacidade( mut self, opacidade: f64) -> Self {
+    self.opacidade = opacidade;
+    return self;
+  }
+
+
+  /// Retorna o estilo com outro tamanho de texto
+  pub fn fonte( mut self, fonte: f64) -> Self {
+    self.fonte = fonte;
+    return self;
+  }
+
+
+  /// Atributos de linha e interior do elemento
+  fn atributos( &self ) -> String {
+    format!(
+      "stroke=\"{}\" fill=\"{}\" stroke-width=\"{}\" opacity=\"{}\" vector-effect=\"non-scaling-stroke\"",
+      escapa(&self.traco), escapa(&self.preenchimento), self.espessura, self.opacidade
+    )
+  }
+}
+
+impl Default for Estilo {
+  fn default() -> Self {
+    Self {
+      traco: "black".to_string(),
+      preenchimento: "none".to_string(),
+      espessura: 1.0,
+      opacidade: 1.0,
+      fonte: 12.0
+    }
+  }
+}
+
+
+// Métodos relacionados ao documento
+impl Svg {
+
+
+  /// Gera um novo documento vazio com 20 pixels por unidade
+  pub fn new() -> Self {
+    Self {
+      escala: 20.0,
+      margem: 1.0,
+      fundo: Some("white".to_string()),
+      primitivas: Vec::new()
+    }
+  }
+
+
+  /// Desenha um bloco
+  pub fn bloco( &mut self, b: Bloco, e: &Estilo) {
+    let p1 = (b.p1.x as f64, b.p1.y as f64);
+    let p2 = (b.p2.x as f64, b.p2.y as f64);
+    self.primitivas.push((Primitiva::Retangulo(p1, p2), e.clone()));
+  }
+
+
+  /// Desenha um quadrilátero
+  pub fn quad( &mut self, q: Quadrilatero, e: &Estilo) {
+    self.bloco(q.into_block(), e);
+  }
+
+
+  /// Desenha um círculo
+  pub fn circulo( &mut self, c: Circulo, e: &Estilo) {
+    let p = (c.p.x as f64, c.p.y as f64);
+    self.primitivas.push((Primitiva::Circulo(p, c.r), e.clone()));
+  }
+
+
+  /// Desenha um quadrilátero orientado
+  pub fn orientado( &mut self, o: QuadOrientado, e: &Estilo) {
+    let cantos = o.corners().to_vec();
+    self.primitivas.push((Primitiva::Poligono(cantos), e.clone()));
+  }
+
+
+  /// Desenha um segmento
+  pub fn segmento( &mut self, s: Segmento, e: &Estilo) {
+    let p1 = (s.p1.x as f64, s.p1.y as f64);
+    let p2 = (s.p2.x as f64, s.p2.y as f64);
+    self.primitivas.push((Primitiva::Linha(p1, p2), e.clone()));
+  }
+
+
+  /// Desenha um polígono
+  pub fn poligono( &mut self, p: &Poligono, e: &Estilo) {
+    let pontos = p.pontos.iter().map(|v| (v.x as f64, v.y as f64)).collect();
+    self.primitivas.push((Primitiva::Poligono(pontos), e.clone()));
+  }
+
+
+  /// Desenha um vetor como uma seta partindo da origem dada
+  pub fn vetor( &mut self, origem: Ponto, v: Vetor, e: &Estilo) {
+    let p1 = (origem.x as f64, origem.y as f64);
+    let p2 = (p1.0 + v.x as f64, p1.1 + v.y as f64);
+    self.primitivas.push((Primitiva::Seta(p1, p2), e.clone()));
+  }
+
+
+  /// Desenha um ponto como um pequeno círculo na cor da linha
+  pub fn ponto( &mut self, p: Ponto, e: &Estilo) {
+    self.primitivas.push((Primitiva::Ponto((p.x as f64, p.y as f64)), e.clone()));
+  }
+
+
+  /// Desenha qualquer forma
+  pub fn forma( &mut self, f: &Forma, e: &Estilo) {
+    match f {
+      Forma::Bloco(b)        => self.bloco(*b, e),
+      Forma::Quadrilatero(q) => self.quad(*q, e),
+      Forma::Circulo(c)      => self.circulo(*c, e),
+      Forma::Orientado(o)    => self.orientado(*o, e),
+      Forma::Segmento(s)     => self.segmento(*s, e),
+      Forma::Poligono(p)     => self.poligono(p, e),
+    }
+  }
+
+
+  /// Escreve um texto com a esquerda-inferior na coordenada dada
+  pub fn rotulo( &mut self, p: (f64, f64), texto: &str, e: &Estilo) {
+    self.primitivas.push((Primitiva::Texto(p, texto.to_string()), e.clone()));
+  }
+
+
+  /// Desenha todos os elementos de uma cena com seus nomes, os
+  /// vetores partem da origem do plano
+  pub fn cena( &mut self, cena: &Cena, e: &Estilo) {
+    for (nome, el) in cena.iter() {
+      let posicao = match el {
+        Elemento::Ponto(p) => {
+          self.ponto(*p, e);
+          (p.x as f64, p.y as f64)
+        },
+
+        Elemento::Vetor(v) => {
+          self.vetor(Ponto::new(0, 0), *v, e);
+          (v.x as f64, v.y as f64)
+        },
+
+        Elemento::Forma(f) => {
+          self.forma(f, e);
+          ancora(f)
+        },
+      };
+
+      self.rotulo(posicao, nome, e);
+    }
+  }
+
+
+  /// Escreve o documento em um arquivo
+  pub fn salvar<P: AsRef<std::path::Path>>( &self, caminho: P) -> std::io::Result<()> {
+    std::fs::write(caminho, self.to_string())
+  }
+
+
+  /// Retorna os limites do desenho: esquerda-inferior e direita-superior
+  fn limites( &self ) -> ((f64, f64), (f64, f64)) {
+    let mut min = (f64::MAX, f64::MAX);
+    let mut max = (f64::MIN, f64::MIN);
+    let mut inclui = |p: (f64, f64)| {
+      min = (min.0.min(p.0), min.1.min(p.1));
+      max = (max.0.max(p.0), max.1.max(p.1));
+    };
+
+    for (p, _) in self.primitivas.iter() {
+      match p {
+        Primitiva::Retangulo(a, b) |
+        Primitiva::Linha(a, b) |
+        Primitiva::Seta(a, b) => {
+          inclui(*a);
+          inclui(*b);
+        },
+        Primitiva::Circulo(c, r) => {
+          inclui((c.0 - r, c.1 - r));
+          inclui((c.0 + r, c.1 + r));
+        },
+        Primitiva::Poligono(pontos) => {
+          pontos.iter().for_each(|v| inclui(*v));
+        },
+        Primitiva::Ponto(a) |
+        Primitiva::Texto(a, _) => inclui(*a),
+      }
+    }
+
+    // documento vazio
+    if min.0 > max.0 {
+      return ((0.0, 0.0), (0.0, 0.0));
+    }
+
+    return (min, max);
+  }
+}
+
+impl Default for Svg {
+  fn default() -> Self {
+    Svg::new()
+  }
+}
+
+
+impl fmt::Display for Svg {
+  fn fmt( &self, f: &mut fmt::Formatter) -> fmt::Result {
+    let (min, max) = self.limites();
+    let x = min.0 - self.margem;
+    let y = inv(max.1 + self.margem);
+    let w = max.0 - min.0 + 2.0 * self.margem;
+    let h = max.1 - min.1 + 2.0 * self.margem;
+
+    writeln!(
+      f,
+      "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">",
+      w * self.escala, h * self.escala, x, y, w, h
+    )?;
+
+    if let Some(fundo) = &self.fundo {
+      writeln!(f, "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>", x, y, w, h, escapa(fundo))?;
+    }
+
+    // o y de cada coordenada é negado para que cresça para cima
+    for (p, e) in self.primitivas.iter() {
+      match p {
+        Primitiva::Retangulo(a, b) => {
+          writeln!(
+            f,
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>",
+            a.0, inv(b.1), b.0 - a.0, b.1 - a.1, e.atributos()
+          )?;
+        },
+
+        Primitiva::Circulo(c, r) => {
+          writeln!(f, "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" {}/>", c.0, inv(c.1), r, e.atributos())?;
+        },
+
+        Primitiva::Ponto(c) => {
+          // o raio tem tamanho fixo em pixels
+          let r = (1.5 + 1.5 * e.espessura) / self.escala;
+          writeln!(
+            f,
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" stroke=\"none\" fill=\"{}\" opacity=\"{}\"/>",
+            c.0, inv(c.1), r, escapa(&e.traco), e.opacidade
+          )?;
+        },
+
+        Primitiva::Poligono(pontos) => {
+          writeln!(f, "  <polygon points=\"{}\" {}/>", lista(pontos), e.atributos())?;
+        },
+
+        Primitiva::Linha(a, b) => {
+          writeln!(
+            f,
+            "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {}/>",
+            a.0, inv(a.1), b.0, inv(b.1), e.atributos()
+          )?;
+        },
+
+        Primitiva::Seta(a, b) => {
+          writeln!(
+            f,
+            "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {}/>",
+            a.0, inv(a.1), b.0, inv(b.1), e.atributos()
+          )?;
+
+          // a ponta tem tamanho fixo em pixels
+          let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+          let len = dx.hypot(dy);
+          if len > 0.0 {
+            let t = (4.0 + 3.0 * e.espessura) / self.escala;
+            let (ux, uy) = (dx / len * t, dy / len * t);
+            let ponta = [
+              *b,
+              (b.0 - 2.0 * ux - uy, b.1 - 2.0 * uy + ux),
+              (b.0 - 2.0 * ux + uy, b.1 - 2.0 * uy - ux),
+            ];
+            writeln!(
+              f,
+              "  <polygon points=\"{}\" stroke=\"none\" fill=\"{}\" opacity=\"{}\"/>",
+              lista(&ponta), escapa(&e.traco), e.opacidade
+            )?;
+          }
+        },
+
+        Primitiva::Texto(a, texto) => {
+          writeln!(
+            f,
+            "  <text x=\"{}\" y=\"{}\" font-size=\"{}\" font-family=\"monospace\" fill=\"{}\" opacity=\"{}\">{}</text>",
+            a.0, inv(a.1), e.fonte / self.escala, escapa(&e.traco), e.opacidade, escapa(texto)
+          )?;
+        },
+      }
+    }
+
+    writeln!(f, "</svg>")
+  }
+}
+
+
+/// Coordenada onde o nome de uma forma é escrito
+fn ancora( f: &Forma) -> (f64, f64) {
+  match f {
+    Forma::Bloco(b)        => (b.p1.x as f64, b.p2.y as f64),
+    Forma::Quadrilatero(q) => (q.p.x as f64, (q.p.y + q.ty) as f64),
+    Forma::Circulo(c)      => (c.p.x as f64, c.p.y as f64 + c.r),
+    Forma::Orientado(o)    => o.c,
+    Forma::Segmento(s)     => (s.p2.x as f64, s.p2.y as f64),
+    Forma::Poligono(p)     => match p.pontos.first() {
+      Some(v) => (v.x as f64, v.y as f64),
+      None    => (0.0, 0.0),
+    },
+  }
+}
+
+
+/// Lista de coordenadas no formato do atributo `points`
+fn lista( pontos: &[(f64, f64)]) -> String {
+  pontos.iter()
+    .map(|p| format!("{},{}", p.0, inv(p.1)))
+    .collect::<Vec<String>>()
+    .join(" ")
+}
+
+
+/// Inverte o eixo y sem produzir `-0`
+fn inv( y: f64) -> f64 {
+  0.0 - y
+}
+
+
+/// Escapa os caracteres especiais do XML
+fn escapa( texto: &str) -> String {
+  texto
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+
+
+  #[test]
+  fn cena_pequena_com_nomes_escapados() {
+    let mut cena = Cena::new();
+    cena.inserir("a<b&\"c\"", Elemento::Forma(Forma::Bloco(Bloco::new((0, 0), (2, 1))))).unwrap();
+    cena.inserir("c>", Elemento::Forma(Forma::Circulo(Circulo::new((3, 2), 1.0)))).unwrap();
+    cena.inserir("p", Elemento::Ponto(Ponto::new(-1, -2))).unwrap();
+
+    let mut svg = Svg::new();
+    svg.cena(&cena, &Estilo::new("blue", "none"));
+
+    // o desenho vai de (-1, -2) a (4, 3), a margem soma uma
+    // unidade de cada lado e o topo passa a ser o y negativo
+    let atributos = "stroke=\"blue\" fill=\"none\" stroke-width=\"1\" opacity=\"1\" vector-effect=\"non-scaling-stroke\"";
+    let texto = "font-size=\"0.6\" font-family=\"monospace\" fill=\"blue\" opacity=\"1\"";
+    let esperado = [
+      "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"140\" height=\"140\" viewBox=\"-2 -4 7 7\">".to_string(),
+      "  <rect x=\"-2\" y=\"-4\" width=\"7\" height=\"7\" fill=\"white\"/>".to_string(),
+      format!("  <rect x=\"0\" y=\"-1\" width=\"2\" height=\"1\" {}/>", atributos),
+      format!("  <text x=\"0\" y=\"-1\" {}>a&lt;b&amp;&quot;c&quot;</text>", texto),
+      format!("  <circle cx=\"3\" cy=\"-2\" r=\"1\" {}/>", atributos),
+      format!("  <text x=\"3\" y=\"-3\" {}>c&gt;</text>", texto),
+      "  <circle cx=\"-1\" cy=\"2\" r=\"0.15\" stroke=\"none\" fill=\"blue\" opacity=\"1\"/>".to_string(),
+      format!("  <text x=\"-1\" y=\"2\" {}>p</text>", texto),
+      "</svg>".to_string(),
+    ];
+
+    assert_eq!(svg.to_string(), esperado.join("\n") + "\n");
+  }
+
+
+  #[test]
+  fn documento_vazio_e_cores_escapadas() {
+    let mut svg = Svg::new();
+    svg.fundo = None;
+    assert_eq!(
+      svg.to_string(),
+      "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"40\" height=\"40\" viewBox=\"-1 -1 2 2\">\n</svg>\n"
+    );
+
+    // a escala muda o tamanho em pixels mas não o viewBox
+    svg.escala = 10.0;
+    svg.fundo = Some("\"><script>".to_string());
+    svg.segmento(Segmento::new((0, 0), (3, 4)), &Estilo::new("red", "none"));
+    let documento = svg.to_string();
+    let linhas: Vec<&str> = documento.lines().collect();
+    assert_eq!(linhas[0], "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"50\" height=\"60\" viewBox=\"-1 -5 5 6\">");
+    assert_eq!(linhas[1], "  <rect x=\"-1\" y=\"-5\" width=\"5\" height=\"6\" fill=\"&quot;&gt;&lt;script&gt;\"/>");
+    assert!(linhas[2].starts_with("  <line x1=\"0\" y1=\"0\" x2=\"3\" y2=\"-4\" stroke=\"red\""));
+    assert!(!documento.contains("<script>"));
+  }
+}
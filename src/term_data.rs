@@ -0,0 +1,315 @@
+/// Desenho de objetos no terminal, em uma grade de caracteres.
+///
+/// Cada célula da grade corresponde a uma pequena região do
+/// plano e recebe o símbolo do objeto que contém o seu centro.
+/// Células cobertas por mais de um objeto são destacadas, o
+/// que torna as colisões visíveis sem desenhos feitos à mão.
+///
+/// A grade segue a convenção dos módulos de data, com o eixo y
+/// crescendo para cima, e é acompanhada dos valores dos eixos e
+/// de uma legenda com o nome de cada objeto.
+pub use super::object_data::{
+    Ponto as Ponto,
+    Vetor as Vetor,
+    Bloco as Bloco,
+    Segmento as Segmento,
+    Forma as Forma,
+};
+use super::scene_data::{
+    Cena,
+    Elemento,
+};
+use std::fmt;
+
+
+/// Símbolos atribuídos aos objetos, em ordem
+static SIMBOLOS: &str = "123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Cores ANSI atribuídas aos objetos, em ordem
+static CORES: [&str; 6] = ["36", "32", "33", "34", "35", "96"];
+
+/// Símbolo e cor ANSI das células sobrepostas
+static SOBREPOSTO: (char, &str) = ('#', "1;97;41");
+
+/// Limite de células em cada eixo, para escalas exageradas
+static LIMITE: usize = 400;
+
+
+// Estruturas
+/// Grade de caracteres com objetos nomeados
+#[derive(Debug, Clone)]
+pub struct Terminal {
+  /// Colunas por unidade do plano
+  pub escala_x: f64,
+
+  /// Linhas por unidade do plano
+  pub escala_y: f64,
+
+  /// Usa cores ANSI nos símbolos
+  pub cores: bool,
+
+  /// Região desenhada, `None` ajusta a região aos objetos
+  pub regiao: Option<Bloco>,
+
+  /// Objetos na ordem de desenho
+  formas: Vec<(String, Forma)>
+}
+
+
+// Métodos relacionados ao terminal
+impl Terminal {
+
+
+  /// Gera uma nova grade vazia, com duas colunas por unidade
+  /// para compensar a altura dos caracteres
+  pub fn new() -> Self {
+    Self {
+      escala_x: 2.0,
+      escala_y: 1.0,
+      cores: true,
+      regiao: None,
+      formas: Vec::new()
+    }
+  }
+
+
+  /// Adiciona um objeto com nome
+  pub fn forma( &mut self, nome: &str, f: Forma) {
+    self.formas.push((nome.to_string(), f));
+  }
+
+
+  /// Adiciona um ponto com nome
+  pub fn ponto( &mut self, nome: &str, p: Ponto) {
+    self.forma(nome, Forma::Segmento(Segmento::from(p, p)));
+  }
+
+
+  /// Adiciona todos os elementos de uma cena, os vetores
+  /// são desenhados como segmentos partindo da origem
+  pub fn cena( &mut self, cena: &Cena) {
+    for (nome, e) in cena.iter() {
+      match e {
+        Elemento::Ponto(p) => self.ponto(nome, *p),
+        Elemento::Vetor(v) => {
+          let origem = Ponto::new(0, 0);
+          self.forma(nome, Forma::Segmento(Segmento::from(origem, origem.sum(*v))));
+        },
+        Elemento::Forma(f) => self.forma(nome, f.clone()),
+      }
+    }
+  }
+
+
+  /// Retorna a região desenhada, com limites inteiros
+  fn limites( &self ) -> (i32, i32, i32, i32) {
+    if let Some(b) = self.regiao {
+      return (b.p1.x, b.p1.y, b.p2.x, b.p2.y);
+    }
+
+    let mut min = (f64::MAX, f64::MAX);
+    let mut max = (f64::MIN, f64::MIN);
+    for (_, f) in self.formas.iter() {
+      let (a, b) = f.limites();
+      min = (min.0.min(a.0), min.1.min(a.1));
+      max = (max.0.max(b.0), max.1.max(b.1));
+    }
+
+    if self.formas.is_empty() {
+      return (0, 0, 1, 1);
+    }
+
+    // objetos sem largura ou altura ainda ocupam uma célula
+    let (x1, y1) = (min.0.floor() as i32, min.1.floor() as i32);
+    let (x2, y2) = (max.0.ceil() as i32, max.1.ceil() as i32);
+    return (x1, y1, x2.max(x1.saturating_add(1)), y2.max(y1.saturating_add(1)));
+  }
+}
+
+impl Default for Terminal {
+  fn default() -> Self {
+    Terminal::new()
+  }
+}
+
+
+impl fmt::Display for Terminal {
+  fn fmt( &self, f: &mut fmt::Formatter) -> fmt::Result {
+    let (x1, y1, x2, y2) = self.limites();
+    // a diferença é feita em reais, regiões do tamanho de i32 estouram
+    let (largura, altura) = (x2 as f64 - x1 as f64, y2 as f64 - y1 as f64);
+
+    // regiões que passariam do limite têm as duas escalas reduzidas
+    // na mesma proporção, para que caibam inteiras sem distorção
+    let reducao = (largura * self.escala_x / LIMITE as f64)
+      .max(altura * self.escala_y / LIMITE as f64)
+      .max(1.0);
+    let (cx, cy) = (reducao / self.escala_x, reducao / self.escala_y);
+    let colunas = ((largura / cx).ceil() as usize).clamp(1, LIMITE);
+    let linhas = ((altura / cy).ceil() as usize).clamp(1, LIMITE);
+
+    // segmentos e pontos não têm área, são desenhados por proximidade
+    let espessura = 0.5 * cx.max(cy);
+
+    let simbolos: Vec<char> = SIMBOLOS.chars().collect();
+    let mut sobreposicao = false;
+
+    // valores do eixo y, um por linha que contenha um inteiro
+    let rotulos_y: Vec<String> = (0..linhas)
+      .map(|l| {
+        let topo = y2 as f64 - l as f64 * cy;
+        inteiro_em(topo - cy, topo).map_or(String::new(), |k| k.to_string())
+      })
+      .collect();
+    let margem = rotulos_y.iter().map(|r| r.len()).max().unwrap_or(0);
+
+    for (l, rotulo) in rotulos_y.iter().enumerate() {
+      write!(f, "{:>w$} |", rotulo, w = margem)?;
+
+      let y = y2 as f64 - (l as f64 + 0.5) * cy;
+      for c in 0..colunas {
+        let x = x1 as f64 + (c as f64 + 0.5) * cx;
+
+        let mut acertos = self.formas.iter().enumerate().filter(|(_, (_, forma))| {
+          match forma {
+            Forma::Segmento(s) => s.diff_f((x, y)) <= espessura,
+            _                  => forma.contains((x, y)),
+          }
+        });
+
+        let celula = match (acertos.next(), acertos.next()) {
+          (None, _)            => None,
+          (Some((i, _)), None) => {
+            Some((simbolos[i % simbolos.len()], CORES[i % CORES.len()]))
+          },
+          (Some(_), Some(_)) => {
+            sobreposicao = true;
+            Some(SOBREPOSTO)
+          },
+        };
+
+        match celula {
+          None                         => write!(f, " ")?,
+          Some((s, cor)) if self.cores => write!(f, "\x1b[{}m{}\x1b[0m", cor, s)?,
+          Some((s, _))                 => write!(f, "{}", s)?,
+        }
+      }
+
+      writeln!(f)?;
+    }
+
+    // eixo x, os valores só são escritos quando há espaço
+    writeln!(f, "{:>w$} +{}", "", "-".repeat(colunas), w = margem)?;
+    let mut rotulos_x = String::new();
+    for c in 0..colunas {
+      let esquerda = x1 as f64 + c as f64 * cx;
+      if let Some(k) = inteiro_em(esquerda, esquerda + cx) {
+        let texto = k.to_string();
+        if rotulos_x.chars().count() <= c && c + texto.len() <= colunas + 1 {
+          let falta = c - rotulos_x.chars().count();
+          rotulos_x.push_str(&" ".repeat(falta));
+          rotulos_x.push_str(&texto);
+          rotulos_x.push(' ');
+        }
+      }
+    }
+    writeln!(f, "{:>w$}  {}", "", rotulos_x.trim_end(), w = margem)?;
+
+    // legenda
+    for (i, (nome, _)) in self.formas.iter().enumerate() {
+      let s = simbolos[i % simbolos.len()];
+      if self.cores {
+        writeln!(f, "  \x1b[{}m{}\x1b[0m {}", CORES[i % CORES.len()], s, nome)?;
+      } else {
+        writeln!(f, "  {} {}", s, nome)?;
+      }
+    }
+
+    if sobreposicao {
+      if self.cores {
+        writeln!(f, "  \x1b[{}m{}\x1b[0m sobreposição", SOBREPOSTO.1, SOBREPOSTO.0)?;
+      } else {
+        writeln!(f, "  {} sobreposição", SOBREPOSTO.0)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+
+/// Retorna o menor inteiro no intervalo [a, b), se existir
+fn inteiro_em( a: f64, b: f64) -> Option<i64> {
+  let k = (a - 1e-9).ceil();
+  if k < b - 1e-9 {
+    return Some(k as i64);
+  }
+
+  return None;
+}
+
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+
+
+  #[test]
+  fn grade_pequena_com_eixos_e_sobreposicao() {
+    let mut t = Terminal::new();
+    t.cores = false;
+    t.forma("a", Forma::Bloco(Bloco::new((0, 0), (2, 2))));
+    t.forma("b", Forma::Bloco(Bloco::new((1, 1), (3, 2))));
+
+    // duas colunas por unidade, os valores do eixo y marcam a
+    // linha que contém o inteiro e os do eixo x a coluna
+    let esperado = [
+      "1 |11##22",
+      "0 |1111  ",
+      "  +------",
+      "   0 1 2",
+      "  1 a",
+      "  2 b",
+      "  # sobreposição",
+    ];
+    assert_eq!(t.to_string(), esperado.join("\n") + "\n");
+
+    // sem sobreposição não há destaque na legenda
+    let mut sozinho = Terminal::new();
+    sozinho.cores = false;
+    sozinho.forma("q", Forma::Bloco(Bloco::new((-1, -1), (0, 0))));
+    assert_eq!(sozinho.to_string(), "-1 |11\n   +--\n    -1\n  1 q\n");
+
+    // com cores, o destaque também aparece na grade
+    t.cores = true;
+    let colorido = t.to_string();
+    assert!(colorido.contains("\x1b[1;97;41m#\x1b[0m\x1b[1;97;41m#\x1b[0m"));
+    assert!(colorido.ends_with("  \x1b[1;97;41m#\x1b[0m sobreposição\n"));
+  }
+
+
+  #[test]
+  fn cenas_grandes_cabem_inteiras_na_grade() {
+    let mut t = Terminal::new();
+    t.cores = false;
+    t.forma("a", Forma::Bloco(Bloco::new((0, 0), (10, 10))));
+    t.forma("b", Forma::Bloco(Bloco::new((990, 0), (1000, 10))));
+
+    // 2000 colunas seriam necessárias, as duas escalas caem
+    // para um quinto e a altura passa a ter duas linhas
+    let desenho = t.to_string();
+    let linhas: Vec<&str> = desenho.lines().take(2).collect();
+    assert_eq!(linhas, ["5 |1111".to_string() + &" ".repeat(392) + "2222", "0 |1111".to_string() + &" ".repeat(392) + "2222"]);
+
+    // regiões do tamanho de i32 também cabem
+    t.forma("c", Forma::Bloco(Bloco::new((i32::MIN, i32::MIN), (i32::MAX, i32::MAX))));
+    let desenho = t.to_string();
+    let grade: Vec<&str> = desenho.lines().take_while(|l| l.contains('|')).collect();
+    assert_eq!(grade.len(), 200);
+    for linha in grade.iter() {
+      let celulas = linha.split('|').nth(1).unwrap();
+      assert!(celulas.len() == 400 && !celulas.contains(' '), "{}", linha);
+    }
+  }
+}
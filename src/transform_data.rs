@@ -0,0 +1,478 @@
+/// Transformações afins no plano, descritas por uma matriz 3×3
+/// em coordenadas homogêneas.
+///
+/// As transformações podem ser compostas, invertidas e aplicadas
+/// a todos os tipos dos módulos de data, coordenadas inteiras
+/// são arredondadas após a transformação.
+///
+/// Objetos alinhados aos eixos, como o bloco e o quadrilátero,
+/// só continuam sendo do mesmo tipo quando a transformação
+/// preserva os eixos, caso contrário são convertidos para o
+/// objeto geral mais adequado através da `Forma`.
+pub use super::vector_data::{
+    Ponto as Ponto,
+    Vetor as Vetor,
+};
+use super::object_data::{
+    Bloco,
+    Quadrilatero,
+    Circulo,
+    QuadOrientado,
+    Segmento,
+    Poligono,
+    Forma,
+};
+use alloc::vec::Vec;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Real;
+
+
+/// Número de vértices usados quando um círculo deixa de ser
+/// um círculo e passa a ser aproximado por um polígono
+pub static VERTICES_CIRCULO: usize = 32;
+
+/// Tolerância das comparações da matriz, os ângulos são
+/// de precisão simples
+const EPS: f64 = 1e-6;
+
+
+// Estruturas
+/// Transformação afim determinada por uma matriz 3×3.
+///
+/// A última linha é sempre `[0, 0, 1]`, um ponto (x, y) é
+/// levado à `(m00·x + m01·y + m02, m10·x + m11·y + m12)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serde_data::TransformacaoBruta"))]
+pub struct Transformacao {
+  /// Matriz em ordem de linhas
+  pub m: [[f64; 3]; 3]
+}
+
+
+// Métodos relacionados à transformação
+impl Transformacao {
+
+
+  /// Gera uma transformação através da parte linear e da translação
+  pub fn new( a: f64, b: f64, c: f64, d: f64, tx: f64, ty: f64) -> Self {
+    Self {
+      m: [
+        [a,   b,   tx ],
+        [c,   d,   ty ],
+        [0.0, 0.0, 1.0],
+      ]
+    }
+  }
+
+
+  /// Transformação que não altera nada
+  pub fn identidade() -> Self {
+    Transformacao::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+  }
+
+
+  /// Translação por valores reais
+  pub fn translacao( x: f64, y: f64) -> Self {
+    Transformacao::new(1.0, 0.0, 0.0, 1.0, x, y)
+  }
+
+
+  /// Translação através de um vetor
+  pub fn from_vetor( v: Vetor) -> Self {
+    Transformacao::translacao(v.x as f64, v.y as f64)
+  }
+
+
+  /// Rotação anti-horária em torno da origem, em radianos
+  pub fn rotacao( â: f32) -> Self {
+    let (sin, cos) = (â as f64).sin_cos();
+    Transformacao::new(cos, -sin, sin, cos, 0.0, 0.0)
+  }
+
+
+  /// Rotação anti-horária em torno de uma coordenada
+  pub fn rotacao_em( â: f32, c: (f64, f64)) -> Self {
+    Transformacao::translacao(-c.0, -c.1)
+      .seguida(Transformacao::rotacao(â))
+      .seguida(Transformacao::translacao(c.0, c.1))
+  }
+
+
+  /// Escala uniforme em relação à origem
+  pub fn escala( s: f64) -> Self {
+    Transformacao::escala_xy(s, s)
+  }
+
+
+  /// Escala com fatores diferentes em cada eixo
+  pub fn escala_xy( sx: f64, sy: f64) -> Self {
+    Transformacao::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+  }
+
+
+  /// Cisalhamento, x recebe kx·y e y recebe ky·x
+  pub fn cisalhamento( kx: f64, ky: f64) -> Self {
+    Transformacao::new(1.0, kx, ky, 1.0, 0.0, 0.0)
+  }
+
+
+  /// Compõe duas transformações, o resultado aplica
+  /// primeiro esta e depois a transformação `t`
+  pub fn seguida( self, t: Transformacao) -> Self {
+    let mut m = [[0.0; 3]; 3];
+    for (i, linha) in m.iter_mut().enumerate() {
+      for (j, v) in linha.iter_mut().enumerate() {
+        *v = (0..3).map(|k| t.m[i][k] * self.m[k][j]).sum();
+      }
+    }
+
+    Self {
+      m
+    }
+  }
+
+
+  /// Determinante da parte linear
+  pub fn det( self ) -> f64 {
+    self.m[0][0] * self.m[1][1] - self.m[0][1] * self.m[1][0]
+  }
+
+
+  /// Retorna a transformação inversa, se existir
+  pub fn inversa( self ) -> Option<Self> {
+    let det = self.det();
+    if det.abs() < EPS {
+      return None;
+    }
+
+    let [[a, b, tx], [c, d, ty], _] = self.m;
+    let ia =  d / det;
+    let ib = -b / det;
+    let ic = -c / det;
+    let id =  a / det;
+
+    return Some(Transformacao::new(
+      ia, ib, ic, id,
+      -(ia * tx + ib * ty),
+      -(ic * tx + id * ty)
+    ));
+  }
+
+
+  /// Analisa se a transformação leva eixos em eixos, de forma
+  /// que objetos alinhados continuem alinhados
+  pub fn preserva_eixos( self ) -> bool {
+    let [[a, b, _], [c, d, _], _] = self.m;
+    (b.abs() < EPS && c.abs() < EPS) ||
+    (a.abs() < EPS && d.abs() < EPS)
+  }
+
+
+  /// Analisa se a transformação preserva ângulos, sendo
+  /// composta apenas por rotação, escala uniforme,
+  /// reflexão e translação
+  pub fn conforme( self ) -> bool {
+    let [[a, b, _], [c, d, _], _] = self.m;
+    let ortogonal = (a * b + c * d).abs() < EPS;
+    let mesma_escala = ((a * a + c * c) - (b * b + d * d)).abs() < EPS;
+    ortogonal && mesma_escala
+  }
+
+
+  /// Aplica a transformação a uma coordenada real
+  pub fn aplica_f( self, p: (f64, f64)) -> (f64, f64) {
+    let [[a, b, tx], [c, d, ty], _] = self.m;
+    (a * p.0 + b * p.1 + tx, c * p.0 + d * p.1 + ty)
+  }
+
+
+  /// Aplica a parte linear a uma direção real
+  pub fn aplica_direcao( self, v: (f64, f64)) -> (f64, f64) {
+    let [[a, b, _], [c, d, _], _] = self.m;
+    (a * v.0 + b * v.1, c * v.0 + d * v.1)
+  }
+
+
+  /// Aplica a transformação a um ponto
+  pub fn aplica_ponto( self, p: Ponto) -> Ponto {
+    arredonda(self.aplica_f((p.x as f64, p.y as f64)))
+  }
+
+
+  /// Aplica a transformação a um vetor, a translação
+  /// não tem efeito sobre vetores
+  pub fn aplica_vetor( self, v: Vetor) -> Vetor {
+    let (x, y) = self.aplica_direcao((v.x as f64, v.y as f64));
+    Vetor::new(x.round() as i32, y.round() as i32)
+  }
+
+
+  /// Aplica a transformação a um segmento
+  pub fn aplica_segmento( self, s: Segmento) -> Segmento {
+    Segmento::from(self.aplica_ponto(s.p1), self.aplica_ponto(s.p2))
+  }
+
+
+  /// Aplica a transformação a um polígono
+  pub fn aplica_poligono( self, p: &Poligono) -> Poligono {
+    Poligono::new(p.pontos.iter().map(|v| self.aplica_ponto(*v)).collect())
+  }
+
+
+  /// Aplica a transformação a um bloco, o resultado só é
+  /// um bloco quando os eixos são preservados
+  pub fn aplica_bloco( self, b: Bloco) -> Forma {
+    if self.preserva_eixos() {
+      let p1 = self.aplica_ponto(b.p1);
+      let p2 = self.aplica_ponto(b.p2);
+      return Forma::Bloco(Bloco::from(p1, p2));
+    }
+
+    return self.aplica_orient(QuadOrientado::from_block(b, 0.0));
+  }
+
+
+  /// Aplica a transformação a um quadrilátero, o resultado só
+  /// é um quadrilátero quando os eixos são preservados
+  pub fn aplica_quad( self, q: Quadrilatero) -> Forma {
+    match self.aplica_bloco(q.into_block()) {
+      Forma::Bloco(b) => Forma::Quadrilatero(b.into_quad()),
+      f               => f,
+    }
+  }
+
+
+  /// Aplica a transformação a um círculo, o resultado só é
+  /// um círculo quando a transformação é conforme, caso
+  /// contrário o círculo é aproximado por um polígono
+  pub fn aplica_circulo( self, c: Circulo) -> Forma {
+    if self.conforme() {
+      let escala = self.det().abs().sqrt();
+      return Forma::Circulo(Circulo::from(self.aplica_ponto(c.p), c.r * escala));
+    }
+
+    let (cx, cy) = (c.p.x as f64, c.p.y as f64);
+    let mut pontos: Vec<Ponto> = (0..VERTICES_CIRCULO)
+      .map(|i| {
+        let â = i as f64 / VERTICES_CIRCULO as f64 * core::f64::consts::TAU;
+        arredonda(self.aplica_f((cx + c.r * â.cos(), cy + c.r * â.sin())))
+      })
+      .collect();
+
+    // círculos pequenos repetem vértices após o arredondamento
+    pontos.dedup();
+    while pontos.len() > 1 && pontos.first() == pontos.last() {
+      pontos.pop();
+    }
+
+    return Forma::Poligono(Poligono::new(pontos));
+  }
+
+
+  /// Aplica a transformação a um quadrilátero orientado, o
+  /// resultado só continua retangular quando os eixos
+  /// transformados permanecem perpendiculares
+  pub fn aplica_orient( self, o: QuadOrientado) -> Forma {
+    let [ex, ey] = o.eixos();
+    let ax = self.aplica_direcao((ex.0 * o.hx, ex.1 * o.hx));
+    let ay = self.aplica_direcao((ey.0 * o.hy, ey.1 * o.hy));
+
+    if (ax.0 * ay.0 + ax.1 * ay.1).abs() < EPS {
+      let hx = ax.0.hypot(ax.1);
+      let hy = ay.0.hypot(ay.1);
+      let â = ax.1.atan2(ax.0) as f32;
+      return Forma::Orientado(QuadOrientado::new(self.aplica_f(o.c), hx, hy, â));
+    }
+
+    let pontos = o.corners().iter()
+      .map(|c| arredonda(self.aplica_f(*c)))
+      .collect();
+
+    return Forma::Poligono(Poligono::new(pontos));
+  }
+
+
+  /// Aplica a transformação a qualquer forma
+  pub fn aplica( self, f: &Forma) -> Forma {
+    match f {
+      Forma::Bloco(b)        => self.aplica_bloco(*b),
+      Forma::Quadrilatero(q) => self.aplica_quad(*q),
+      Forma::Circulo(c)      => self.aplica_circulo(*c),
+      Forma::Orientado(o)    => self.aplica_orient(*o),
+      Forma::Segmento(s)     => Forma::Segmento(self.aplica_segmento(*s)),
+      Forma::Poligono(p)     => Forma::Poligono(self.aplica_poligono(p)),
+    }
+  }
+}
+
+impl Default for Transformacao {
+  fn default() -> Self {
+    Transformacao::identidade()
+  }
+}
+
+
+/// Arredonda uma coordenada real para o ponto inteiro mais próximo
+fn arredonda( p: (f64, f64)) -> Ponto {
+  Ponto::new(p.0.round() as i32, p.1.round() as i32)
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::random_data::sorteio;
+  use core::f32::consts::FRAC_PI_2;
+
+
+  /// Verifica se duas coordenadas reais são quase iguais, os
+  /// ângulos de precisão simples erram perto de 1e-7
+  fn perto( a: (f64, f64), b: (f64, f64)) -> bool {
+    (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6
+  }
+
+
+  /// Transformação sorteada entre rotações, escalas,
+  /// cisalhamentos e translações compostos
+  fn transformacao( estado: &mut u64) -> Transformacao {
+    let mut real = || (sorteio(estado) % 2001) as f64 / 100.0 - 10.0;
+    Transformacao::rotacao(real() as f32)
+      .seguida(Transformacao::escala_xy(real(), real()))
+      .seguida(Transformacao::cisalhamento(real() / 10.0, real() / 10.0))
+      .seguida(Transformacao::translacao(real(), real()))
+  }
+
+
+  #[test]
+  fn composicao_aplica_na_ordem() {
+    // translada e depois gira, ou gira e depois translada
+    let t = Transformacao::translacao(1.0, 0.0);
+    let r = Transformacao::rotacao(FRAC_PI_2);
+    assert!(perto(t.seguida(r).aplica_f((1.0, 0.0)), (0.0, 2.0)));
+    assert!(perto(r.seguida(t).aplica_f((1.0, 0.0)), (1.0, 1.0)));
+    assert_eq!(t.seguida(r).aplica_ponto(Ponto::new(1, 0)), Ponto::new(0, 2));
+
+    // a rotação em torno de uma coordenada a mantém fixa
+    let em = Transformacao::rotacao_em(FRAC_PI_2, (1.0, 1.0));
+    assert!(perto(em.aplica_f((1.0, 1.0)), (1.0, 1.0)));
+    assert!(perto(em.aplica_f((2.0, 1.0)), (1.0, 2.0)));
+
+    // a translação não move vetores
+    let v = t.seguida(Transformacao::escala(3.0)).aplica_vetor(Vetor::new(1, -2));
+    assert_eq!((v.x, v.y), (3, -6));
+    assert_eq!(Transformacao::default(), Transformacao::identidade());
+
+    // compor é associativo e aplicar em sequência é compor
+    let mut estado = 0x5eed_0028;
+    for _ in 0..200 {
+      let (a, b, c) = (transformacao(&mut estado), transformacao(&mut estado), transformacao(&mut estado));
+      let p = ((sorteio(&mut estado) % 200) as f64 - 100.0, (sorteio(&mut estado) % 200) as f64 - 100.0);
+      let direto = c.aplica_f(b.aplica_f(a.aplica_f(p)));
+      let esquerda = a.seguida(b).seguida(c).aplica_f(p);
+      let direita = a.seguida(b.seguida(c)).aplica_f(p);
+      let escala = direto.0.abs().max(direto.1.abs()).max(1.0);
+      assert!((direto.0 - esquerda.0).abs() < 1e-9 * escala && (direto.1 - esquerda.1).abs() < 1e-9 * escala);
+      assert!((direto.0 - direita.0).abs() < 1e-9 * escala && (direto.1 - direita.1).abs() < 1e-9 * escala);
+      assert!((a.seguida(b).det() - a.det() * b.det()).abs() < 1e-9 * a.det().abs().max(1.0) * b.det().abs().max(1.0));
+    }
+  }
+
+
+  #[test]
+  fn inversa_desfaz_a_transformacao() {
+    let mut estado = 0x5eed_1028;
+    for _ in 0..200 {
+      let t = transformacao(&mut estado);
+      let inversa = match t.inversa() {
+        Some(i) => i,
+        None    => {
+          assert!(t.det().abs() < EPS);
+          continue;
+        },
+      };
+
+      let p = ((sorteio(&mut estado) % 200) as f64 - 100.0, (sorteio(&mut estado) % 200) as f64 - 100.0);
+      let volta = inversa.aplica_f(t.aplica_f(p));
+      let ida = t.aplica_f(inversa.aplica_f(p));
+      let tolerancia = 1e-6 * (1.0 + 1.0 / t.det().abs());
+      assert!((volta.0 - p.0).abs() < tolerancia && (volta.1 - p.1).abs() < tolerancia, "{:?} {:?}", p, volta);
+      assert!((ida.0 - p.0).abs() < tolerancia && (ida.1 - p.1).abs() < tolerancia, "{:?} {:?}", p, ida);
+    }
+
+    // transformações que achatam o plano não têm inversa
+    assert!(Transformacao::escala_xy(2.0, 0.0).inversa().is_none());
+    assert!(Transformacao::cisalhamento(1.0, 1.0).inversa().is_none());
+    assert!(Transformacao::new(1.0, 2.0, 2.0, 4.0, 5.0, 5.0).inversa().is_none());
+
+    // inversas conhecidas
+    let t = Transformacao::translacao(3.0, -2.0).seguida(Transformacao::escala(2.0));
+    let i = t.inversa().unwrap();
+    assert!(perto(i.aplica_f((8.0, -4.0)), (1.0, 0.0)));
+    let r = Transformacao::rotacao_em(0.7, (5.0, 5.0));
+    assert!(perto(r.inversa().unwrap().aplica_f(r.aplica_f((2.0, 9.0))), (2.0, 9.0)));
+  }
+
+
+  #[test]
+  fn formas_alinhadas_mudam_de_tipo_sem_preservar_eixos() {
+    let b = Bloco::new((1, 2), (4, 6));
+
+    // escalas, inclusive negativas, e giros de 90 graus mantêm
+    // blocos e quadriláteros
+    let espelho = Transformacao::escala_xy(2.0, -1.0);
+    assert!(espelho.preserva_eixos() && !espelho.conforme());
+    match espelho.aplica_bloco(b) {
+      Forma::Bloco(r) => assert_eq!((r.p1, r.p2), (Ponto::new(2, -6), Ponto::new(8, -2))),
+      f               => panic!("{:?}", f),
+    }
+    let giro = Transformacao::rotacao(FRAC_PI_2);
+    assert!(giro.preserva_eixos() && giro.conforme());
+    match giro.aplica_quad(b.into_quad()) {
+      Forma::Quadrilatero(q) => assert_eq!((q.p, q.tx, q.ty), (Ponto::new(-6, 1), 4, 3)),
+      f                      => panic!("{:?}", f),
+    }
+
+    // outros ângulos geram quadriláteros orientados
+    let oblíquo = Transformacao::rotacao(0.5).seguida(Transformacao::translacao(10.0, 0.0));
+    assert!(!oblíquo.preserva_eixos() && oblíquo.conforme());
+    match oblíquo.aplica_bloco(b) {
+      Forma::Orientado(o) => {
+        assert!(perto(o.c, oblíquo.aplica_f((2.5, 4.0))));
+        assert!((o.hx - 1.5).abs() < 1e-9 && (o.hy - 2.0).abs() < 1e-9);
+        assert!((o.â - 0.5).abs() < 1e-6);
+      },
+      f                   => panic!("{:?}", f),
+    }
+
+    // e o cisalhamento, que não mantém ângulos retos, polígonos
+    let cisalha = Transformacao::cisalhamento(1.0, 0.0);
+    assert!(!cisalha.preserva_eixos() && !cisalha.conforme());
+    match cisalha.aplica_bloco(b) {
+      Forma::Poligono(p) => assert_eq!(p.pontos, vec![Ponto::new(3, 2), Ponto::new(6, 2), Ponto::new(10, 6), Ponto::new(7, 6)]),
+      f                  => panic!("{:?}", f),
+    }
+
+    // círculos só continuam círculos nas conformes
+    let c = Circulo::new((2, 0), 3.0);
+    match giro.seguida(Transformacao::escala(2.0)).aplica_circulo(c) {
+      Forma::Circulo(r) => assert!(r.p == Ponto::new(0, 4) && (r.r - 6.0).abs() < 1e-9),
+      f                 => panic!("{:?}", f),
+    }
+    match espelho.aplica(&Forma::Circulo(Circulo::new((0, 0), 100.0))) {
+      Forma::Poligono(p) => {
+        assert_eq!(p.pontos.len(), VERTICES_CIRCULO);
+        assert!(p.pontos.iter().all(|v| (v.x.pow(2) as f64 / 4.0 + v.y.pow(2) as f64 - 10000.0).abs() < 500.0));
+      },
+      f                  => panic!("{:?}", f),
+    }
+
+    // orientados somam o ângulo e viram polígonos no cisalhamento
+    let o = QuadOrientado::new((0.0, 0.0), 2.0, 1.0, 0.25);
+    match giro.aplica(&Forma::Orientado(o)) {
+      Forma::Orientado(r) => assert!((r.â - (0.25 + FRAC_PI_2)).abs() < 1e-6),
+      f                   => panic!("{:?}", f),
+    }
+    assert!(matches!(cisalha.aplica_orient(o), Forma::Poligono(_)));
+  }
+}
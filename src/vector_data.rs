@@ -2,8 +2,10 @@
 /// de conceitos geométricos em segundo plano, 
 /// como a coordenada e o vetor. 
 use core::fmt;
-use std::f32::consts::PI as pi;
-use std::fmt::Debug;
+use core::f32::consts::PI as pi;
+use core::fmt::Debug;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Real;
 
 /// ângulo de 90 em radianos
 pub static Â90 :f32 = 0.5 * pi;
@@ -21,7 +23,8 @@ pub static Â360:f32 = 2.0 * pi;
 // estruturas
   /// Ponto no plano cartesiano 
   /// descrito por valores inteiros
-  #[derive(Debug, Clone, Copy)]
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+  #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
   pub struct Ponto {
     /// posição x
     pub x: i32,
@@ -31,7 +34,8 @@ pub static Â360:f32 = 2.0 * pi;
 
   /// Vetor no plano cartesiano 
   /// descrito por valores inteiros
-  #[derive(Debug, Clone, Copy)]
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+  #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
   pub struct Vetor {
     /// comprimento x
     pub x: i32,
@@ -42,10 +46,13 @@ pub static Â360:f32 = 2.0 * pi;
   /// Vetor com intensidade explícita
   /// e seu ângulo de inclinação
   #[derive(Clone, Copy)]
+  #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+  #[cfg_attr(feature = "serde", serde(try_from = "crate::serde_data::ModuloBruto"))]
   pub struct Modulo {
     /// intensidade 
     pub i: f64,
     /// ângulo do vetor
+    #[cfg_attr(feature = "serde", serde(rename = "angulo"))]
     pub â: f32,
   }
 
@@ -66,10 +73,10 @@ pub static Â360:f32 = 2.0 * pi;
   }
 
 
-  /// Fórmula de pitágoras
+  /// Fórmula de pitágoras, os quadrados são feitos em reais
+  /// para que lados acima de 46340 não estourem
   pub fn pitagoras( b : i32, c : i32) -> f64 {
-    let aux1 = (b*b + c*c) as f64;
-    return aux1.sqrt();
+    return fpitagoras(b as f64, c as f64);
   }
 
 
@@ -148,8 +155,8 @@ pub static Â360:f32 = 2.0 * pi;
     
     /// Retorna a distância entre dois pontos
     pub fn diff( p1: Ponto, p2: Ponto) -> f64 {
-      let v = Vetor::from(p1, p2);
-      return v.module();
+      // a diferença entre pontos distantes não cabe em i32
+      return fpitagoras(p2.x as f64 - p1.x as f64, p2.y as f64 - p1.y as f64);
     }
   }
 
@@ -0,0 +1,407 @@
+/// Diagramas de Voronoi de pontos, com as células recortadas por
+/// um bloco, para territórios e o local mais próximo.
+///
+/// Cada célula é o bloco recortado pelas mediatrizes entre o
+/// sítio e seus vizinhos na triangulação de Delaunay, que são os
+/// únicos que podem limitar a célula. Sítios todos colineares
+/// usam os vizinhos ao longo da reta.
+///
+/// Os vértices das células são reais, em sentido anti-horário, e
+/// as contas são feitas em relação ao sítio para manter a
+/// precisão longe da origem. Sítios repetidos ficam com a célula
+/// na primeira ocorrência e os demais com células vazias.
+pub use super::vector_data::Ponto as Ponto;
+pub use super::object_data::{
+  Bloco as Bloco,
+  Poligono as Poligono,
+};
+use super::delaunay_data::Triangulacao;
+use super::kdtree_data::ArvoreKd;
+use super::vector_data::fpitagoras;
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Real;
+
+/// Folga relativa ao tamanho `L` do bloco visto do sítio. Com as
+/// contas em relação ao sítio, a distância de um vértice a uma
+/// mediatriz erra por poucos `f64::EPSILON * L` mesmo depois de
+/// vários recortes, e 1e-10 fica cerca de 10⁵ vezes acima disso.
+/// Como `L` não passa de 2³³ em `i32`, a folga fica abaixo de uma
+/// unidade da grade dos sítios
+const FOLGA: f64 = 1e-10;
+
+
+// Estruturas
+/// Diagrama de Voronoi recortado por um bloco
+#[derive(Debug, Clone)]
+pub struct Voronoi {
+  /// Bloco que recorta as células
+  limite: Bloco,
+
+  /// Vértices de cada célula, na ordem dos sítios
+  celulas: Vec<Vec<(f64, f64)>>,
+
+  /// Sítios que dividem uma aresta com cada célula
+  vizinhos: Vec<Vec<usize>>,
+
+  /// Árvore dos sítios para localizar pontos
+  arvore: ArvoreKd
+}
+
+
+// Métodos relacionados ao diagrama
+impl Voronoi {
+
+
+  /// Gera o diagrama dos sítios dentro do bloco
+  pub fn new( sitios: &[Ponto], limite: Bloco) -> Self {
+    let n = sitios.len();
+
+    // primeira ocorrência de cada sítio
+    let mut primeiro = BTreeMap::new();
+    let unico: Vec<bool> = (0..n)
+      .map(|i| *primeiro.entry((sitios[i].x, sitios[i].y)).or_insert(i) == i)
+      .collect();
+
+    // candidatos a vizinhos de cada sítio
+    let mut candidatos = vec![Vec::new(); n];
+    let triangulacao = Triangulacao::new(sitios);
+    let mut pares: Vec<(usize, usize)> = triangulacao.arestas().collect();
+    if triangulacao.is_empty() {
+      let mut ordem: Vec<usize> = (0..n).filter(|&i| unico[i]).collect();
+      ordem.sort_by_key(|&i| (sitios[i].x, sitios[i].y));
+      pares.extend(ordem.windows(2).map(|par| (par[0], par[1])));
+    }
+    for (a, b) in pares {
+      candidatos[a].push(b);
+      candidatos[b].push(a);
+    }
+
+    let mut celulas = Vec::with_capacity(n);
+    let mut vizinhos = Vec::with_capacity(n);
+    for i in 0..n {
+      if !unico[i] {
+        celulas.push(Vec::new());
+        vizinhos.push(Vec::new());
+        continue;
+      }
+
+      let celula = recorta(sitios[i], &candidatos[i], sitios, limite);
+      let mut lados: Vec<usize> = celula.iter().filter_map(|&(_, j)| j).collect();
+      lados.sort_unstable();
+      lados.dedup();
+
+      celulas.push(celula.into_iter().map(|(v, _)| v).collect());
+      vizinhos.push(lados);
+    }
+
+    return Self {
+      limite,
+      celulas,
+      vizinhos,
+      arvore: ArvoreKd::new(sitios)
+    };
+  }
+
+
+  /// Quantidade de sítios
+  pub fn len( &self ) -> usize {
+    self.celulas.len()
+  }
+
+
+  /// Verifica se o diagrama não possui sítios
+  pub fn is_empty( &self ) -> bool {
+    self.celulas.is_empty()
+  }
+
+
+  /// Bloco que recorta as células
+  pub fn limite( &self ) -> Bloco {
+    self.limite
+  }
+
+
+  /// Vértices da célula do sítio `i` em sentido anti-horário,
+  /// vazia quando a célula não alcança o bloco
+  pub fn celula( &self, i: usize) -> &[(f64, f64)] {
+    &self.celulas[i]
+  }
+
+
+  /// Células de todos os sítios
+  pub fn celulas( &self ) -> &[Vec<(f64, f64)>] {
+    &self.celulas
+  }
+
+
+  /// Célula do sítio `i` com os vértices arredondados
+  pub fn poligono( &self, i: usize) -> Poligono {
+    Poligono::new(
+      self.celulas[i]
+        .iter()
+        .map(|&(x, y)| Ponto::new(x.round() as i32, y.round() as i32))
+        .collect()
+    )
+  }
+
+
+  /// Área da célula do sítio `i`, o território dentro do bloco
+  pub fn area( &self, i: usize) -> f64 {
+    let c = &self.celulas[i];
+    let mut dobro = 0.0;
+    for (k, a) in c.iter().enumerate() {
+      let b = c[(k + 1) % c.len()];
+      dobro += a.0 * b.1 - b.0 * a.1;
+    }
+    return dobro / 2.0;
+  }
+
+
+  /// Sítios cujas células dividem uma aresta com a do sítio `i`,
+  /// em ordem crescente
+  pub fn vizinhos( &self, i: usize) -> &[usize] {
+    &self.vizinhos[i]
+  }
+
+
+  /// Sítio de cuja célula o ponto faz parte, o mais próximo dele,
+  /// com empates decididos pelo menor índice
+  pub fn localiza( &self, p: Ponto) -> Option<usize> {
+    self.arvore.mais_proximo(p)
+  }
+}
+
+
+// Funções
+/// Recorta o bloco pelas mediatrizes entre o sítio e cada
+/// candidato. Cada vértice leva o sítio da aresta que começa
+/// nele, `None` nas bordas do bloco
+fn recorta( centro: Ponto, candidatos: &[usize], sitios: &[Ponto], limite: Bloco) -> Vec<((f64, f64), Option<usize>)> {
+  let (cx, cy) = (centro.x as f64, centro.y as f64);
+  let (x1, y1) = (limite.p1.x as f64 - cx, limite.p1.y as f64 - cy);
+  let (x2, y2) = (limite.p2.x as f64 - cx, limite.p2.y as f64 - cy);
+  let mut celula = vec![((x1, y1), None), ((x2, y1), None), ((x2, y2), None), ((x1, y2), None)];
+
+  // distâncias abaixo da folga são erro de arredondamento, nos
+  // vértices em que mais de três células se encontram
+  let folga = FOLGA * x1.abs().max(y1.abs()).max(x2.abs()).max(y2.abs()).max(1.0);
+
+  for &j in candidatos {
+    // o lado do sítio da mediatriz é d·v <= |d|²/2
+    let d = (
+      sitios[j].x as f64 - cx,
+      sitios[j].y as f64 - cy
+    );
+    let norma = fpitagoras(d.0, d.1);
+    let limiar = (d.0 * d.0 + d.1 * d.1) / 2.0;
+    let excesso = |v: (f64, f64)| d.0 * v.0 + d.1 * v.1 - limiar;
+    let lado_de = |e: f64| {
+      if e > folga * norma {
+        1
+      } else if e < -folga * norma {
+        -1
+      } else {
+        0
+      }
+    };
+
+    let mut recortada = Vec::with_capacity(celula.len() + 1);
+    for (k, &(a, lado)) in celula.iter().enumerate() {
+      let (b, _) = celula[(k + 1) % celula.len()];
+      let (ea, eb) = (excesso(a), excesso(b));
+      let (sa, sb) = (lado_de(ea), lado_de(eb));
+
+      if sa <= 0 {
+        // um vértice sobre a mediatriz já é o ponto de saída
+        if sb > 0 && sa == 0 {
+          recortada.push((a, Some(j)));
+        } else {
+          recortada.push((a, lado));
+        }
+        if sb > 0 && sa < 0 {
+          recortada.push((intersecao(a, b, ea, eb), Some(j)));
+        }
+      } else if sb < 0 {
+        recortada.push((intersecao(a, b, ea, eb), lado));
+      }
+    }
+
+    // em vértices repetidos a aresta começa no último deles
+    let perto = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).abs() <= folga && (a.1 - b.1).abs() <= folga;
+    recortada.dedup_by(|a, b| {
+      let repetido = perto(a.0, b.0);
+      if repetido {
+        b.1 = a.1;
+      }
+      repetido
+    });
+    if recortada.len() > 1 && perto(recortada[0].0, recortada[recortada.len() - 1].0) {
+      recortada.pop();
+    }
+
+    celula = recortada;
+    if celula.len() < 3 {
+      return Vec::new();
+    }
+  }
+
+  return celula.into_iter().map(|((x, y), j)| ((x + cx, y + cy), j)).collect();
+}
+
+
+/// Ponto da aresta `ab` em que o excesso se anula
+fn intersecao( a: (f64, f64), b: (f64, f64), ea: f64, eb: f64) -> (f64, f64) {
+  let t = ea / (ea - eb);
+  (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+
+#[cfg(test)]
+mod testes {
+  use super::*;
+  use crate::random_data::{entre, sorteio};
+
+
+  /// Sítio mais próximo pela força bruta, o de menor índice nos
+  /// empates
+  fn mais_proximo( sitios: &[Ponto], p: Ponto) -> usize {
+    let distancia = |s: Ponto| {
+      let (dx, dy) = (s.x as i128 - p.x as i128, s.y as i128 - p.y as i128);
+      dx * dx + dy * dy
+    };
+    (0..sitios.len()).min_by_key(|&i| (distancia(sitios[i]), i)).unwrap()
+  }
+
+
+  /// Verifica se o ponto está na célula convexa, com uma
+  /// tolerância relativa ao tamanho do bloco
+  fn na_celula( celula: &[(f64, f64)], p: Ponto, escala: f64) -> bool {
+    let (x, y) = (p.x as f64, p.y as f64);
+    (0..celula.len()).all(|k| {
+      let (a, b) = (celula[k], celula[(k + 1) % celula.len()]);
+      let (ex, ey) = (b.0 - a.0, b.1 - a.1);
+      let lado = ex * (y - a.1) - ey * (x - a.0);
+      lado >= -1e-9 * escala * fpitagoras(ex, ey)
+    })
+  }
+
+
+  /// Confere o diagrama: células convexas em sentido anti-horário
+  /// dentro do bloco, áreas somando a do bloco, vizinhos
+  /// recíprocos, repetidos vazios e `localiza` igual à força bruta
+  fn confere( sitios: &[Ponto], limite: Bloco, estado: &mut u64) {
+    let v = Voronoi::new(sitios, limite);
+    assert_eq!(v.len(), sitios.len());
+
+    let (x1, y1, x2, y2) = (limite.p1.x, limite.p1.y, limite.p2.x, limite.p2.y);
+    let largura = x2 as f64 - x1 as f64;
+    let altura = y2 as f64 - y1 as f64;
+    let escala = largura.max(altura).max(1.0);
+
+    let mut soma = 0.0;
+    for i in 0..sitios.len() {
+      let celula = v.celula(i);
+      let repetido = sitios[..i].contains(&sitios[i]);
+      if repetido {
+        assert!(celula.is_empty() && v.vizinhos(i).is_empty(), "sítio {}", i);
+      }
+
+      for &(x, y) in celula {
+        let folga = 1e-9 * escala;
+        assert!(x >= x1 as f64 - folga && x <= x2 as f64 + folga, "sítio {} em {}", i, x);
+        assert!(y >= y1 as f64 - folga && y <= y2 as f64 + folga, "sítio {} em {}", i, y);
+      }
+      for k in 0..celula.len() {
+        let (a, b, c) = (celula[k], celula[(k + 1) % celula.len()], celula[(k + 2) % celula.len()]);
+        let curva = (b.0 - a.0) * (c.1 - b.1) - (b.1 - a.1) * (c.0 - b.0);
+        assert!(curva >= -1e-9 * escala * escala, "sítio {} não é convexo", i);
+      }
+
+      assert!(v.area(i) >= 0.0);
+      soma += v.area(i);
+
+      for &j in v.vizinhos(i) {
+        assert!(v.vizinhos(j).contains(&i), "vizinhos {} e {}", i, j);
+      }
+    }
+    let area = largura * altura;
+    assert!((soma - area).abs() <= 1e-9 * area.max(1.0), "soma {} e bloco {}", soma, area);
+
+    // pontos sorteados no bloco e os sítios, que podem estar fora
+    // dele e então não estão em nenhuma célula
+    let consultas = (0..200)
+      .map(|_| Ponto::new(entre(estado, x1, x2), entre(estado, y1, y2)))
+      .chain(sitios.iter().copied());
+    for p in consultas {
+      let esperado = mais_proximo(sitios, p);
+      assert_eq!(v.localiza(p), Some(esperado), "ponto {:?}", p);
+      let dentro = p.x >= x1 && p.x <= x2 && p.y >= y1 && p.y <= y2;
+      if dentro {
+        assert!(na_celula(v.celula(esperado), p, escala), "ponto {:?} fora da célula {}", p, esperado);
+      }
+    }
+  }
+
+
+  #[test]
+  fn celulas_cobrem_o_bloco() {
+    let mut estado = 0x5eed_0050;
+    for rodada in 0..150 {
+      // grades pequenas com repetidos e cocirculares, e sítios
+      // espalhados dentro e fora do bloco
+      let (lado, n) = match rodada % 3 {
+        0 => (4, 1 + (sorteio(&mut estado) % 30) as usize),
+        1 => (1000, 1 + (sorteio(&mut estado) % 80) as usize),
+        _ => (i32::MAX, 1 + (sorteio(&mut estado) % 80) as usize),
+      };
+      let sitios: Vec<Ponto> = (0..n)
+        .map(|_| Ponto::new(entre(&mut estado, -lado, lado), entre(&mut estado, -lado, lado)))
+        .collect();
+
+      let (a, b) = (entre(&mut estado, -lado, lado), entre(&mut estado, -lado, lado));
+      let (c, d) = (entre(&mut estado, -lado, lado), entre(&mut estado, -lado, lado));
+      let limite = Bloco::new((a.min(b), c.min(d)), (a.max(b), c.max(d)));
+      confere(&sitios, limite, &mut estado);
+    }
+  }
+
+
+  #[test]
+  fn sitios_repetidos_e_colineares() {
+    let mut estado = 0x5eed_1050;
+    let limite = Bloco::new((-50, -50), (50, 50));
+
+    // todos no mesmo ponto
+    let sitios = vec![Ponto::new(3, 4); 5];
+    confere(&sitios, limite, &mut estado);
+    let v = Voronoi::new(&sitios, limite);
+    assert!((v.area(0) - 10000.0).abs() < 1e-6);
+
+    // colineares, fora de ordem e repetidos, a célula de cada um
+    // é uma faixa entre os vizinhos ao longo da reta
+    let sitios: Vec<Ponto> = [5, -3, 0, 9, -3, 2, 5, -8]
+      .iter()
+      .map(|&t| Ponto::new(2 * t, -t))
+      .collect();
+    confere(&sitios, limite, &mut estado);
+    let v = Voronoi::new(&sitios, limite);
+    assert_eq!(v.vizinhos(2), &[1, 5]);
+    assert_eq!(v.vizinhos(7), &[1]);
+    assert!(v.vizinhos(4).is_empty() && v.vizinhos(6).is_empty());
+
+    // horizontais e verticais, com as mediatrizes nas bordas
+    for sitios in [
+      (0..10).map(|i| Ponto::new(10 * i - 50, 7)).collect::<Vec<_>>(),
+      (0..10).map(|i| Ponto::new(-1, 10 * i - 45)).collect::<Vec<_>>(),
+    ] {
+      confere(&sitios, limite, &mut estado);
+    }
+
+    // nenhum sítio
+    let v = Voronoi::new(&[], limite);
+    assert!(v.is_empty() && v.localiza(Ponto::new(0, 0)).is_none());
+  }
+}